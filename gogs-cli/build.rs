@@ -0,0 +1,40 @@
+use std::env;
+use std::process::Command;
+
+/// Record build metadata for `gog version --verbose`.
+fn main() {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+    };
+
+    let sha = git(&["rev-parse", "--short=12", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GOG_GIT_SHA={}", sha);
+    // Rebuild when HEAD moves, not on every build
+    if let Some(dir) = git(&["rev-parse", "--git-dir"]) {
+        println!("cargo:rerun-if-changed={}/HEAD", dir);
+        println!("cargo:rerun-if-changed={}/index", dir);
+    }
+
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase().replace('_', "-")))
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=GOG_FEATURES={}", features.join(","));
+
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=GOG_RUSTC_VERSION={}", rustc_version);
+    println!("cargo:rustc-env=GOG_TARGET={}", env::var("TARGET").unwrap_or_default());
+    println!("cargo:rustc-env=GOG_PROFILE={}", env::var("PROFILE").unwrap_or_default());
+    println!("cargo:rerun-if-changed=build.rs");
+}
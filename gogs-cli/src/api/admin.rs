@@ -0,0 +1,52 @@
+use super::client::GogsClient;
+use super::types::{CreateOrgParams, CreateRepoParams, CreateUserParams, Organization, Repository, User};
+use crate::error::GogsError;
+use anyhow::Result;
+
+impl GogsClient {
+    /// Create an account. Needs a site administrator's token.
+    pub async fn admin_create_user(&self, params: &CreateUserParams) -> Result<User> {
+        let payload = serde_json::to_value(params)?;
+        let resp = self.post("/admin/users", payload).await.map_err(admin_only)?;
+        let user: User = resp.json().await?;
+        Ok(user)
+    }
+
+    /// Delete an account. Gogs refuses while it still owns repositories or
+    /// organizations.
+    pub async fn admin_delete_user(&self, username: &str) -> Result<()> {
+        let path = format!("/admin/users/{}", username);
+        self.delete(&path).await.map_err(admin_only)?;
+        Ok(())
+    }
+
+    /// Create an organization owned by `owner`.
+    pub async fn admin_create_org(&self, owner: &str, params: &CreateOrgParams) -> Result<Organization> {
+        let path = format!("/admin/users/{}/orgs", owner);
+        let payload = serde_json::to_value(params)?;
+        let resp = self.post(&path, payload).await.map_err(admin_only)?;
+        let org: Organization = resp.json().await?;
+        Ok(org)
+    }
+
+    /// Create a repository for any user or organization.
+    pub async fn admin_create_repo(&self, owner: &str, params: &CreateRepoParams) -> Result<Repository> {
+        let path = format!("/admin/users/{}/repos", owner);
+        let payload = serde_json::to_value(params)?;
+        let resp = self.post(&path, payload).await.map_err(admin_only)?;
+        let repository: Repository = resp.json().await?;
+        Ok(repository)
+    }
+}
+
+/// Gogs answers every admin route with a bare 403 when the token's user
+/// isn't a site administrator, so say that is what's missing.
+fn admin_only(e: anyhow::Error) -> anyhow::Error {
+    match e.downcast_ref() {
+        Some(GogsError::Forbidden) => anyhow::anyhow!(
+            "Access denied: this token is not a site administrator's. `gog admin` commands \
+            need the token of an admin account; check which account the profile uses with `gog whoami`"
+        ),
+        _ => e,
+    }
+}
@@ -0,0 +1,21 @@
+use super::client::GogsClient;
+use super::types::Branch;
+use anyhow::Result;
+
+impl GogsClient {
+    pub async fn list_branches(&self, owner: &str, repo: &str) -> Result<Vec<Branch>> {
+        let path = format!("/repos/{}/{}/branches", owner, repo);
+        let resp = self.get(&path).await?;
+        let branches: Vec<Branch> = resp.json().await?;
+        Ok(branches)
+    }
+
+    pub async fn get_branch(&self, owner: &str, repo: &str, branch: &str) -> Result<Branch> {
+        // Branch names like "agent/fix" keep their slashes; the route takes the rest of the path
+        let name: Vec<String> = branch.split('/').map(Self::encode_query).collect();
+        let path = format!("/repos/{}/{}/branches/{}", owner, repo, name.join("/"));
+        let resp = self.get(&path).await?;
+        let branch: Branch = resp.json().await?;
+        Ok(branch)
+    }
+}
@@ -0,0 +1,217 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use super::types::Label;
+use crate::storage::{self, Storage};
+
+/// Repository label listings, kept for the life of the process and optionally
+/// in local storage so repeated invocations can skip `GET /labels`.
+#[derive(Default)]
+pub struct LabelCache {
+    memory: Mutex<HashMap<String, Vec<Label>>>,
+    disk: Option<DiskCache>,
+}
+
+struct DiskCache {
+    storage: Arc<dyn Storage>,
+    ttl: Duration,
+}
+
+impl LabelCache {
+    /// Also persist listings in `storage`, treating ones older than `ttl` as stale.
+    pub fn with_storage(storage: Arc<dyn Storage>, ttl: Duration) -> Self {
+        Self {
+            memory: Mutex::default(),
+            disk: Some(DiskCache { storage, ttl }),
+        }
+    }
+
+    pub fn get(&self, owner: &str, repo: &str) -> Option<Vec<Label>> {
+        let key = cache_key(owner, repo);
+        if let Some(labels) = self.memory.lock().unwrap().get(&key) {
+            return Some(labels.clone());
+        }
+
+        let labels = self.disk.as_ref()?.read(&key)?;
+        self.memory.lock().unwrap().insert(key, labels.clone());
+        Some(labels)
+    }
+
+    pub fn put(&self, owner: &str, repo: &str, labels: &[Label]) {
+        let key = cache_key(owner, repo);
+        if let Some(disk) = &self.disk {
+            // A cache that can't be written is only a missed optimisation
+            let _ = disk.write(&key, labels);
+        }
+        self.memory.lock().unwrap().insert(key, labels.to_vec());
+    }
+
+    /// Forget a repository's labels, e.g. after creating or deleting one.
+    pub fn invalidate(&self, owner: &str, repo: &str) {
+        let key = cache_key(owner, repo);
+        self.memory.lock().unwrap().remove(&key);
+        if let Some(disk) = &self.disk {
+            let _ = disk.storage.remove(&DiskCache::key(&key));
+        }
+    }
+}
+
+impl DiskCache {
+    fn key(key: &str) -> String {
+        format!("labels/{}.json", key)
+    }
+
+    fn read(&self, key: &str) -> Option<Vec<Label>> {
+        let stored = self.storage.read(&Self::key(key))?;
+        let age = SystemTime::now().duration_since(stored.modified).unwrap_or_default();
+        if age > self.ttl {
+            return None;
+        }
+
+        serde_json::from_slice(&stored.data).ok()
+    }
+
+    fn write(&self, key: &str, labels: &[Label]) -> std::io::Result<()> {
+        let contents = serde_json::to_vec(labels)?;
+        self.storage.write(&Self::key(key), &contents)
+    }
+}
+
+fn cache_key(owner: &str, repo: &str) -> String {
+    format!("{}__{}", owner, repo)
+}
+
+/// Successful GET responses, kept in local storage. When the server marked
+/// one with an `ETag` or `Last-Modified`, the next request for the same URL
+/// asks whether it changed, and a 304 is answered with the stored body.
+/// Stored responses also stand in for the server when working offline.
+pub struct ResponseCache {
+    storage: Arc<dyn Storage>,
+    /// When the oldest response served in place of the server was fetched
+    stale_since: Mutex<Option<DateTime<Utc>>>,
+}
+
+/// A stored response and what to revalidate it with.
+#[derive(Deserialize, Serialize)]
+pub struct CachedResponse {
+    pub url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    headers: Vec<(String, String)>,
+    body: String,
+    /// When the server last sent or confirmed this body
+    pub fetched: DateTime<Utc>,
+}
+
+impl ResponseCache {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self {
+            storage,
+            stale_since: Mutex::default(),
+        }
+    }
+
+    /// The stored response for `url` as fetched with `token`, if any.
+    pub fn get(&self, url: &str, token: &str) -> Option<CachedResponse> {
+        storage::load_json::<Option<CachedResponse>>(self.storage.as_ref(), &Self::key(url, token))
+    }
+
+    /// Answer a 304 from `cached`, and store a fresh response. Anything else
+    /// is passed through untouched.
+    pub async fn update(
+        &self,
+        url: &str,
+        token: &str,
+        resp: Response,
+        cached: Option<CachedResponse>,
+    ) -> Result<Response> {
+        let key = Self::key(url, token);
+        if resp.status() == StatusCode::NOT_MODIFIED {
+            let mut cached = cached.context("Server answered 304 Not Modified to a request gog never cached")?;
+            cached.fetched = Utc::now();
+            let _ = storage::save_json(self.storage.as_ref(), &key, &cached);
+            return cached.into_response();
+        }
+        if resp.status() != StatusCode::OK {
+            return Ok(resp);
+        }
+
+        let validator = |name| resp.headers().get(name).and_then(|v| v.to_str().ok()).map(String::from);
+        let (etag, last_modified) = (validator(ETAG), validator(LAST_MODIFIED));
+
+        let headers = resp
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = resp.text().await.with_context(|| format!("Failed to read {}", url))?;
+        let fresh = CachedResponse {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            headers,
+            body,
+            fetched: Utc::now(),
+        };
+        // A cache that can't be written is only a missed optimisation
+        let _ = storage::save_json(self.storage.as_ref(), &key, &fresh);
+        fresh.into_response()
+    }
+
+    /// Serve `cached` in place of the server's answer, noting its age for
+    /// `stale_since`. True alongside it the first time this happens.
+    pub fn serve_stale(&self, cached: CachedResponse) -> Result<(Response, bool)> {
+        let mut stale_since = self.stale_since.lock().unwrap();
+        let first = stale_since.is_none();
+        if stale_since.is_none_or(|since| cached.fetched < since) {
+            *stale_since = Some(cached.fetched);
+        }
+        drop(stale_since);
+        Ok((cached.into_response()?, first))
+    }
+
+    /// When the oldest response served from here instead of the server was
+    /// fetched, or None if the server answered everything.
+    pub fn stale_since(&self) -> Option<DateTime<Utc>> {
+        *self.stale_since.lock().unwrap()
+    }
+
+    /// Responses are kept per token as well as URL, so one account never
+    /// sees what the server showed another.
+    fn key(url: &str, token: &str) -> String {
+        let digest = Sha256::digest(format!("{}\n{}", token, url));
+        format!("responses/{:x}.json", digest)
+    }
+}
+
+impl CachedResponse {
+    /// Ask the server to send the body only if it changed since this copy.
+    pub fn conditional(&self, mut req: RequestBuilder) -> RequestBuilder {
+        if let Some(etag) = &self.etag {
+            req = req.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            req = req.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        req
+    }
+
+    /// The stored response, as if the server had just sent it.
+    pub fn into_response(self) -> Result<Response> {
+        let mut builder = http::Response::builder().status(StatusCode::OK);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let resp = builder
+            .body(self.body)
+            .with_context(|| format!("Damaged cache entry for {}", self.url))?;
+        Ok(Response::from(resp))
+    }
+}
@@ -1,19 +1,58 @@
 use anyhow::{Context, Result};
-use reqwest::{Client, Method, Response, StatusCode};
+use chrono::{DateTime, Utc};
+use reqwest::multipart::Form;
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+use super::cache::{LabelCache, ResponseCache};
+#[cfg(unix)]
+use super::daemon::DaemonLink;
+use super::flood::CommentGuard;
+use super::mirror::{Mirror, MirrorConfig};
+use super::quarantine::Quarantine;
+use super::ratelimit::RateLimiter;
+use super::retry::{self, RetryMode};
+use crate::error::GogsError;
+
+/// Upper bound on pages fetched by `get_paginated` unless configured otherwise.
+pub const DEFAULT_MAX_PAGES: usize = 100;
+
+/// Path under the server URL where the API lives unless configured otherwise.
+pub const DEFAULT_API_PREFIX: &str = "/api/v1";
 
 #[derive(Clone)]
 pub struct GogsClient {
     base_url: String,
+    api_prefix: String,
     token: String,
     client: Client,
+    timeout: Duration,
+    max_pages: usize,
+    retry_attempts: usize,
+    retry_mode: RetryMode,
+    retry_base_delay: Duration,
+    retry_max_delay: Duration,
+    labels: Arc<LabelCache>,
+    responses: Option<Arc<ResponseCache>>,
+    offline: bool,
+    mirror: Option<Arc<Mirror>>,
+    comment_guard: Option<Arc<CommentGuard>>,
+    quarantine: Option<Arc<Quarantine>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    #[cfg(unix)]
+    daemon: Option<Arc<DaemonLink>>,
 }
 
 impl GogsClient {
     pub fn new(base_url: String, token: String) -> Self {
+        let timeout = Duration::from_secs(30);
         let client = Client::builder()
-            .timeout(Duration::from_secs(30))
+            .timeout(timeout)
             .build()
             .expect("Failed to create HTTP client");
 
@@ -22,22 +61,179 @@ impl GogsClient {
 
         Self {
             base_url,
+            api_prefix: DEFAULT_API_PREFIX.to_string(),
             token,
             client,
+            timeout,
+            max_pages: DEFAULT_MAX_PAGES,
+            retry_attempts: retry::DEFAULT_ATTEMPTS,
+            retry_mode: RetryMode::default(),
+            retry_base_delay: retry::DEFAULT_BASE_DELAY,
+            retry_max_delay: retry::DEFAULT_MAX_DELAY,
+            labels: Arc::default(),
+            responses: None,
+            offline: false,
+            mirror: None,
+            comment_guard: None,
+            quarantine: None,
+            rate_limiter: None,
+            #[cfg(unix)]
+            daemon: None,
         }
     }
 
+    /// Allow each request this long instead of the default 30 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("Failed to create HTTP client");
+        self.timeout = timeout;
+        self
+    }
+
+    /// Replace the process-local label cache, e.g. with one backed by disk.
+    pub fn with_label_cache(mut self, cache: LabelCache) -> Self {
+        self.labels = Arc::new(cache);
+        self
+    }
+
+    pub fn label_cache(&self) -> &LabelCache {
+        &self.labels
+    }
+
+    /// Revalidate GET responses kept in `cache` instead of fetching them again.
+    pub fn with_response_cache(mut self, cache: ResponseCache) -> Self {
+        self.responses = Some(Arc::new(cache));
+        self
+    }
+
+    /// Fetch every response in full, neither revalidating nor storing it.
+    pub fn without_response_cache(mut self) -> Self {
+        self.responses = None;
+        self
+    }
+
+    /// Answer GETs from the response cache only, never asking the server, and
+    /// refuse anything else.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// When the oldest response shown from the cache instead of the server
+    /// was fetched, or None if everything came from the server.
+    pub fn stale_since(&self) -> Option<DateTime<Utc>> {
+        self.responses.as_ref()?.stale_since()
+    }
+
+    /// Serve the API from a different path, e.g. behind a proxy.
+    pub fn with_api_prefix(mut self, prefix: &str) -> Self {
+        self.api_prefix = format!("/{}", prefix.trim_matches('/'));
+        self
+    }
+
+    /// Limit how many pages `get_paginated` will follow.
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    /// Try retryable requests up to `attempts` times in total (1 disables retries).
+    pub fn with_retry_attempts(mut self, attempts: usize) -> Self {
+        self.retry_attempts = attempts.max(1);
+        self
+    }
+
+    /// Wait `base` before the first retry, doubling up to `max` between later
+    /// ones. `max` also caps how long a server's `Retry-After` is honoured.
+    pub fn with_retry_delays(mut self, base: Duration, max: Duration) -> Self {
+        self.retry_base_delay = base;
+        self.retry_max_delay = max.max(base);
+        self
+    }
+
+    /// Choose which requests are retried, e.g. per command.
+    pub fn with_retry_mode(mut self, mode: RetryMode) -> Self {
+        self.retry_mode = mode;
+        self
+    }
+
+    /// Send at most `rate` requests per second, across this client and its
+    /// clones. A rate that isn't positive means no limit.
+    pub fn with_rate_limit(mut self, rate: f64) -> Self {
+        self.rate_limiter = RateLimiter::new(rate).map(Arc::new);
+        self
+    }
+
+    /// Repeat issue tracker changes on a second server.
+    pub fn with_mirror(mut self, config: &MirrorConfig) -> Self {
+        self.mirror = Some(Arc::new(Mirror::new(config)));
+        self
+    }
+
+    /// Refuse comments over the guard's limit.
+    pub fn with_comment_guard(mut self, guard: CommentGuard) -> Self {
+        self.comment_guard = Some(Arc::new(guard));
+        self
+    }
+
+    /// Send requests through `gog daemon` when it's running.
+    pub fn with_daemon(mut self) -> Self {
+        #[cfg(unix)]
+        {
+            self.daemon = DaemonLink::detect().map(Arc::new);
+        }
+        self
+    }
+
+    /// Talk to the server directly even when the daemon is running.
+    pub fn without_daemon(mut self) -> Self {
+        #[cfg(unix)]
+        {
+            self.daemon = None;
+        }
+        self
+    }
+
+    pub(super) fn comment_guard(&self) -> Option<&CommentGuard> {
+        self.comment_guard.as_deref()
+    }
+
+    /// Skip repositories that keep failing in fan-out commands.
+    pub fn with_quarantine(mut self, quarantine: Quarantine) -> Self {
+        self.quarantine = Some(Arc::new(quarantine));
+        self
+    }
+
+    pub fn quarantine(&self) -> Option<&Quarantine> {
+        self.quarantine.as_deref()
+    }
+
     pub async fn request(
         &self,
         method: Method,
         path: &str,
         body: Option<Value>,
     ) -> Result<Response> {
-        let url = format!("{}/api/v1{}", self.base_url, path);
+        match &self.mirror {
+            Some(mirror) if method != Method::GET => mirror.request(self, method, path, body).await,
+            _ => self.request_direct(method, path, body).await,
+        }
+    }
+
+    /// Send a request to this server only, bypassing any mirror.
+    pub(super) async fn request_direct(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Response> {
+        let url = format!("{}{}{}", self.base_url, self.api_prefix, path);
 
         let mut req = self
             .client
-            .request(method, &url)
+            .request(method.clone(), &url)
             .header("Authorization", format!("token {}", self.token))
             .header("Content-Type", "application/json");
 
@@ -45,18 +241,237 @@ impl GogsClient {
             req = req.json(&body);
         }
 
+        if self.offline {
+            if self.responses.is_none() {
+                anyhow::bail!("Working offline needs cached responses: set responses = true under [cache]");
+            }
+            if method != Method::GET {
+                anyhow::bail!("Working offline: can't {} {}", method, path);
+            }
+        }
+        let Some(cache) = self.responses.as_ref().filter(|_| method == Method::GET) else {
+            return self.send(&method, path, req).await;
+        };
+        let cached = cache.get(&url, &self.token);
+        if self.offline {
+            let cached = cached.with_context(|| format!("Working offline: no cached response for {}", path))?;
+            return Ok(cache.serve_stale(cached)?.0);
+        }
+
+        if let Some(cached) = &cached {
+            req = cached.conditional(req);
+        }
+        match (self.send(&method, path, req).await, cached) {
+            (Ok(resp), cached) => cache.update(&url, &self.token, resp, cached).await,
+            // Show the last known answer rather than nothing
+            (Err(e), Some(cached)) if is_unreachable(&e) => {
+                let (resp, first) = cache.serve_stale(cached)?;
+                if first {
+                    eprintln!("Warning: Can't reach {}; showing cached data", self.base_url);
+                }
+                Ok(resp)
+            }
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    /// Send a request signed in with a username and password instead of the
+    /// token, as the endpoints that manage tokens require. It goes straight to
+    /// this server: never mirrored, relayed through the daemon or retried.
+    pub(super) async fn request_with_password(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+        username: &str,
+        password: &str,
+    ) -> Result<Response> {
+        let mut req = self
+            .client
+            .request(method, self.api_url(path))
+            .basic_auth(username, Some(password));
+        if let Some(body) = body {
+            req = req.json(&body);
+        }
+
         let resp = req.send().await.context("Failed to send request")?;
+        if resp.status() == StatusCode::UNAUTHORIZED {
+            anyhow::bail!(
+                "Wrong username or password for {}. Accounts with two-factor authentication \
+                 need a token made in the web UI (Settings > Applications)",
+                username
+            );
+        }
+        Self::check(Ok(resp)).await
+    }
+
+    /// POST a multipart form, e.g. a file upload.
+    pub async fn post_multipart(&self, path: &str, form: Form) -> Result<Response> {
+        if let Some(mirror) = &self.mirror {
+            mirror.skip_upload(path);
+        }
+        let url = format!("{}{}{}", self.base_url, self.api_prefix, path);
+        let req = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("token {}", self.token))
+            .multipart(form);
+
+        self.send(&Method::POST, path, req).await
+    }
+
+    /// Download a file by absolute URL. The token is only sent to this server.
+    pub async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        // Binaries take longer than API calls
+        let mut req = self.client.get(url).timeout(Duration::from_secs(300));
+        if url.starts_with(&format!("{}/", self.base_url)) {
+            req = req.header("Authorization", format!("token {}", self.token));
+        }
+
+        let resp = self.send(&Method::GET, url, req).await?;
+        let bytes = resp
+            .bytes()
+            .await
+            .with_context(|| format!("Failed to download {}", url))?;
+        Ok(bytes.to_vec())
+    }
+
+    /// Download a file by absolute URL into `dest`, writing it as it arrives
+    /// rather than holding it in memory. Returns the bytes written. The file
+    /// is written under a ".part" name and renamed once complete, so a failed
+    /// download never leaves a truncated `dest`.
+    ///
+    /// `progress` is called after each chunk with the bytes written so far and
+    /// the total, when the server says.
+    pub async fn download_to(&self, url: &str, dest: &Path, mut progress: impl FnMut(u64, Option<u64>)) -> Result<u64> {
+        let mut req = self.client.get(url).timeout(Duration::from_secs(300));
+        if url.starts_with(&format!("{}/", self.base_url)) {
+            req = req.header("Authorization", format!("token {}", self.token));
+        }
+        let mut resp = self.send_request(&Method::GET, url, req, false).await?;
+        let total = resp.content_length();
+
+        let mut partial = dest.as_os_str().to_owned();
+        partial.push(".part");
+        let partial = PathBuf::from(partial);
+        let written = async {
+            let mut file = tokio::fs::File::create(&partial)
+                .await
+                .with_context(|| format!("Failed to create {}", partial.display()))?;
+            let mut written = 0;
+            while let Some(chunk) = resp
+                .chunk()
+                .await
+                .with_context(|| format!("Failed to download {}", url))?
+            {
+                file.write_all(&chunk)
+                    .await
+                    .with_context(|| format!("Failed to write {}", partial.display()))?;
+                written += chunk.len() as u64;
+                progress(written, total);
+            }
+            file.flush().await?;
+            Ok::<_, anyhow::Error>(written)
+        }
+        .await;
+
+        match written {
+            Ok(written) => {
+                tokio::fs::rename(&partial, dest)
+                    .await
+                    .with_context(|| format!("Failed to write {}", dest.display()))?;
+                Ok(written)
+            }
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&partial).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Send `req`, retrying transient failures when `retry_mode` says the
+    /// request is safe to repeat. Waits as long as the server's `Retry-After`
+    /// asks, else backs off exponentially with jitter.
+    async fn send(&self, method: &Method, path: &str, req: RequestBuilder) -> Result<Response> {
+        self.send_request(method, path, req, true).await
+    }
+
+    /// `send`, through the daemon only if `relay` allows it. Responses that
+    /// are streamed to disk don't go through it, since it reads them whole.
+    async fn send_request(&self, method: &Method, path: &str, req: RequestBuilder, relay: bool) -> Result<Response> {
+        let mut attempt = 1;
+        let mut pending = req;
+        loop {
+            // Streaming bodies (uploads) can't be cloned, so they get one attempt
+            let spare = if attempt < self.retry_attempts {
+                pending.try_clone()
+            } else {
+                None
+            };
+            let outcome = self.execute(pending, relay).await;
+
+            let Some(next) = spare else {
+                return Self::check(outcome).await;
+            };
+            let retry = match &outcome {
+                // Never connected, so the server can't have acted on it
+                Err(e) if e.is_connect() => self.retry_mode != RetryMode::Never,
+                Err(e) if e.is_timeout() => self.retry_mode.allows(method, path),
+                Ok(resp) if retry::is_transient(resp.status()) => self.retry_mode.allows(method, path),
+                _ => false,
+            };
+            if !retry {
+                return Self::check(outcome).await;
+            }
+
+            let delay = match &outcome {
+                Ok(resp) => retry::retry_after(resp).map(|wait| wait.min(self.retry_max_delay)),
+                Err(_) => None,
+            }
+            .unwrap_or_else(|| retry::backoff(attempt, self.retry_base_delay, self.retry_max_delay));
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+            pending = next;
+        }
+    }
+
+    /// Send API requests through the daemon if there is one, `relay` allows it
+    /// and the daemon takes the request, else directly. Waits first for the
+    /// rate limit, if any, so retries count against it too. Downloads outside the
+    /// API always go direct, to be streamed.
+    async fn execute(&self, req: RequestBuilder, relay: bool) -> reqwest::Result<Response> {
+        let request = req.build()?;
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+        #[cfg(unix)]
+        if let Some(daemon) = self
+            .daemon
+            .as_ref()
+            .filter(|_| relay && request.url().as_str().starts_with(&self.api_url("/")))
+        {
+            if let Ok(resp) = daemon.relay(&request, self.timeout).await {
+                return Ok(resp);
+            }
+        }
+        self.client.execute(request).await
+    }
+
+    /// Turn a response into an error unless it succeeded. A 304 passes, as
+    /// only requests revalidating the response cache get one.
+    async fn check(outcome: reqwest::Result<Response>) -> Result<Response> {
+        let resp = outcome.context("Failed to send request")?;
 
         let status = resp.status();
-        if !status.is_success() {
+        if !status.is_success() && status != StatusCode::NOT_MODIFIED {
             let text = resp.text().await.unwrap_or_default();
 
             if status == StatusCode::UNAUTHORIZED {
-                anyhow::bail!("Authentication failed. Check your API token.");
+                return Err(GogsError::AuthError("Check your API token.".to_string()).into());
             } else if status == StatusCode::NOT_FOUND {
-                anyhow::bail!("Resource not found: {}", text);
+                return Err(GogsError::NotFound(text).into());
             } else if status == StatusCode::FORBIDDEN {
-                anyhow::bail!("Access denied. Check permissions for this resource.");
+                return Err(GogsError::Forbidden.into());
             } else {
                 anyhow::bail!("API error {}: {}", status, text);
             }
@@ -69,6 +484,49 @@ impl GogsClient {
         self.request(Method::GET, path, None).await
     }
 
+    /// GET a list endpoint, following `?page=N` until a page comes back empty,
+    /// shorter than the first, or identical to the previous one (servers that
+    /// ignore `page` return the full list every time). Stops early once `limit`
+    /// items have been collected.
+    pub async fn get_paginated<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<T>> {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let mut items: Vec<Value> = Vec::new();
+        let mut previous: Option<Vec<Value>> = None;
+        let mut page_size = None;
+
+        for page in 1..=self.max_pages {
+            let page_path = format!("{}{}page={}", path, separator, page);
+            let resp = self.get(&page_path).await?;
+            let batch: Vec<Value> = resp.json().await?;
+
+            if batch.is_empty() || previous.as_ref() == Some(&batch) {
+                break;
+            }
+
+            let len = batch.len();
+            let first_size = *page_size.get_or_insert(len);
+            items.extend(batch.iter().cloned());
+
+            if len < first_size || limit.is_some_and(|limit| items.len() >= limit) {
+                break;
+            }
+            previous = Some(batch);
+        }
+
+        if let Some(limit) = limit {
+            items.truncate(limit);
+        }
+
+        items
+            .into_iter()
+            .map(|item| serde_json::from_value(item).context("Failed to parse list item"))
+            .collect()
+    }
+
     pub async fn post(&self, path: &str, body: Value) -> Result<Response> {
         self.request(Method::POST, path, Some(body)).await
     }
@@ -77,12 +535,44 @@ impl GogsClient {
         self.request(Method::PATCH, path, Some(body)).await
     }
 
-    #[allow(dead_code)]
+    pub async fn put(&self, path: &str, body: Value) -> Result<Response> {
+        self.request(Method::PUT, path, Some(body)).await
+    }
+
     pub async fn delete(&self, path: &str) -> Result<Response> {
         self.request(Method::DELETE, path, None).await
     }
 
+    /// Percent-encode a value for use in a query string.
+    pub fn encode_query(value: &str) -> String {
+        url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+    }
+
+    /// Percent-encode each segment of a slash-separated path, e.g. a file path
+    /// or a branch name, keeping the slashes.
+    pub fn encode_path(path: &str) -> String {
+        // Query encoding writes spaces as '+', which a path would keep literally
+        let segments: Vec<String> = path
+            .trim_start_matches('/')
+            .split('/')
+            .map(|segment| Self::encode_query(segment).replace('+', "%20"))
+            .collect();
+        segments.join("/")
+    }
+
     pub fn base_url(&self) -> &str {
         &self.base_url
     }
+
+    /// Absolute URL of an API path, e.g. "/repos/owner/repo".
+    pub fn api_url(&self, path: &str) -> String {
+        format!("{}{}{}", self.base_url, self.api_prefix, path)
+    }
+}
+
+/// Whether `e` means the server couldn't be reached at all, as opposed to
+/// answering with an error.
+fn is_unreachable(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<reqwest::Error>()
+        .is_some_and(|e| e.is_connect() || e.is_timeout())
 }
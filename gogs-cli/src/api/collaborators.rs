@@ -0,0 +1,31 @@
+use super::client::GogsClient;
+use super::types::{Collaborator, CollaboratorPermission};
+use anyhow::Result;
+
+impl GogsClient {
+    pub async fn list_collaborators(&self, owner: &str, repo: &str) -> Result<Vec<Collaborator>> {
+        let path = format!("/repos/{}/{}/collaborators", owner, repo);
+        let resp = self.get(&path).await?;
+        let collaborators: Vec<Collaborator> = resp.json().await?;
+        Ok(collaborators)
+    }
+
+    /// Add `user` as a collaborator, or change their permission if they already are one.
+    pub async fn add_collaborator(
+        &self,
+        owner: &str,
+        repo: &str,
+        user: &str,
+        permission: CollaboratorPermission,
+    ) -> Result<()> {
+        let path = format!("/repos/{}/{}/collaborators/{}", owner, repo, user);
+        self.put(&path, serde_json::json!({ "permission": permission })).await?;
+        Ok(())
+    }
+
+    pub async fn remove_collaborator(&self, owner: &str, repo: &str, user: &str) -> Result<()> {
+        let path = format!("/repos/{}/{}/collaborators/{}", owner, repo, user);
+        self.delete(&path).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use super::client::GogsClient;
+use super::types::{FileCommit, FileContents, PutFileParams};
+use crate::error::GogsError;
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+impl GogsClient {
+    /// Fetch a file from a repository, or `None` if it doesn't exist.
+    /// `git_ref` selects a branch, tag or commit (default: the default branch).
+    pub async fn get_file_contents(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        git_ref: Option<&str>,
+    ) -> Result<Option<FileContents>> {
+        let mut url = contents_path(owner, repo, path);
+        if let Some(git_ref) = git_ref {
+            url.push_str(&format!("?ref={}", Self::encode_query(git_ref)));
+        }
+
+        let resp = match self.get(&url).await {
+            Ok(resp) => resp,
+            Err(e) if matches!(e.downcast_ref(), Some(GogsError::NotFound(_))) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        // A directory comes back as a list of its entries
+        let value: Value = resp.json().await?;
+        if value.is_array() {
+            anyhow::bail!("{} is a directory in {}/{}", path, owner, repo);
+        }
+        Ok(Some(serde_json::from_value(value).context("Failed to parse file contents")?))
+    }
+
+    /// Commit a file at `path`, creating or replacing it.
+    pub async fn put_file_contents(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        params: &PutFileParams,
+    ) -> Result<FileCommit> {
+        let resp = self
+            .put(&contents_path(owner, repo, path), serde_json::to_value(params)?)
+            .await?;
+        let commit: FileCommit = resp.json().await?;
+        Ok(commit)
+    }
+}
+
+fn contents_path(owner: &str, repo: &str, path: &str) -> String {
+    format!("/repos/{}/{}/contents/{}", owner, repo, GogsClient::encode_path(path))
+}
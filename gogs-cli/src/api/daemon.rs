@@ -0,0 +1,401 @@
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::{Client, Method, Request, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::Notify;
+use tokio::task::JoinSet;
+
+use crate::shutdown;
+
+/// Seconds the daemon reuses a GET response unless told otherwise.
+pub const DEFAULT_CACHE_TTL: u64 = 10;
+
+/// Responses kept at most; the cache is emptied when it would grow past this.
+const CACHE_ENTRIES: usize = 1000;
+
+/// Where the daemon listens: the user's runtime directory, else their cache directory.
+pub fn socket_path() -> Option<PathBuf> {
+    let dir = dirs::runtime_dir().or_else(dirs::cache_dir)?;
+    Some(dir.join("gogs-cli").join("daemon.sock"))
+}
+
+/// One line from the CLI to the daemon.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Message {
+    Relay(Relayed),
+    Status,
+    Stop,
+}
+
+/// One line back.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "reply", rename_all = "snake_case")]
+enum Reply {
+    Response(Relayed),
+    Status(DaemonStatus),
+    Stopping { pid: u32 },
+    /// `connected` is false when the request never reached the server
+    Failed { error: String, connected: bool },
+}
+
+/// A request or response on the wire. Requests carry a method, URL and
+/// timeout; responses a status.
+#[derive(Serialize, Deserialize, Default)]
+struct Relayed {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    method: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    url: String,
+    #[serde(default)]
+    timeout_ms: u64,
+    #[serde(default)]
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// Base64
+    body: String,
+}
+
+/// What `gog daemon --status` reports.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub socket: String,
+    pub uptime_secs: u64,
+    pub requests: u64,
+    pub cache_hits: u64,
+    pub cached: usize,
+    pub cache_ttl_secs: u64,
+}
+
+/// The CLI's side: a running daemon's socket, found when the client is built.
+pub struct DaemonLink {
+    path: PathBuf,
+}
+
+impl DaemonLink {
+    /// The daemon's socket if one is listening there. GOGS_NO_DAEMON bypasses it.
+    pub fn detect() -> Option<Self> {
+        if std::env::var_os("GOGS_NO_DAEMON").is_some() {
+            return None;
+        }
+        let path = socket_path()?;
+        path.exists().then_some(Self { path })
+    }
+
+    /// Send `request` through the daemon. `Err` means nothing was sent and the
+    /// caller should send it itself: the daemon isn't answering, the body is a
+    /// stream, or the daemon couldn't connect to the server. Once the request
+    /// has been handed over, failures come back as a 502 or 504 so they are
+    /// retried (or not) like any other gateway error.
+    pub(super) async fn relay(&self, request: &Request, timeout: Duration) -> Result<Response> {
+        let body = match request.body() {
+            Some(body) => body.as_bytes().context("streaming bodies aren't relayed")?,
+            None => &[],
+        };
+        let timeout = request.timeout().copied().unwrap_or(timeout);
+        let message = Message::Relay(Relayed {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            timeout_ms: timeout.as_millis() as u64,
+            headers: header_pairs(request.headers()),
+            body: STANDARD.encode(body),
+            ..Relayed::default()
+        });
+
+        let mut stream = UnixStream::connect(&self.path).await?;
+        write_line(&mut stream, &message).await?;
+
+        let reply = match read_line::<Reply>(&mut stream).await {
+            Ok(reply) => reply,
+            Err(e) => return gateway_error(502, &format!("lost the daemon's reply: {:#}", e)),
+        };
+        match reply {
+            Reply::Response(relayed) => {
+                let mut builder = http::Response::builder().status(relayed.status);
+                for (name, value) in &relayed.headers {
+                    builder = builder.header(name, value);
+                }
+                let body = STANDARD.decode(&relayed.body).context("Malformed daemon reply")?;
+                Ok(Response::from(builder.body(body)?))
+            }
+            Reply::Failed { connected: false, error } => anyhow::bail!(error),
+            Reply::Failed { error, .. } => gateway_error(504, &format!("via gog daemon: {}", error)),
+            _ => gateway_error(502, "unexpected daemon reply"),
+        }
+    }
+}
+
+fn gateway_error(status: u16, text: &str) -> Result<Response> {
+    let resp = http::Response::builder().status(status).body(text.to_string())?;
+    Ok(Response::from(resp))
+}
+
+/// Ask the daemon at `path` how it's doing, or None when none is running.
+pub async fn status(path: &Path) -> Option<DaemonStatus> {
+    match ask(path, &Message::Status).await.ok()? {
+        Reply::Status(status) => Some(status),
+        _ => None,
+    }
+}
+
+/// Stop the daemon at `path`, returning its pid, or None when none is running.
+pub async fn stop(path: &Path) -> Option<u32> {
+    match ask(path, &Message::Stop).await.ok()? {
+        Reply::Stopping { pid } => Some(pid),
+        _ => None,
+    }
+}
+
+async fn ask(path: &Path, message: &Message) -> Result<Reply> {
+    let mut stream = UnixStream::connect(path).await?;
+    write_line(&mut stream, message).await?;
+    read_line(&mut stream).await
+}
+
+struct Cached {
+    at: Instant,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+/// The daemon's side: one HTTP client whose connection pool outlives the
+/// commands using it, and recent GET responses.
+struct Daemon {
+    client: Client,
+    socket: PathBuf,
+    cache_ttl: Duration,
+    cache: Mutex<HashMap<(String, String), Cached>>,
+    started: Instant,
+    requests: AtomicU64,
+    hits: AtomicU64,
+    stop: Notify,
+}
+
+/// Create `dir` readable by this user only, or make an existing one so.
+/// Refuses a directory someone else owns, as they could swap the socket.
+fn private_dir(dir: &Path) -> Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    let metadata = fs::symlink_metadata(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    // SAFETY: getuid has no preconditions and can't fail
+    let uid = unsafe { libc::getuid() };
+    if !metadata.is_dir() || metadata.uid() != uid {
+        anyhow::bail!(
+            "{} is not a directory owned by you; refusing to put the daemon's socket there",
+            dir.display()
+        );
+    }
+    if metadata.mode() & 0o077 != 0 {
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+            .with_context(|| format!("Failed to make {} private", dir.display()))?;
+    }
+    Ok(())
+}
+
+/// Listen on `path` until stopped by `gog daemon --stop`, SIGINT or SIGTERM. GET
+/// responses are reused for `cache_ttl`, and any other request empties the
+/// cache, since it may have changed what they would return.
+pub async fn serve(path: &Path, cache_ttl: Duration) -> Result<()> {
+    if status(path).await.is_some() {
+        anyhow::bail!("A daemon is already listening on {}", path.display());
+    }
+    // Only this user may reach the socket from the moment it exists, since
+    // the daemon attaches the profile's token to whatever it relays
+    if let Some(parent) = path.parent() {
+        private_dir(parent)?;
+    }
+    // Left behind by a daemon that didn't exit cleanly
+    let _ = fs::remove_file(path);
+
+    let listener = UnixListener::bind(path).with_context(|| format!("Failed to listen on {}", path.display()))?;
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+
+    let daemon = Arc::new(Daemon {
+        client: Client::new(),
+        socket: path.to_path_buf(),
+        cache_ttl,
+        cache: Mutex::default(),
+        started: Instant::now(),
+        requests: AtomicU64::new(0),
+        hits: AtomicU64::new(0),
+        stop: Notify::new(),
+    });
+
+    shutdown::graceful();
+    let mut conversations = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else { continue };
+                let daemon = daemon.clone();
+                conversations.spawn(async move { daemon.converse(stream).await });
+            }
+            // Reap finished conversations so the set doesn't grow
+            Some(_) = conversations.join_next(), if !conversations.is_empty() => {}
+            _ = daemon.stop.notified() => break,
+            _ = shutdown::wait() => break,
+        }
+    }
+
+    // Stop accepting, then let relayed requests finish so no command loses its reply
+    drop(listener);
+    let _ = fs::remove_file(path);
+    while conversations.join_next().await.is_some() {}
+    Ok(())
+}
+
+impl Daemon {
+    async fn converse(&self, mut stream: UnixStream) {
+        let Ok(message) = read_line::<Message>(&mut stream).await else {
+            return;
+        };
+        let reply = match message {
+            Message::Relay(request) => self.relay(request).await,
+            Message::Status => Reply::Status(self.status()),
+            Message::Stop => {
+                self.stop.notify_one();
+                Reply::Stopping { pid: std::process::id() }
+            }
+        };
+        let _ = write_line(&mut stream, &reply).await;
+    }
+
+    fn status(&self) -> DaemonStatus {
+        DaemonStatus {
+            pid: std::process::id(),
+            socket: self.socket.display().to_string(),
+            uptime_secs: self.started.elapsed().as_secs(),
+            requests: self.requests.load(Ordering::Relaxed),
+            cache_hits: self.hits.load(Ordering::Relaxed),
+            cached: self.cache.lock().unwrap().len(),
+            cache_ttl_secs: self.cache_ttl.as_secs(),
+        }
+    }
+
+    async fn relay(&self, request: Relayed) -> Reply {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let method = Method::from_bytes(request.method.as_bytes()).unwrap_or(Method::GET);
+        let auth = request
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default();
+        let key = (request.url.clone(), auth);
+
+        if method == Method::GET {
+            if let Some(cached) = self.cache.lock().unwrap().get(&key) {
+                if cached.at.elapsed() < self.cache_ttl {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    return Reply::Response(Relayed {
+                        status: cached.status,
+                        headers: cached.headers.clone(),
+                        body: cached.body.clone(),
+                        ..Relayed::default()
+                    });
+                }
+            }
+        } else {
+            self.cache.lock().unwrap().clear();
+        }
+
+        let mut req = self
+            .client
+            .request(method.clone(), &request.url)
+            .timeout(Duration::from_millis(request.timeout_ms));
+        for (name, value) in &request.headers {
+            req = req.header(name, value);
+        }
+        let body = STANDARD.decode(&request.body).unwrap_or_default();
+        if !body.is_empty() {
+            req = req.body(body);
+        }
+
+        let resp = match req.send().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                return Reply::Failed {
+                    error: format!("{:#}", e),
+                    connected: !e.is_connect(),
+                }
+            }
+        };
+        let status = resp.status();
+        let headers = header_pairs(resp.headers());
+        let body = match resp.bytes().await {
+            Ok(bytes) => STANDARD.encode(bytes),
+            Err(e) => {
+                return Reply::Failed {
+                    error: format!("{:#}", e),
+                    connected: true,
+                }
+            }
+        };
+
+        if method == Method::GET && status.is_success() && !self.cache_ttl.is_zero() {
+            let mut cache = self.cache.lock().unwrap();
+            cache.retain(|_, cached| cached.at.elapsed() < self.cache_ttl);
+            if cache.len() >= CACHE_ENTRIES {
+                cache.clear();
+            }
+            cache.insert(
+                key,
+                Cached {
+                    at: Instant::now(),
+                    status: status.as_u16(),
+                    headers: headers.clone(),
+                    body: body.clone(),
+                },
+            );
+        }
+
+        Reply::Response(Relayed {
+            status: status.as_u16(),
+            headers,
+            body,
+            ..Relayed::default()
+        })
+    }
+}
+
+fn header_pairs(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+async fn write_line<T: Serialize>(stream: &mut UnixStream, value: &T) -> Result<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    stream.write_all(&line).await?;
+    Ok(())
+}
+
+async fn read_line<T: for<'de> Deserialize<'de>>(stream: &mut UnixStream) -> Result<T> {
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).await?;
+    if line.is_empty() {
+        anyhow::bail!("connection closed");
+    }
+    Ok(serde_json::from_str(&line)?)
+}
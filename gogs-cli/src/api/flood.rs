@@ -0,0 +1,141 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::storage::{self, Storage};
+
+/// How many comments a profile may post on one issue within a window.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CommentLimit {
+    /// Comments allowed per issue within the window
+    pub max: usize,
+
+    /// Length of the window in minutes (default 60)
+    #[serde(default = "default_window", skip_serializing_if = "is_default_window")]
+    pub window_minutes: u64,
+}
+
+fn default_window() -> u64 {
+    60
+}
+
+fn is_default_window(minutes: &u64) -> bool {
+    *minutes == default_window()
+}
+
+impl CommentLimit {
+    /// What the agent presets start with: generous for real work, far below a loop.
+    pub fn agent_default() -> Self {
+        Self {
+            max: 10,
+            window_minutes: default_window(),
+        }
+    }
+}
+
+/// Post times per issue for each identity, in seconds since the epoch.
+type CommentLog = HashMap<String, HashMap<String, Vec<i64>>>;
+
+/// Key of the comment log in local storage.
+const LOG_KEY: &str = "comments.json";
+
+/// Refuses comments over a profile's limit. Post times are kept in local
+/// storage, so the limit holds across invocations: a looping agent usually
+/// runs gog once per comment.
+pub struct CommentGuard {
+    limit: CommentLimit,
+    identity: String,
+    storage: Arc<dyn Storage>,
+    /// Held while the log is read and rewritten
+    lock: Mutex<()>,
+}
+
+impl CommentGuard {
+    /// Limit comments posted as `identity` (user and signature), logging
+    /// them in `storage`.
+    pub fn new(limit: CommentLimit, identity: String, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            limit,
+            identity,
+            storage,
+            lock: Mutex::default(),
+        }
+    }
+
+    /// Fail if another comment on owner/repo#number would go over the limit.
+    pub fn check(&self, owner: &str, repo: &str, number: i64) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+        let log = self.load();
+        let recent = self.recent(&log, &issue_key(owner, repo, number));
+        if recent.len() < self.limit.max {
+            return Ok(());
+        }
+
+        // The oldest comment in the window is the next to drop out of it
+        let window = self.window_secs();
+        let wait = recent
+            .iter()
+            .min()
+            .map(|oldest| (oldest + window - Utc::now().timestamp()).max(0))
+            .unwrap_or(window);
+        anyhow::bail!(
+            "Comment limit reached: {} comment(s) on {}/{}#{} in the last {} minutes (limit {}). \
+            Try again in {} minute(s), or pass --override if you are a person at a terminal",
+            recent.len(),
+            owner,
+            repo,
+            number,
+            self.limit.window_minutes,
+            self.limit.max,
+            (wait + 59) / 60
+        );
+    }
+
+    /// Log a comment just posted on owner/repo#number.
+    pub fn record(&self, owner: &str, repo: &str, number: i64) {
+        let _lock = self.lock.lock().unwrap();
+        let mut log = self.load();
+        let key = issue_key(owner, repo, number);
+        let now = Utc::now().timestamp();
+        let cutoff = now - self.window_secs();
+
+        let issues = log.entry(self.identity.clone()).or_default();
+        issues.entry(key).or_default().push(now);
+        // Old entries only matter within the window, so the file stays small
+        for times in issues.values_mut() {
+            times.retain(|t| *t > cutoff);
+        }
+        issues.retain(|_, times| !times.is_empty());
+
+        if let Err(e) = storage::save_json(&*self.storage, LOG_KEY, &log) {
+            eprintln!(
+                "Warning: Failed to record comment in {}: {}",
+                self.storage.describe(LOG_KEY),
+                e
+            );
+        }
+    }
+
+    fn load(&self) -> CommentLog {
+        // A missing or damaged log starts afresh rather than blocking comments
+        storage::load_json(&*self.storage, LOG_KEY)
+    }
+
+    fn recent(&self, log: &CommentLog, key: &str) -> Vec<i64> {
+        let cutoff = Utc::now().timestamp() - self.window_secs();
+        log.get(&self.identity)
+            .and_then(|issues| issues.get(key))
+            .map(|times| times.iter().copied().filter(|t| *t > cutoff).collect())
+            .unwrap_or_default()
+    }
+
+    fn window_secs(&self) -> i64 {
+        self.limit.window_minutes as i64 * 60
+    }
+}
+
+fn issue_key(owner: &str, repo: &str, number: i64) -> String {
+    format!("{}/{}#{}", owner.to_lowercase(), repo.to_lowercase(), number)
+}
@@ -1,21 +1,133 @@
 use super::client::GogsClient;
-use super::types::{Comment, Issue, Label};
-use anyhow::Result;
+use super::types::{
+    Attachment, Comment, CreateIssueParams, Issue, IssueState, Label, ListIssuesParams, StateFilter,
+    UpdateIssueParams,
+};
+use anyhow::{Context, Result};
+use reqwest::multipart::{Form, Part};
 use serde_json::json;
+use std::path::Path;
 
 impl GogsClient {
     pub async fn list_issues(
         &self,
         owner: &str,
         repo: &str,
-        state: &str,
+        params: &ListIssuesParams,
     ) -> Result<Vec<Issue>> {
-        let path = format!("/repos/{}/{}/issues?state={}", owner, repo, state);
-        let resp = self.get(&path).await?;
-        let issues: Vec<Issue> = resp.json().await?;
+        let mut issues = match params.state {
+            StateFilter::Open => {
+                self.list_issues_in_state(owner, repo, IssueState::Open, params).await?
+            }
+            StateFilter::Closed => {
+                self.list_issues_in_state(owner, repo, IssueState::Closed, params).await?
+            }
+            StateFilter::All => {
+                // Gogs only filters by open or closed, so fetch both and merge
+                let (mut open, closed) = tokio::try_join!(
+                    self.list_issues_in_state(owner, repo, IssueState::Open, params),
+                    self.list_issues_in_state(owner, repo, IssueState::Closed, params),
+                )?;
+                open.extend(closed);
+                open.sort_by_key(|issue| std::cmp::Reverse(issue.number));
+                open
+            }
+        };
+
+        // Servers without label filtering ignore the parameter; filter here instead
+        if !params.labels.is_empty() {
+            issues.retain(|i| params.labels.matches(i));
+        }
+
+        // Same for keyword search
+        if let Some(query) = &params.query {
+            issues.retain(|i| matches_query(i, query));
+        }
+
+        if let Some(author) = &params.author {
+            issues.retain(|i| i.user.username.eq_ignore_ascii_case(author));
+        }
+        if let Some(milestone) = &params.milestone {
+            issues.retain(|i| milestone.matches(i));
+        }
+        if let Some(after) = params.created_after {
+            issues.retain(|i| i.created_at >= after);
+        }
+        if let Some(before) = params.created_before {
+            issues.retain(|i| i.created_at < before);
+        }
+        if let Some(since) = params.updated_since {
+            issues.retain(|i| i.updated_at >= since);
+        }
+        if let Some(limit) = params.limit {
+            issues.truncate(limit);
+        }
+
         Ok(issues)
     }
 
+    /// Count matching issues, using the server's `X-Total-Count` header when only the
+    /// state is filtered and falling back to listing and counting otherwise.
+    pub async fn count_issues(
+        &self,
+        owner: &str,
+        repo: &str,
+        params: &ListIssuesParams,
+    ) -> Result<usize> {
+        if !params.has_item_filters() {
+            let total = match params.state {
+                StateFilter::Open => self.total_count(owner, repo, IssueState::Open).await?,
+                StateFilter::Closed => self.total_count(owner, repo, IssueState::Closed).await?,
+                StateFilter::All => {
+                    let (open, closed) = tokio::try_join!(
+                        self.total_count(owner, repo, IssueState::Open),
+                        self.total_count(owner, repo, IssueState::Closed),
+                    )?;
+                    open.zip(closed).map(|(o, c)| o + c)
+                }
+            };
+            if let Some(total) = total {
+                return Ok(total);
+            }
+        }
+
+        Ok(self.list_issues(owner, repo, params).await?.len())
+    }
+
+    async fn total_count(&self, owner: &str, repo: &str, state: IssueState) -> Result<Option<usize>> {
+        let path = format!("/repos/{}/{}/issues?state={}&limit=1", owner, repo, state);
+        let resp = self.get(&path).await?;
+        let total = resp
+            .headers()
+            .get("X-Total-Count")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+        Ok(total)
+    }
+
+    async fn list_issues_in_state(
+        &self,
+        owner: &str,
+        repo: &str,
+        state: IssueState,
+        params: &ListIssuesParams,
+    ) -> Result<Vec<Issue>> {
+        let mut path = format!("/repos/{}/{}/issues?state={}", owner, repo, state);
+        // Servers differ on whether `labels` means any or all of the list, so only send
+        // it when both readings agree with what was asked; negation stays client-side
+        let labels = &params.labels;
+        if labels.include.len() == 1 || (labels.require_all && !labels.include.is_empty()) {
+            path.push_str(&format!("&labels={}", Self::encode_query(&labels.include.join(","))));
+        }
+        if let Some(query) = &params.query {
+            path.push_str(&format!("&q={}", Self::encode_query(query)));
+        }
+
+        // Client-side filters may drop items, so only stop paging early without them
+        let limit = if params.has_item_filters() { None } else { params.limit };
+        self.get_paginated(&path, limit).await
+    }
+
     pub async fn get_issue(&self, owner: &str, repo: &str, number: i64) -> Result<Issue> {
         let path = format!("/repos/{}/{}/issues/{}", owner, repo, number);
         let resp = self.get(&path).await?;
@@ -27,22 +139,10 @@ impl GogsClient {
         &self,
         owner: &str,
         repo: &str,
-        title: &str,
-        body: Option<&str>,
-        labels: Vec<String>,
+        params: &CreateIssueParams,
     ) -> Result<Issue> {
         let path = format!("/repos/{}/{}/issues", owner, repo);
-        let mut payload = json!({
-            "title": title,
-        });
-
-        if let Some(b) = body {
-            payload["body"] = json!(b);
-        }
-
-        if !labels.is_empty() {
-            payload["labels"] = json!(labels);
-        }
+        let payload = serde_json::to_value(params)?;
 
         let resp = self.post(&path, payload).await?;
         let issue: Issue = resp.json().await?;
@@ -54,14 +154,10 @@ impl GogsClient {
         owner: &str,
         repo: &str,
         number: i64,
-        state: Option<&str>,
+        params: &UpdateIssueParams,
     ) -> Result<Issue> {
         let path = format!("/repos/{}/{}/issues/{}", owner, repo, number);
-        let mut payload = json!({});
-
-        if let Some(s) = state {
-            payload["state"] = json!(s);
-        }
+        let payload = serde_json::to_value(params)?;
 
         let resp = self.patch(&path, payload).await?;
         let issue: Issue = resp.json().await?;
@@ -87,18 +183,58 @@ impl GogsClient {
         number: i64,
         body: &str,
     ) -> Result<Comment> {
+        if let Some(guard) = self.comment_guard() {
+            guard.check(owner, repo, number)?;
+        }
+
         let path = format!("/repos/{}/{}/issues/{}/comments", owner, repo, number);
         let payload = json!({ "body": body });
         let resp = self.post(&path, payload).await?;
         let comment: Comment = resp.json().await?;
+
+        if let Some(guard) = self.comment_guard() {
+            guard.record(owner, repo, number);
+        }
         Ok(comment)
     }
 
-    pub async fn list_repo_labels(&self, owner: &str, repo: &str) -> Result<Vec<Label>> {
-        let path = format!("/repos/{}/{}/labels", owner, repo);
-        let resp = self.get(&path).await?;
-        let labels: Vec<Label> = resp.json().await?;
-        Ok(labels)
+    /// Replace a comment's text. Edits don't count against the comment limit,
+    /// which is about how many comments an issue collects.
+    pub async fn edit_comment(&self, owner: &str, repo: &str, id: i64, body: &str) -> Result<Comment> {
+        let path = format!("/repos/{}/{}/issues/comments/{}", owner, repo, id);
+        let payload = json!({ "body": body });
+        let resp = self.patch(&path, payload).await?;
+        let comment: Comment = resp.json().await?;
+        Ok(comment)
+    }
+
+    pub async fn delete_comment(&self, owner: &str, repo: &str, id: i64) -> Result<()> {
+        let path = format!("/repos/{}/{}/issues/comments/{}", owner, repo, id);
+        self.delete(&path).await?;
+        Ok(())
+    }
+
+    /// Upload a file as an issue attachment.
+    pub async fn upload_issue_attachment(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: i64,
+        file: &Path,
+    ) -> Result<Attachment> {
+        let name = file
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "attachment".to_string());
+        let data = tokio::fs::read(file)
+            .await
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+
+        let form = Form::new().part("attachment", Part::bytes(data).file_name(name));
+        let path = format!("/repos/{}/{}/issues/{}/assets", owner, repo, number);
+        let resp = self.post_multipart(&path, form).await?;
+        let attachment: Attachment = resp.json().await?;
+        Ok(attachment)
     }
 
     pub async fn add_labels_to_issue(
@@ -127,3 +263,12 @@ impl GogsClient {
         Ok(())
     }
 }
+
+fn matches_query(issue: &Issue, query: &str) -> bool {
+    let query = query.to_lowercase();
+    issue.title.to_lowercase().contains(&query)
+        || issue
+            .body
+            .as_deref()
+            .is_some_and(|body| body.to_lowercase().contains(&query))
+}
@@ -0,0 +1,26 @@
+use super::client::GogsClient;
+use super::types::PublicKey;
+use anyhow::Result;
+
+impl GogsClient {
+    /// SSH keys registered to the current user.
+    pub async fn list_keys(&self) -> Result<Vec<PublicKey>> {
+        let resp = self.get("/user/keys").await?;
+        let keys: Vec<PublicKey> = resp.json().await?;
+        Ok(keys)
+    }
+
+    /// Register an SSH public key (`TYPE BASE64 [COMMENT]`) for the current user.
+    pub async fn add_key(&self, title: &str, key: &str) -> Result<PublicKey> {
+        let resp = self
+            .post("/user/keys", serde_json::json!({ "title": title, "key": key }))
+            .await?;
+        let key: PublicKey = resp.json().await?;
+        Ok(key)
+    }
+
+    pub async fn delete_key(&self, id: i64) -> Result<()> {
+        self.delete(&format!("/user/keys/{}", id)).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use super::client::GogsClient;
+use super::types::Label;
+use anyhow::Result;
+use serde_json::json;
+
+impl GogsClient {
+    /// List a repository's labels, served from the label cache when possible.
+    pub async fn list_repo_labels(&self, owner: &str, repo: &str) -> Result<Vec<Label>> {
+        if let Some(labels) = self.label_cache().get(owner, repo) {
+            return Ok(labels);
+        }
+
+        let path = format!("/repos/{}/{}/labels", owner, repo);
+        let resp = self.get(&path).await?;
+        let labels: Vec<Label> = resp.json().await?;
+        self.label_cache().put(owner, repo, &labels);
+        Ok(labels)
+    }
+
+    /// Create a repository label. `color` is a hex string such as `#ee0701`.
+    pub async fn create_label(
+        &self,
+        owner: &str,
+        repo: &str,
+        name: &str,
+        color: &str,
+    ) -> Result<Label> {
+        let path = format!("/repos/{}/{}/labels", owner, repo);
+        let payload = json!({ "name": name, "color": color });
+        let resp = self.post(&path, payload).await?;
+        let label: Label = resp.json().await?;
+        self.label_cache().invalidate(owner, repo);
+        Ok(label)
+    }
+
+    /// Rename and/or recolor a label; `None` leaves a field unchanged.
+    pub async fn edit_label(
+        &self,
+        owner: &str,
+        repo: &str,
+        id: i64,
+        name: Option<&str>,
+        color: Option<&str>,
+    ) -> Result<Label> {
+        let path = format!("/repos/{}/{}/labels/{}", owner, repo, id);
+        let mut payload = json!({});
+        if let Some(name) = name {
+            payload["name"] = json!(name);
+        }
+        if let Some(color) = color {
+            payload["color"] = json!(color);
+        }
+        let resp = self.patch(&path, payload).await?;
+        let label: Label = resp.json().await?;
+        self.label_cache().invalidate(owner, repo);
+        Ok(label)
+    }
+
+    /// Delete a label, which also removes it from every issue.
+    pub async fn delete_label(&self, owner: &str, repo: &str, id: i64) -> Result<()> {
+        let path = format!("/repos/{}/{}/labels/{}", owner, repo, id);
+        self.delete(&path).await?;
+        self.label_cache().invalidate(owner, repo);
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use anyhow::{Context, Result};
+use reqwest::{Method, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::client::GogsClient;
+use super::types::Label;
+
+/// A second server that a profile's issue tracker changes are repeated on,
+/// e.g. while moving from Gogs to Gitea.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MirrorConfig {
+    /// Server URL of the mirror
+    pub url: String,
+
+    /// API token on the mirror
+    pub token: String,
+
+    /// API path on the mirror (default /api/v1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_prefix: Option<String>,
+
+    /// Mirror repository for each "owner/repo" whose name differs there
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub repos: HashMap<String, String>,
+}
+
+/// Repeats mutating issue and label requests on the mirror. The primary server
+/// stays authoritative: a mirror failure never fails the command, it is
+/// reported as a divergence instead.
+pub struct Mirror {
+    client: GogsClient,
+    repos: HashMap<String, String>,
+}
+
+/// The mirror's version of one request.
+struct Replica {
+    path: String,
+    body: Option<Value>,
+}
+
+impl Mirror {
+    pub fn new(config: &MirrorConfig) -> Self {
+        let mut client = GogsClient::new(config.url.clone(), config.token.clone());
+        if let Some(prefix) = &config.api_prefix {
+            client = client.with_api_prefix(prefix);
+        }
+        Self {
+            client,
+            repos: config.repos.clone(),
+        }
+    }
+
+    /// Send a mutating request to `primary`, then repeat it on the mirror if it
+    /// touches an issue tracker.
+    pub(super) async fn request(
+        &self,
+        primary: &GogsClient,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Response> {
+        let Some((repo, rest)) = tracker_path(path) else {
+            return primary.request_direct(method, path, body).await;
+        };
+        // Comments are numbered per server, so an edit would land on the wrong one
+        if rest.starts_with("/issues/comments/") {
+            self.report(&method, path, &anyhow::anyhow!("comment edits aren't mirrored"));
+            return primary.request_direct(method, path, body).await;
+        }
+
+        // Label ids are resolved now, since the request may delete the label
+        let replica = self.prepare(primary, &repo, &rest, body.clone()).await;
+        let resp = primary.request_direct(method.clone(), path, body).await?;
+
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let bytes = resp.bytes().await.context("Failed to read response")?;
+
+        let outcome = match replica {
+            Ok(replica) => self.apply(&method, &rest, replica, &bytes).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = outcome {
+            self.report(&method, path, &e);
+        }
+
+        let mut rebuilt = http::Response::builder().status(status);
+        if let Some(slot) = rebuilt.headers_mut() {
+            *slot = headers;
+        }
+        Ok(Response::from(rebuilt.body(bytes)?))
+    }
+
+    /// Uploads aren't repeated; say so when they would have been.
+    pub(super) fn skip_upload(&self, path: &str) {
+        if tracker_path(path).is_some() {
+            let e = anyhow::anyhow!("uploads aren't mirrored");
+            self.report(&Method::POST, path, &e);
+        }
+    }
+
+    fn report(&self, method: &Method, path: &str, e: &anyhow::Error) {
+        eprintln!(
+            "Warning: Mirror {} diverged on {} {}: {:#}",
+            self.client.base_url(),
+            method,
+            path,
+            e
+        );
+    }
+
+    /// Rewrite the request for the mirror: its repository name and label ids.
+    async fn prepare(
+        &self,
+        primary: &GogsClient,
+        repo: &str,
+        rest: &str,
+        mut body: Option<Value>,
+    ) -> Result<Replica> {
+        let target = self.repos.get(repo).map(String::as_str).unwrap_or(repo);
+        let mut rest = rest.to_string();
+
+        let path_id = label_id_in(&rest);
+        let body_ids: Vec<i64> = body
+            .as_ref()
+            .and_then(|b| b.get("labels"))
+            .and_then(Value::as_array)
+            .map(|ids| ids.iter().filter_map(Value::as_i64).collect())
+            .unwrap_or_default();
+
+        if path_id.is_some() || !body_ids.is_empty() {
+            let ours = list_labels(primary, repo).await?;
+            let theirs = list_labels(&self.client, target).await?;
+            let translate = |id: i64| -> Result<i64> {
+                let name = ours
+                    .iter()
+                    .find(|l| l.id == id)
+                    .map(|l| l.name.as_str())
+                    .with_context(|| format!("label {} not found", id))?;
+                theirs
+                    .iter()
+                    .find(|l| l.name == name)
+                    .map(|l| l.id)
+                    .with_context(|| format!("label '{}' doesn't exist on the mirror", name))
+            };
+
+            if let Some(id) = path_id {
+                let prefix = rest.rsplit_once('/').map(|(prefix, _)| prefix.to_string()).unwrap_or_default();
+                rest = format!("{}/{}", prefix, translate(id)?);
+            }
+            if let Some(labels) = body.as_mut().and_then(|b| b.get_mut("labels")) {
+                let mapped = body_ids.into_iter().map(translate).collect::<Result<Vec<_>>>()?;
+                *labels = serde_json::json!(mapped);
+            }
+        }
+
+        Ok(Replica {
+            path: format!("/repos/{}{}", target, rest),
+            body,
+        })
+    }
+
+    async fn apply(&self, method: &Method, rest: &str, replica: Replica, primary_body: &[u8]) -> Result<()> {
+        let resp = self
+            .client
+            .request_direct(method.clone(), &replica.path, replica.body)
+            .await?;
+
+        // Later requests address issues by number, so the two must line up
+        if *method == Method::POST && rest == "/issues" {
+            let number = |bytes: &[u8]| {
+                serde_json::from_slice::<Value>(bytes)
+                    .ok()
+                    .and_then(|v| v.get("number").and_then(Value::as_i64))
+            };
+            let ours = number(primary_body);
+            let theirs = number(&resp.bytes().await.unwrap_or_default());
+            if let (Some(ours), Some(theirs)) = (ours, theirs) {
+                if ours != theirs {
+                    anyhow::bail!(
+                        "issue #{} was created as #{} on the mirror; changes to it won't reach the right mirror issue",
+                        ours,
+                        theirs
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split "/repos/OWNER/REPO/issues/..." into ("OWNER/REPO", "/issues/...").
+/// Only issue and label paths are mirrored, never the repository itself.
+fn tracker_path(path: &str) -> Option<(String, String)> {
+    let rest = path.strip_prefix("/repos/")?;
+    let mut parts = rest.splitn(3, '/');
+    let owner = parts.next()?;
+    let repo = parts.next()?;
+    let tail = parts.next()?;
+    let section = tail.split(['/', '?']).next()?;
+    if section != "issues" && section != "labels" {
+        return None;
+    }
+    Some((format!("{}/{}", owner, repo), format!("/{}", tail)))
+}
+
+/// The label id at the end of "/labels/ID" or "/issues/N/labels/ID".
+fn label_id_in(rest: &str) -> Option<i64> {
+    let (prefix, id) = rest.rsplit_once('/')?;
+    if !prefix.ends_with("/labels") {
+        return None;
+    }
+    id.parse().ok()
+}
+
+async fn list_labels(client: &GogsClient, repo: &str) -> Result<Vec<Label>> {
+    let resp = client
+        .request_direct(Method::GET, &format!("/repos/{}/labels", repo), None)
+        .await?;
+    Ok(resp.json().await?)
+}
@@ -0,0 +1,31 @@
+use super::client::GogsClient;
+use super::types::{Organization, Repository, User};
+use anyhow::Result;
+
+impl GogsClient {
+    /// Organizations the current user belongs to.
+    pub async fn list_user_orgs(&self) -> Result<Vec<Organization>> {
+        let resp = self.get("/user/orgs").await?;
+        let orgs: Vec<Organization> = resp.json().await?;
+        Ok(orgs)
+    }
+
+    pub async fn get_org(&self, org: &str) -> Result<Organization> {
+        let path = format!("/orgs/{}", org);
+        let resp = self.get(&path).await?;
+        let organization: Organization = resp.json().await?;
+        Ok(organization)
+    }
+
+    /// Repositories owned by an organization, fetching no more pages than needed for `limit`.
+    pub async fn list_org_repos(&self, org: &str, limit: Option<usize>) -> Result<Vec<Repository>> {
+        let path = format!("/orgs/{}/repos", org);
+        self.get_paginated(&path, limit).await
+    }
+
+    /// Members of an organization visible to the current user.
+    pub async fn list_org_members(&self, org: &str) -> Result<Vec<User>> {
+        let path = format!("/orgs/{}/members", org);
+        self.get_paginated(&path, None).await
+    }
+}
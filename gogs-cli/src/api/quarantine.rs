@@ -0,0 +1,163 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use crate::error::GogsError;
+use crate::storage::{self, Storage};
+
+/// Fan-out runs in a row a repository must fail before it is quarantined.
+pub const QUARANTINE_AFTER: u32 = 3;
+
+/// Length of a first quarantine; each failure after it doubles the next one.
+const FIRST_QUARANTINE_MINUTES: i64 = 60;
+
+/// Longest a quarantine lasts before the repository is tried again.
+const MAX_QUARANTINE_MINUTES: i64 = 24 * 60;
+
+/// Key of the quarantine in local storage.
+const QUARANTINE_KEY: &str = "quarantine.json";
+
+/// A repository that keeps failing in fan-out commands.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Quarantined {
+    pub repo: String,
+    /// Failures in a row
+    pub failures: u32,
+    pub last_error: String,
+    pub last_failed: DateTime<Utc>,
+    /// Skipped until then; none while there are fewer than `QUARANTINE_AFTER` failures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl Quarantined {
+    pub fn is_active(&self) -> bool {
+        self.until.is_some_and(|until| until > Utc::now())
+    }
+}
+
+/// Entries per user, by lowercased "owner/repo".
+type QuarantineLog = HashMap<String, HashMap<String, Quarantined>>;
+
+/// Repositories that `--all` style commands skip for a while because they
+/// failed with "not found" or "access denied" run after run, e.g. a deleted
+/// sandbox. Kept in local storage per user, since each run is a new process.
+pub struct Quarantine {
+    user: String,
+    storage: Arc<dyn Storage>,
+    /// Held while the log is read and rewritten
+    lock: Mutex<()>,
+}
+
+/// Whether an error says the repository is gone or closed to us, rather than
+/// that the server or network had trouble.
+pub fn is_persistent(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref(),
+        Some(GogsError::NotFound(_)) | Some(GogsError::Forbidden)
+    )
+}
+
+impl Quarantine {
+    pub fn new(user: String, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            user: user.to_lowercase(),
+            storage,
+            lock: Mutex::default(),
+        }
+    }
+
+    /// This user's entries, quarantined or still counting failures, by repository.
+    pub fn entries(&self) -> Vec<Quarantined> {
+        let _lock = self.lock.lock().unwrap();
+        let mut entries: Vec<Quarantined> = self
+            .load()
+            .remove(&self.user)
+            .map(|repos| repos.into_values().collect())
+            .unwrap_or_default();
+        entries.sort_by(|a, b| a.repo.cmp(&b.repo));
+        entries
+    }
+
+    /// Lowercased names of the repositories to skip now.
+    pub fn active(&self) -> HashSet<String> {
+        self.entries()
+            .into_iter()
+            .filter(Quarantined::is_active)
+            .map(|entry| entry.repo.to_lowercase())
+            .collect()
+    }
+
+    /// Note one fan-out run's outcome: repositories that answered, and those
+    /// that failed persistently with the error. Returns the entries this run
+    /// quarantined.
+    pub fn record(&self, succeeded: &[String], failed: &[(String, String)]) -> Vec<Quarantined> {
+        let _lock = self.lock.lock().unwrap();
+        let mut log = self.load();
+        let repos = log.entry(self.user.clone()).or_default();
+
+        let mut changed = false;
+        for repo in succeeded {
+            changed |= repos.remove(&repo.to_lowercase()).is_some();
+        }
+
+        let now = Utc::now();
+        let mut quarantined = Vec::new();
+        for (repo, error) in failed {
+            let entry = repos.entry(repo.to_lowercase()).or_insert_with(|| Quarantined {
+                repo: repo.clone(),
+                failures: 0,
+                last_error: String::new(),
+                last_failed: now,
+                until: None,
+            });
+            entry.failures += 1;
+            entry.last_error = error.clone();
+            entry.last_failed = now;
+            if entry.failures >= QUARANTINE_AFTER {
+                let doublings = (entry.failures - QUARANTINE_AFTER).min(10);
+                let minutes = (FIRST_QUARANTINE_MINUTES << doublings).min(MAX_QUARANTINE_MINUTES);
+                entry.until = Some(now + Duration::minutes(minutes));
+                quarantined.push(entry.clone());
+            }
+            changed = true;
+        }
+
+        if changed {
+            self.save(log);
+        }
+        quarantined
+    }
+
+    /// Forget `repo`, or every entry, returning how many were removed.
+    pub fn clear(&self, repo: Option<&str>) -> usize {
+        let _lock = self.lock.lock().unwrap();
+        let mut log = self.load();
+        let repos = log.entry(self.user.clone()).or_default();
+        let removed = match repo {
+            Some(repo) => repos.remove(&repo.to_lowercase()).map_or(0, |_| 1),
+            None => std::mem::take(repos).len(),
+        };
+        if removed > 0 {
+            self.save(log);
+        }
+        removed
+    }
+
+    fn load(&self) -> QuarantineLog {
+        // A missing or damaged log only means every repository gets tried
+        storage::load_json(&*self.storage, QUARANTINE_KEY)
+    }
+
+    fn save(&self, mut log: QuarantineLog) {
+        log.retain(|_, repos| !repos.is_empty());
+        if let Err(e) = storage::save_json(&*self.storage, QUARANTINE_KEY, &log) {
+            eprintln!(
+                "Warning: Failed to update {}: {}",
+                self.storage.describe(QUARANTINE_KEY),
+                e
+            );
+        }
+    }
+}
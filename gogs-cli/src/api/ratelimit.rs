@@ -0,0 +1,55 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Spaces requests out to at most `rate` per second, after an initial burst
+/// of up to one second's worth. Clones of a client share one, so the
+/// requests of an `--all` fan-out are limited together.
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    /// Requests that may start now; negative when callers are already queued
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `rate` requests per second, or None if `rate` isn't positive.
+    pub fn new(rate: f64) -> Option<Self> {
+        if !(rate > 0.0 && rate.is_finite()) {
+            return None;
+        }
+        let burst = rate.max(1.0);
+        Some(Self {
+            rate,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                updated: Instant::now(),
+            }),
+        })
+    }
+
+    /// Wait for a turn to send a request.
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            let now = Instant::now();
+            let refill = now.duration_since(bucket.updated).as_secs_f64() * self.rate;
+            bucket.tokens = (bucket.tokens + refill).min(self.burst);
+            bucket.updated = now;
+
+            // Take the token now and sleep off the debt, so waiters go in
+            // the order they arrived without holding the lock
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-bucket.tokens / self.rate)
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
@@ -0,0 +1,58 @@
+use super::client::GogsClient;
+use super::types::{Attachment, Release};
+use crate::error::GogsError;
+use anyhow::{Context, Result};
+use reqwest::multipart::{Form, Part};
+use std::path::Path;
+
+impl GogsClient {
+    /// List a repository's releases, newest first.
+    pub async fn list_releases(&self, owner: &str, repo: &str) -> Result<Vec<Release>> {
+        // Gogs returns every release in one response
+        let path = format!("/repos/{}/{}/releases", owner, repo);
+        let resp = self.get(&path).await?;
+        let releases: Vec<Release> = resp.json().await?;
+        Ok(releases)
+    }
+
+    /// The release tagged `tag`. There is no lookup by tag, so this lists them all.
+    pub async fn get_release_by_tag(&self, owner: &str, repo: &str, tag: &str) -> Result<Release> {
+        let releases = self.list_releases(owner, repo).await?;
+        releases
+            .into_iter()
+            .find(|r| r.tag_name == tag)
+            .ok_or_else(|| GogsError::NotFound(format!("{}/{} has no release tagged {}", owner, repo, tag)).into())
+    }
+
+    pub async fn list_release_assets(&self, owner: &str, repo: &str, id: i64) -> Result<Vec<Attachment>> {
+        let path = format!("/repos/{}/{}/releases/{}/assets", owner, repo, id);
+        let resp = self.get(&path).await?;
+        let assets: Vec<Attachment> = resp.json().await?;
+        Ok(assets)
+    }
+
+    /// Upload a file as a release asset, streaming it from disk.
+    pub async fn upload_release_asset(&self, owner: &str, repo: &str, id: i64, file: &Path) -> Result<Attachment> {
+        let name = file
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .with_context(|| format!("{} is not a file", file.display()))?;
+        let opened = tokio::fs::File::open(file)
+            .await
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let len = opened.metadata().await?.len();
+
+        let part = Part::stream_with_length(opened, len).file_name(name.clone());
+        let form = Form::new().part("attachment", part);
+        let path = format!(
+            "/repos/{}/{}/releases/{}/assets?name={}",
+            owner,
+            repo,
+            id,
+            Self::encode_query(&name)
+        );
+        let resp = self.post_multipart(&path, form).await?;
+        let asset: Attachment = resp.json().await?;
+        Ok(asset)
+    }
+}
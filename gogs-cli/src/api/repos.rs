@@ -1,20 +1,201 @@
 use super::client::GogsClient;
-use super::types::Repository;
+use super::types::{ArchiveFormat, CreateRepoParams, EditRepoParams, Fork, MigrateRepoParams, Repository};
 use anyhow::Result;
+use std::collections::HashSet;
+use std::path::Path;
 
 impl GogsClient {
     pub async fn list_user_repos(&self) -> Result<Vec<Repository>> {
-        let path = "/user/repos";
-        let resp = self.get(path).await?;
-        let repos: Vec<Repository> = resp.json().await?;
+        self.list_user_repos_limited(None).await
+    }
+
+    /// List accessible repositories, fetching no more pages than needed for `limit`.
+    pub async fn list_user_repos_limited(&self, limit: Option<usize>) -> Result<Vec<Repository>> {
+        self.get_paginated("/user/repos", limit).await
+    }
+
+    /// The user's repositories plus, with `include_orgs`, every repository of
+    /// the organizations they belong to, each listed once. `/user/repos` can
+    /// leave out org repositories reached only through a team.
+    pub async fn list_accessible_repos(&self, include_orgs: bool) -> Result<Vec<Repository>> {
+        let mut repos = self.list_user_repos().await?;
+        if !include_orgs {
+            return Ok(repos);
+        }
+
+        let mut seen: HashSet<i64> = repos.iter().map(|r| r.id).collect();
+        for org in self.list_user_orgs().await? {
+            match self.list_org_repos(&org.username, None).await {
+                Ok(org_repos) => repos.extend(org_repos.into_iter().filter(|r| seen.insert(r.id))),
+                Err(e) => eprintln!("Warning: Failed to list repositories of {}: {}", org.username, e),
+            }
+        }
         Ok(repos)
     }
 
-    #[allow(dead_code)]
+    /// Forks of a repository, one level deep.
+    pub async fn list_forks(&self, owner: &str, repo: &str) -> Result<Vec<Repository>> {
+        let path = format!("/repos/{}/{}/forks", owner, repo);
+        self.get_paginated(&path, None).await
+    }
+
+    /// The forks of a repository and, with `deep`, their forks in turn. Each
+    /// fork is followed by its own forks, in name order.
+    pub async fn list_fork_tree(&self, owner: &str, repo: &str, deep: bool) -> Result<Vec<Fork>> {
+        let mut tree = Vec::new();
+        let mut seen = HashSet::new();
+        let mut stack = self.forks_at(1, &format!("{}/{}", owner, repo)).await?;
+        stack.reverse();
+
+        while let Some(fork) = stack.pop() {
+            if !seen.insert(fork.repo.id) {
+                continue;
+            }
+            if deep {
+                let children = self.forks_at(fork.depth + 1, &fork.repo.full_name).await?;
+                stack.extend(children.into_iter().rev());
+            }
+            tree.push(fork);
+        }
+        Ok(tree)
+    }
+
+    async fn forks_at(&self, depth: usize, full_name: &str) -> Result<Vec<Fork>> {
+        let (owner, name) = full_name.split_once('/').unwrap_or_default();
+        let mut forks = self.list_forks(owner, name).await?;
+        forks.sort_by(|a, b| a.full_name.cmp(&b.full_name));
+        Ok(forks
+            .into_iter()
+            .map(|repo| Fork {
+                depth,
+                forked_from: full_name.to_string(),
+                repo,
+            })
+            .collect())
+    }
+
+    /// Repositories the current user watches.
+    pub async fn list_watched_repos(&self) -> Result<Vec<Repository>> {
+        self.get_paginated("/user/subscriptions", None).await
+    }
+
+    /// Repositories the current user has starred, fetching no more pages than needed for `limit`.
+    pub async fn list_starred_repos(&self, limit: Option<usize>) -> Result<Vec<Repository>> {
+        self.get_paginated("/user/starred", limit).await
+    }
+
+    pub async fn star_repo(&self, owner: &str, repo: &str) -> Result<()> {
+        let path = format!("/user/starred/{}/{}", owner, repo);
+        self.put(&path, serde_json::json!({})).await?;
+        Ok(())
+    }
+
+    pub async fn unstar_repo(&self, owner: &str, repo: &str) -> Result<()> {
+        let path = format!("/user/starred/{}/{}", owner, repo);
+        self.delete(&path).await?;
+        Ok(())
+    }
+
+    pub async fn watch_repo(&self, owner: &str, repo: &str) -> Result<()> {
+        let path = format!("/repos/{}/{}/subscription", owner, repo);
+        self.put(&path, serde_json::json!({ "subscribed": true })).await?;
+        Ok(())
+    }
+
+    pub async fn unwatch_repo(&self, owner: &str, repo: &str) -> Result<()> {
+        let path = format!("/repos/{}/{}/subscription", owner, repo);
+        self.delete(&path).await?;
+        Ok(())
+    }
+
+    /// Search repositories visible to the current user by name.
+    pub async fn search_repos(&self, query: &str, limit: usize) -> Result<Vec<Repository>> {
+        #[derive(serde::Deserialize)]
+        struct SearchResults {
+            #[serde(default)]
+            data: Vec<Repository>,
+        }
+
+        let path = format!("/repos/search?q={}&limit={}", Self::encode_query(query), limit);
+        let resp = self.get(&path).await?;
+        let results: SearchResults = resp.json().await?;
+        Ok(results.data)
+    }
+
+    /// Create a repository owned by the current user, or by `org` when given.
+    pub async fn create_repo(&self, org: Option<&str>, params: &CreateRepoParams) -> Result<Repository> {
+        let path = match org {
+            Some(org) => format!("/org/{}/repos", org),
+            None => "/user/repos".to_string(),
+        };
+        let payload = serde_json::to_value(params)?;
+        let resp = self.post(&path, payload).await?;
+        let repository: Repository = resp.json().await?;
+        Ok(repository)
+    }
+
+    /// Import a repository from another server. The server clones it before
+    /// answering, so this can take a while.
+    pub async fn migrate_repo(&self, params: &MigrateRepoParams) -> Result<Repository> {
+        let payload = serde_json::to_value(params)?;
+        let resp = self.post("/repos/migrate", payload).await?;
+        let repository: Repository = resp.json().await?;
+        Ok(repository)
+    }
+
+    /// Change a repository's settings, returning the updated repository.
+    pub async fn edit_repo(&self, owner: &str, repo: &str, params: &EditRepoParams) -> Result<Repository> {
+        let path = format!("/repos/{}/{}", owner, repo);
+        let payload = serde_json::to_value(params)?;
+        let resp = self.patch(&path, payload).await?;
+        let repository: Repository = resp.json().await?;
+        Ok(repository)
+    }
+
+    /// Fork a repository into the current user's account, or into `org` when given.
+    pub async fn fork_repo(&self, owner: &str, repo: &str, org: Option<&str>) -> Result<Repository> {
+        let path = format!("/repos/{}/{}/forks", owner, repo);
+        let payload = match org {
+            Some(org) => serde_json::json!({ "organization": org }),
+            None => serde_json::json!({}),
+        };
+        let resp = self.post(&path, payload).await?;
+        let repository: Repository = resp.json().await?;
+        Ok(repository)
+    }
+
+    /// Delete a repository, with its issues, wiki and releases.
+    pub async fn delete_repo(&self, owner: &str, repo: &str) -> Result<()> {
+        let path = format!("/repos/{}/{}", owner, repo);
+        self.delete(&path).await?;
+        Ok(())
+    }
+
     pub async fn get_repo(&self, owner: &str, repo: &str) -> Result<Repository> {
         let path = format!("/repos/{}/{}", owner, repo);
         let resp = self.get(&path).await?;
         let repository: Repository = resp.json().await?;
         Ok(repository)
     }
+
+    /// Stream a snapshot of `git_ref` in `format` into `dest`, returning the
+    /// bytes written; see `download_to` for `progress`.
+    pub async fn download_archive(
+        &self,
+        owner: &str,
+        repo: &str,
+        git_ref: &str,
+        format: ArchiveFormat,
+        dest: &Path,
+        progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<u64> {
+        let path = format!(
+            "/repos/{}/{}/archive/{}.{}",
+            owner,
+            repo,
+            GogsClient::encode_path(git_ref),
+            format.extension()
+        );
+        self.download_to(&self.api_url(&path), dest, progress).await
+    }
 }
@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Method, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Total attempts for a retryable request unless configured otherwise.
+pub const DEFAULT_ATTEMPTS: usize = 3;
+
+/// Delay before the first retry unless configured otherwise.
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest wait between attempts unless configured otherwise, also applied to
+/// a server's `Retry-After`.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// How a command's requests are retried after a transient failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetryMode {
+    /// Retry only requests that are safe to repeat (see `is_idempotent`)
+    #[default]
+    Auto,
+    /// Retry every request, including ones that create things
+    Always,
+    /// Never retry
+    Never,
+}
+
+impl RetryMode {
+    /// Whether a request that may already have reached the server can be sent again.
+    pub fn allows(self, method: &Method, path: &str) -> bool {
+        match self {
+            RetryMode::Auto => is_idempotent(method, path),
+            RetryMode::Always => true,
+            RetryMode::Never => false,
+        }
+    }
+}
+
+/// Requests whose repetition leaves the server as a single one would: reads,
+/// deletes, field updates, and adding labels (a set union). Other POSTs create
+/// something, so repeating one can double-post a comment or issue.
+pub fn is_idempotent(method: &Method, path: &str) -> bool {
+    match *method {
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::PATCH => true,
+        Method::POST => {
+            let path = path.split('?').next().unwrap_or_default();
+            path.contains("/issues/") && path.ends_with("/labels")
+        }
+        _ => false,
+    }
+}
+
+/// Server responses worth retrying: overload and gateway errors.
+pub fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Delay before retry number `attempt` (1-based): `base`, doubling each time
+/// up to `max`. Up to half of it is random, so that the parallel requests of
+/// an `--all` command which failed together don't all retry together.
+pub fn backoff(attempt: usize, base: Duration, max: Duration) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1) as u32);
+    let delay = base.saturating_mul(factor).min(max);
+    delay / 2 + (delay / 2).mul_f64(jitter())
+}
+
+/// How long the server asked for before trying again, from a `Retry-After`
+/// header given either in seconds or as an HTTP date.
+pub fn retry_after(resp: &Response) -> Option<Duration> {
+    let value = resp.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc);
+    // A date already past means go ahead now
+    Some((at - Utc::now()).to_std().unwrap_or_default())
+}
+
+/// A random fraction in [0, 1), or 0 if the system has no randomness to give.
+fn jitter() -> f64 {
+    let mut bytes = [0u8; 8];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        return 0.0;
+    }
+    (u64::from_le_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64
+}
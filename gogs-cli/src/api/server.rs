@@ -0,0 +1,24 @@
+use super::client::GogsClient;
+use crate::error::GogsError;
+use anyhow::Result;
+use reqwest::Response;
+
+impl GogsClient {
+    /// The server's self-reported version, or `None` if it doesn't expose one.
+    pub async fn get_server_version(&self) -> Result<Option<String>> {
+        let Some(resp) = self.probe("/version").await? else {
+            return Ok(None);
+        };
+        let body: serde_json::Value = resp.json().await?;
+        Ok(body.get("version").and_then(|v| v.as_str()).map(String::from))
+    }
+
+    /// GET `path`, returning `None` when the server has no such endpoint.
+    pub async fn probe(&self, path: &str) -> Result<Option<Response>> {
+        match self.get(path).await {
+            Ok(resp) => Ok(Some(resp)),
+            Err(e) if matches!(e.downcast_ref(), Some(GogsError::NotFound(_))) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
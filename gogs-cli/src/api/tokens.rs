@@ -0,0 +1,31 @@
+use super::client::GogsClient;
+use super::types::AccessToken;
+use anyhow::Result;
+use reqwest::Method;
+
+impl GogsClient {
+    /// `username`'s API tokens. Needs their password, not a token.
+    pub async fn list_tokens(&self, username: &str, password: &str) -> Result<Vec<AccessToken>> {
+        let path = tokens_path(username);
+        let resp = self
+            .request_with_password(Method::GET, &path, None, username, password)
+            .await?;
+        let tokens: Vec<AccessToken> = resp.json().await?;
+        Ok(tokens)
+    }
+
+    /// Create an API token called `name` for `username`, signing in with their password.
+    pub async fn create_token(&self, username: &str, password: &str, name: &str) -> Result<AccessToken> {
+        let path = tokens_path(username);
+        let body = serde_json::json!({ "name": name });
+        let resp = self
+            .request_with_password(Method::POST, &path, Some(body), username, password)
+            .await?;
+        let token: AccessToken = resp.json().await?;
+        Ok(token)
+    }
+}
+
+fn tokens_path(username: &str) -> String {
+    format!("/users/{}/tokens", GogsClient::encode_path(username))
+}
@@ -1,4 +1,7 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct User {
@@ -8,6 +11,49 @@ pub struct User {
     pub email: Option<String>,
 }
 
+/// An API token. Gogs returns the token itself as `sha1`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AccessToken {
+    pub name: String,
+    pub sha1: String,
+}
+
+/// An SSH public key registered to the current user.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PublicKey {
+    pub id: i64,
+    pub key: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl PublicKey {
+    /// The key's SHA256 fingerprint as `ssh-keygen -l` prints it, e.g.
+    /// `SHA256:nThbg6kXUpJWGl7E1IGOCspRomTxdCARLviKw6E5SY8`.
+    pub fn fingerprint(&self) -> Option<String> {
+        key_fingerprint(&self.key)
+    }
+}
+
+/// Fingerprint of an OpenSSH public key line (`TYPE BASE64 [COMMENT]`), or
+/// None if it isn't one.
+pub fn key_fingerprint(key: &str) -> Option<String> {
+    use base64::Engine;
+    use sha2::{Digest, Sha256};
+
+    let blob = key.split_whitespace().nth(1)?;
+    let blob = base64::engine::general_purpose::STANDARD.decode(blob).ok()?;
+    let digest = Sha256::digest(&blob);
+    Some(format!(
+        "SHA256:{}",
+        base64::engine::general_purpose::STANDARD_NO_PAD.encode(digest)
+    ))
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Repository {
     pub id: i64,
@@ -18,6 +64,150 @@ pub struct Repository {
     pub private: bool,
     pub html_url: String,
     pub clone_url: String,
+    #[serde(default)]
+    pub ssh_url: String,
+    #[serde(default)]
+    pub website: Option<String>,
+    #[serde(default)]
+    pub default_branch: String,
+    #[serde(default)]
+    pub fork: bool,
+    /// The repository this one was forked from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Box<Repository>>,
+    #[serde(default)]
+    pub mirror: bool,
+    #[serde(default)]
+    pub empty: bool,
+    /// Size in KiB
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub stars_count: i64,
+    #[serde(default)]
+    pub forks_count: i64,
+    #[serde(default)]
+    pub watchers_count: i64,
+    #[serde(default)]
+    pub open_issues_count: i64,
+    /// What the current user may do; absent for anonymous requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Permissions>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A repository in a fork network, below the repository it was listed from.
+#[derive(Debug, Serialize, Clone)]
+pub struct Fork {
+    /// 1 for a direct fork, 2 for a fork of a fork, ...
+    pub depth: usize,
+    /// Full name of the repository this was forked from
+    pub forked_from: String,
+    #[serde(flatten)]
+    pub repo: Repository,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct Permissions {
+    #[serde(default)]
+    pub admin: bool,
+    #[serde(default)]
+    pub push: bool,
+    #[serde(default)]
+    pub pull: bool,
+}
+
+/// Payload for `POST /user/repos` and `POST /org/:org/repos`.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CreateRepoParams {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub private: bool,
+    /// Create an initial commit with a README (and any gitignores/license)
+    pub auto_init: bool,
+    /// Comma-separated .gitignore templates, e.g. "Rust,Python"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gitignores: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// README template; Gogs only ships "Default"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub readme: Option<String>,
+}
+
+/// Payload for `POST /admin/users`.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CreateUserParams {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+    /// Email the new user their account details
+    pub send_notify: bool,
+}
+
+/// Payload for `POST /admin/users/:username/orgs`.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CreateOrgParams {
+    pub username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+/// Payload for `POST /repos/migrate`.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct MigrateRepoParams {
+    /// URL to clone from
+    pub clone_addr: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_username: Option<String>,
+    /// Password or access token for the source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_password: Option<String>,
+    /// Id of the user or organization that will own the repository
+    pub uid: i64,
+    pub repo_name: String,
+    /// Keep pulling from the source instead of copying once
+    pub mirror: bool,
+    pub private: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Organization {
+    pub id: i64,
+    pub username: String,
+    #[serde(default)]
+    pub full_name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub website: Option<String>,
+}
+
+/// Payload for `PATCH /repos/:owner/:repo`; only the fields given are changed.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct EditRepoParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -27,6 +217,270 @@ pub struct Label {
     pub color: String,
 }
 
+/// Normalize a label color given as `#rrggbb`, `rrggbb` or `#rgb` to `#rrggbb`.
+pub fn parse_color(value: &str) -> std::result::Result<String, String> {
+    let hex = value.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("'{}' is not a hex color like #ee0701", value));
+    }
+    match hex.len() {
+        6 => Ok(format!("#{}", hex.to_ascii_lowercase())),
+        3 => Ok(hex
+            .chars()
+            .fold(String::from("#"), |mut out, c| {
+                out.push(c);
+                out.push(c);
+                out
+            })
+            .to_ascii_lowercase()),
+        _ => Err(format!("'{}' is not a hex color like #ee0701", value)),
+    }
+}
+
+/// A stable color for a label name, so the same label gets the same color in
+/// every repository it is created in.
+pub fn default_label_color(name: &str) -> String {
+    // FNV-1a; std's hasher is randomly seeded per process
+    let hash = name
+        .bytes()
+        .fold(0x811c9dc5u32, |h, b| (h ^ b as u32).wrapping_mul(0x01000193));
+    format!("#{:06x}", hash & 0xffffff)
+}
+
+/// What the contents API answers after committing a file.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FileCommit {
+    #[serde(default)]
+    pub content: Option<FileContents>,
+    #[serde(default)]
+    pub commit: Option<CommitRef>,
+}
+
+/// The commit a file change was made in.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CommitRef {
+    pub sha: String,
+    #[serde(default)]
+    pub html_url: Option<String>,
+}
+
+/// A file or directory entry from the repository contents API.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FileContents {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: String,
+    pub path: String,
+    #[serde(default)]
+    pub sha: String,
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+impl FileContents {
+    /// The file's bytes, decoding the base64 the API wraps them in.
+    pub fn decode(&self) -> Result<Vec<u8>> {
+        use base64::Engine;
+
+        let content = self.content.as_deref().unwrap_or_default();
+        match self.encoding.as_deref() {
+            Some("base64") => {
+                // The API wraps base64 at 60 columns
+                let compact: String = content.split_whitespace().collect();
+                base64::engine::general_purpose::STANDARD
+                    .decode(compact)
+                    .context(format!("Failed to decode contents of {}", self.path))
+            }
+            _ => Ok(content.as_bytes().to_vec()),
+        }
+    }
+}
+
+/// A file uploaded to an issue.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Attachment {
+    pub id: i64,
+    pub name: String,
+    #[serde(default)]
+    pub size: i64,
+    pub browser_download_url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueState {
+    Open,
+    Closed,
+}
+
+impl IssueState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueState::Open => "open",
+            IssueState::Closed => "closed",
+        }
+    }
+}
+
+impl fmt::Display for IssueState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A user with access to a repository, and what they may do there.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Collaborator {
+    #[serde(flatten)]
+    pub user: User,
+    #[serde(default)]
+    pub permissions: Permissions,
+}
+
+/// Access level granted to a collaborator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CollaboratorPermission {
+    Read,
+    Write,
+    Admin,
+}
+
+impl fmt::Display for CollaboratorPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CollaboratorPermission::Read => "read",
+            CollaboratorPermission::Write => "write",
+            CollaboratorPermission::Admin => "admin",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Archive format served by `/repos/:owner/:repo/archive/:ref.:ext`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ArchiveFormat {
+    #[default]
+    #[value(name = "tar.gz")]
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::Zip => "zip",
+        }
+    }
+}
+
+/// State selector for listing issues; `All` has no single API equivalent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum StateFilter {
+    #[default]
+    Open,
+    Closed,
+    All,
+}
+
+/// Label constraints for listing issues.
+#[derive(Debug, Clone, Default)]
+pub struct LabelFilter {
+    /// Labels the issue should carry
+    pub include: Vec<String>,
+    /// Labels the issue must not carry
+    pub exclude: Vec<String>,
+    /// Require every included label rather than any of them
+    pub require_all: bool,
+    /// Compare names case-sensitively
+    pub exact: bool,
+}
+
+impl LabelFilter {
+    /// Build a filter from label arguments, where a leading `!` negates a label.
+    pub fn parse(labels: &[String], require_all: bool) -> Self {
+        let mut filter = Self {
+            require_all,
+            ..Default::default()
+        };
+        for label in labels {
+            match label.strip_prefix('!') {
+                Some(name) => filter.exclude.push(name.to_string()),
+                None => filter.include.push(label.clone()),
+            }
+        }
+        filter
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn matches(&self, issue: &Issue) -> bool {
+        let has = |name: &String| issue.labels.iter().any(|l| label_name_matches(&l.name, name, self.exact));
+
+        if self.exclude.iter().any(has) {
+            return false;
+        }
+        if self.include.is_empty() {
+            return true;
+        }
+        if self.require_all {
+            self.include.iter().all(has)
+        } else {
+            self.include.iter().any(has)
+        }
+    }
+}
+
+/// Compare label names, ignoring ASCII case unless `exact`.
+pub fn label_name_matches(label: &str, name: &str, exact: bool) -> bool {
+    if exact {
+        label == name
+    } else {
+        label.eq_ignore_ascii_case(name)
+    }
+}
+
+/// Filters for listing issues.
+#[derive(Debug, Clone, Default)]
+pub struct ListIssuesParams {
+    pub state: StateFilter,
+    pub labels: LabelFilter,
+    /// Keyword that must appear in the title or body (case-insensitive)
+    pub query: Option<String>,
+    /// Username of the issue author (filtered client-side)
+    pub author: Option<String>,
+    /// Milestone the issue must (or must not) be in (filtered client-side)
+    pub milestone: Option<MilestoneFilter>,
+    /// Only issues created at or after this time (filtered client-side)
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only issues created before this time (filtered client-side)
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only issues updated at or after this time (filtered client-side)
+    pub updated_since: Option<DateTime<Utc>>,
+    /// Return at most this many issues
+    pub limit: Option<usize>,
+}
+
+impl ListIssuesParams {
+    /// Whether any filter beyond the state has to be checked against the issues themselves.
+    pub fn has_item_filters(&self) -> bool {
+        !self.labels.is_empty()
+            || self.query.is_some()
+            || self.author.is_some()
+            || self.milestone.is_some()
+            || self.created_after.is_some()
+            || self.created_before.is_some()
+            || self.updated_since.is_some()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Issue {
     pub id: i64,
@@ -35,11 +489,156 @@ pub struct Issue {
     pub body: Option<String>,
     pub user: User,
     pub labels: Vec<Label>,
-    pub state: String,
+    #[serde(default)]
+    pub assignee: Option<User>,
+    #[serde(default)]
+    pub milestone: Option<Milestone>,
+    pub state: IssueState,
     pub comments: i64,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
     pub html_url: String,
+    /// Present when the issue is a pull request, on servers that list those too
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_request: Option<PullRequestMeta>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PullRequestMeta {
+    #[serde(default)]
+    pub merged: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Release {
+    pub id: i64,
+    pub tag_name: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub prerelease: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Branch {
+    pub name: String,
+    pub commit: BranchCommit,
+}
+
+/// The commit at the head of a branch.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BranchCommit {
+    /// Full SHA
+    pub id: String,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub url: String,
+    pub author: Option<CommitUser>,
+    pub committer: Option<CommitUser>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CommitUser {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub username: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Milestone {
+    pub id: i64,
+    pub title: String,
+    pub state: IssueState,
+    #[serde(default)]
+    pub deadline: Option<DateTime<Utc>>,
+}
+
+/// Milestone constraint for listing issues.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MilestoneFilter {
+    /// Issues without a milestone
+    None,
+    /// Issues in the milestone with this title (case-insensitive) or ID
+    Matching(String),
+}
+
+impl MilestoneFilter {
+    pub fn matches(&self, issue: &Issue) -> bool {
+        match (self, &issue.milestone) {
+            (MilestoneFilter::None, milestone) => milestone.is_none(),
+            (MilestoneFilter::Matching(_), None) => false,
+            (MilestoneFilter::Matching(wanted), Some(milestone)) => {
+                milestone.title.eq_ignore_ascii_case(wanted)
+                    || wanted.parse::<i64>().is_ok_and(|id| id == milestone.id)
+            }
+        }
+    }
+}
+
+/// Payload for `POST /repos/:owner/:repo/issues`.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CreateIssueParams {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Username to assign
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// Label IDs (Gogs does not accept names here)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<i64>,
+}
+
+/// A file to commit with `PUT /repos/:owner/:repo/contents/:path`.
+#[derive(Debug, Serialize, Clone)]
+pub struct PutFileParams {
+    pub message: String,
+    /// Base64
+    pub content: String,
+    /// Default: the repository's default branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Blob being replaced. Gogs ignores it; servers that check it refuse to
+    /// overwrite a file that changed in the meantime.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha: Option<String>,
+}
+
+impl PutFileParams {
+    pub fn new(content: &[u8], message: &str) -> Self {
+        use base64::Engine;
+
+        Self {
+            message: message.to_string(),
+            content: base64::engine::general_purpose::STANDARD.encode(content),
+            branch: None,
+            sha: None,
+        }
+    }
+}
+
+/// Fields to change with `PATCH /repos/:owner/:repo/issues/:number`.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct UpdateIssueParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<IssueState>,
+    /// Username to assign; an empty string clears the assignee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -47,6 +646,8 @@ pub struct Comment {
     pub id: i64,
     pub body: String,
     pub user: User,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub html_url: Option<String>,
 }
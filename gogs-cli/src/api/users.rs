@@ -0,0 +1,37 @@
+use super::client::GogsClient;
+use super::types::User;
+use crate::error::GogsError;
+use anyhow::Result;
+
+impl GogsClient {
+    /// The user the API token belongs to.
+    pub async fn get_current_user(&self) -> Result<User> {
+        let resp = self.get("/user").await?;
+        let user: User = resp.json().await?;
+        Ok(user)
+    }
+
+    /// Look a user up by name, or `None` if there is no such user.
+    pub async fn get_user(&self, username: &str) -> Result<Option<User>> {
+        let path = format!("/users/{}", Self::encode_path(username));
+        match self.get(&path).await {
+            Ok(resp) => Ok(Some(resp.json().await?)),
+            Err(e) if matches!(e.downcast_ref(), Some(GogsError::NotFound(_))) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Search users whose username or full name contains `query`.
+    pub async fn search_users(&self, query: &str, limit: usize) -> Result<Vec<User>> {
+        #[derive(serde::Deserialize)]
+        struct SearchResults {
+            #[serde(default)]
+            data: Vec<User>,
+        }
+
+        let path = format!("/users/search?q={}&limit={}", Self::encode_query(query), limit);
+        let resp = self.get(&path).await?;
+        let results: SearchResults = resp.json().await?;
+        Ok(results.data)
+    }
+}
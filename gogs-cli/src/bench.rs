@@ -0,0 +1,149 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::api::GogsClient;
+
+/// Read endpoints `gog bench` can exercise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Endpoint {
+    /// First page of a repository's open issues
+    #[default]
+    Issues,
+    /// A repository's labels
+    Labels,
+    /// Repositories of the current user
+    Repos,
+    /// The authenticated user
+    User,
+}
+
+impl Endpoint {
+    fn path(self, repo: Option<&(String, String)>) -> String {
+        match (self, repo) {
+            (Endpoint::Issues, Some((owner, repo))) => format!("/repos/{}/{}/issues?state=open", owner, repo),
+            (Endpoint::Labels, Some((owner, repo))) => format!("/repos/{}/{}/labels", owner, repo),
+            (Endpoint::Repos, _) => "/user/repos".to_string(),
+            _ => "/user".to_string(),
+        }
+    }
+
+    /// Whether the endpoint needs `--repo` (or a default repository).
+    pub fn needs_repo(self) -> bool {
+        matches!(self, Endpoint::Issues | Endpoint::Labels)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub endpoint: String,
+    pub requests: usize,
+    pub concurrency: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Failures by error message
+    pub errors: BTreeMap<String, usize>,
+    pub elapsed_ms: f64,
+    pub requests_per_sec: f64,
+    /// Latency of successful requests
+    pub latency_ms: Option<Latency>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Latency {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// Send `requests` GETs to `endpoint`, at most `concurrency` at a time.
+///
+/// The client should not retry, or retries would hide failures and inflate
+/// latencies.
+pub async fn run(
+    client: &GogsClient,
+    endpoint: Endpoint,
+    repo: Option<&(String, String)>,
+    requests: usize,
+    concurrency: usize,
+) -> BenchReport {
+    let path = endpoint.path(repo);
+    let permits = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+
+    let started = Instant::now();
+    for _ in 0..requests {
+        let client = client.clone();
+        let path = path.clone();
+        let permits = permits.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let sent = Instant::now();
+            // Read the body too: a response isn't served until it has all arrived
+            let result = match client.get(&path).await {
+                Ok(resp) => resp.bytes().await.map(|_| ()).map_err(anyhow::Error::from),
+                Err(e) => Err(e),
+            };
+            (sent.elapsed(), result.map_err(|e| format!("{:#}", e)))
+        });
+    }
+
+    let mut latencies = Vec::with_capacity(requests);
+    let mut errors: BTreeMap<String, usize> = BTreeMap::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((elapsed, Ok(()))) => latencies.push(elapsed),
+            Ok((_, Err(e))) => *errors.entry(e).or_default() += 1,
+            Err(e) => *errors.entry(e.to_string()).or_default() += 1,
+        }
+    }
+    let elapsed = started.elapsed();
+
+    BenchReport {
+        endpoint: path,
+        requests,
+        concurrency,
+        succeeded: latencies.len(),
+        failed: errors.values().sum(),
+        errors,
+        elapsed_ms: ms(elapsed),
+        requests_per_sec: (requests as f64 / elapsed.as_secs_f64().max(f64::EPSILON) * 10.0).round() / 10.0,
+        latency_ms: latency(latencies),
+    }
+}
+
+fn latency(mut samples: Vec<Duration>) -> Option<Latency> {
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort();
+
+    // Nearest-rank percentile
+    let percentile = |p: f64| {
+        let rank = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+        ms(samples[rank.clamp(1, samples.len()) - 1])
+    };
+    let total: Duration = samples.iter().sum();
+    Some(Latency {
+        min: ms(samples[0]),
+        mean: ms(total / samples.len() as u32),
+        p50: percentile(50.0),
+        p90: percentile(90.0),
+        p95: percentile(95.0),
+        p99: percentile(99.0),
+        max: ms(samples[samples.len() - 1]),
+    })
+}
+
+/// Milliseconds, to the microsecond.
+fn ms(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
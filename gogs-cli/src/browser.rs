@@ -0,0 +1,40 @@
+use anyhow::{Context, Result};
+use std::process::{Command, Stdio};
+
+/// Open a URL in the user's default browser.
+pub fn open(url: &str) -> Result<()> {
+    let mut cmd = opener(url);
+    let status = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to launch a browser")?;
+
+    if !status.success() {
+        anyhow::bail!("Browser command exited with {}. Open {} manually.", status, url);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn opener(url: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    // The empty string is the window title `start` expects before the target
+    cmd.args(["/C", "start", "", url]);
+    cmd
+}
+
+#[cfg(target_os = "macos")]
+fn opener(url: &str) -> Command {
+    let mut cmd = Command::new("open");
+    cmd.arg(url);
+    cmd
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn opener(url: &str) -> Command {
+    let mut cmd = Command::new("xdg-open");
+    cmd.arg(url);
+    cmd
+}
@@ -0,0 +1,157 @@
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use crate::signature;
+
+/// Lines of each stream kept in a report unless told otherwise.
+pub const DEFAULT_LINES: usize = 50;
+
+/// Start of the line identifying which failure an issue reports; an HTML
+/// comment, so the web UI doesn't render it.
+const MARKER: &str = "<!-- gog-capture ";
+const MARKER_END: &str = " -->";
+
+/// CI variables worth quoting in a report, when set.
+const CI_VARS: &[&str] = &[
+    "CI",
+    "GITHUB_REPOSITORY",
+    "GITHUB_RUN_ID",
+    "GITHUB_SHA",
+    "GITHUB_REF",
+    "CI_JOB_URL",
+    "CI_COMMIT_SHA",
+    "CI_COMMIT_REF_NAME",
+    "DRONE_BUILD_LINK",
+    "DRONE_COMMIT_SHA",
+    "BUILD_URL",
+    "GIT_COMMIT",
+    "GIT_BRANCH",
+];
+
+/// A command that has run, with everything it wrote.
+#[derive(Debug)]
+pub struct Captured {
+    pub command: Vec<String>,
+    /// Exit status; 128 + the signal number for a command killed by one
+    pub status: i32,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub duration: Duration,
+}
+
+impl Captured {
+    /// The command line as a shell would take it.
+    pub fn command_line(&self) -> String {
+        self.command.iter().map(|arg| quote(arg)).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Identifies this failure across runs: the command line and exit status,
+    /// or `key` when given. Output is left out, since timestamps and paths in
+    /// it change from run to run.
+    pub fn fingerprint(&self, key: Option<&str>) -> String {
+        let source = match key {
+            Some(key) => key.to_string(),
+            None => format!("{}\n{}", self.command.join("\0"), self.status),
+        };
+        format!("{:x}", Sha256::digest(source.as_bytes()))[..12].to_string()
+    }
+
+    pub fn title(&self) -> String {
+        format!("{} failed with exit status {}", self.command_line(), self.status)
+    }
+
+    /// Markdown reporting the failure: exit status, the last `lines` lines of
+    /// stdout and stderr, and where it ran.
+    pub fn report(&self, lines: usize) -> String {
+        let mut report = format!(
+            "`{}` exited with status {} after {:.1}s.\n",
+            self.command_line(),
+            self.status,
+            self.duration.as_secs_f64()
+        );
+        for (name, bytes) in [("stderr", &self.stderr), ("stdout", &self.stdout)] {
+            report.push_str(&format!("\n### {}\n\n", name));
+            let (text, omitted) = tail(bytes, lines);
+            if text.is_empty() {
+                report.push_str("(empty)\n");
+                continue;
+            }
+            if omitted > 0 {
+                report.push_str(&format!("Last {} lines; {} earlier lines omitted.\n\n", lines, omitted));
+            }
+            report.push_str(&fenced(&text));
+        }
+
+        report.push_str("\n### Environment\n\n");
+        for (name, value) in environment() {
+            report.push_str(&format!("- {}: `{}`\n", name, value));
+        }
+        report
+    }
+}
+
+/// The line an issue body carries so later failures find it.
+pub fn marker(fingerprint: &str) -> String {
+    format!("{}fingerprint={}{}", MARKER, fingerprint, MARKER_END)
+}
+
+/// Whether `body` reports the failure with this fingerprint.
+pub fn has_marker(body: &str, fingerprint: &str) -> bool {
+    body.contains(&marker(fingerprint))
+}
+
+/// The last `lines` lines of `bytes`, and how many were dropped before them.
+fn tail(bytes: &[u8], lines: usize) -> (String, usize) {
+    let text = String::from_utf8_lossy(bytes);
+    let all: Vec<&str> = text.trim_end().lines().collect();
+    let omitted = all.len().saturating_sub(lines);
+    (all[omitted..].join("\n"), omitted)
+}
+
+/// `text` in a code block whose fence no line of it can close.
+pub fn fenced(text: &str) -> String {
+    let longest = text
+        .lines()
+        .map(|line| line.trim_start().chars().take_while(|c| *c == '`').count())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{}\n{}\n{}\n", fence, text, fence)
+}
+
+fn environment() -> Vec<(String, String)> {
+    let mut env = vec![
+        ("gog".to_string(), env!("CARGO_PKG_VERSION").to_string()),
+        (
+            "Platform".to_string(),
+            format!("{}/{}", std::env::consts::OS, std::env::consts::ARCH),
+        ),
+    ];
+    if let Some(host) = signature::hostname() {
+        env.push(("Host".to_string(), host));
+    }
+    if let Ok(dir) = std::env::current_dir() {
+        env.push(("Directory".to_string(), dir.display().to_string()));
+    }
+    for name in CI_VARS {
+        if let Ok(value) = std::env::var(name) {
+            if !value.is_empty() {
+                env.push((name.to_string(), value));
+            }
+        }
+    }
+    env
+}
+
+/// `arg` quoted for a POSIX shell when it needs to be.
+fn quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ':' | '=' | '+' | ',' | '@'));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
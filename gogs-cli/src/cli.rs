@@ -1,4 +1,12 @@
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, Utc};
+use clap::{ArgGroup, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use crate::api::{parse_color, ArchiveFormat, CollaboratorPermission, StateFilter};
+use crate::bench::Endpoint;
+use crate::output::ColorChoice;
+use crate::time::parse_date_arg;
+use crate::update::Channel;
 
 #[derive(Parser)]
 #[command(name = "gog")]
@@ -15,14 +23,137 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub json: bool,
 
+    /// Repeatable output: sorted JSON keys, issues by repo then number, labels by name
+    #[arg(long, global = true)]
+    pub canonical: bool,
+
+    /// Show absolute timestamps instead of relative times ("3h ago")
+    #[arg(long, global = true)]
+    pub absolute: bool,
+
+    /// Timezone for absolute timestamps (IANA name, e.g. Europe/London; default: local)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub tz: Option<String>,
+
+    /// Server for this invocation: a name from [servers], or a URL (which requires --token).
+    /// GOGS_SERVER or GOGS_URL, when set, take precedence over this flag
+    #[arg(long, global = true, value_name = "NAME|URL")]
+    pub server: Option<String>,
+
+    /// API token for this invocation, instead of the profile's. GOGS_TOKEN, when set, takes
+    /// precedence over this flag
+    #[arg(long, global = true)]
+    pub token: Option<String>,
+
+    /// Color labels in human output: auto (terminal, no NO_COLOR), always or never
+    #[arg(id = "output_color", long = "color", global = true, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Replace field captions in human output (State, Author, ...) from a TOML file
+    #[arg(long, global = true, value_name = "PATH")]
+    pub labels_file: Option<PathBuf>,
+
+    /// Post even past the profile's comment limit (only from a terminal)
+    #[arg(long = "override", global = true)]
+    pub override_limit: bool,
+
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Subcommand names as typed, e.g. "issue comment"
+    #[arg(skip)]
+    pub command_path: String,
+}
+
+impl Cli {
+    /// Parse the process arguments, also recording `command_path`.
+    pub fn parse_args() -> Self {
+        let matches = Self::command().get_matches();
+        let mut cli = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        // Environment beats flags beats config, so a CI job's variables hold
+        // whatever the scripts it runs pass; GOGS_URL is the name CI setups tend to use
+        let env = |name| std::env::var(name).ok().filter(|value: &String| !value.is_empty());
+        if let Some(server) = env("GOGS_SERVER").or_else(|| env("GOGS_URL")) {
+            cli.server = Some(server);
+        }
+        if let Some(token) = env("GOGS_TOKEN") {
+            cli.token = Some(token);
+        }
+
+        let mut names = Vec::new();
+        let mut current = &matches;
+        while let Some((name, sub)) = current.subcommand() {
+            names.push(name);
+            current = sub;
+        }
+        cli.command_path = names.join(" ");
+        cli
+    }
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize configuration
-    Init,
+    #[command(
+        long_about = "Initialize configuration. From a terminal this asks for everything the flags\n\
+        don't give. Given --url, --user and a token (--token or GOGS_TOKEN) it asks nothing, for\n\
+        provisioning scripts and Dockerfiles; name the profile with --profile.\n\n\
+        Example:\n  \
+        gog --profile builder init --url https://gogs.example.com --user builder-bot \\\n    \
+        --token \"$TOKEN\" --role builder --default-repo team/app --yes"
+    )]
+    Init {
+        /// Save without checking the server and token
+        #[arg(long)]
+        skip_test: bool,
+
+        /// Gogs server URL
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Gogs username for the profile (named by --profile, default "default")
+        #[arg(long)]
+        user: Option<String>,
+
+        /// Role: a preset name (see 'gog profile presets') or a description of your own
+        #[arg(long)]
+        role: Option<String>,
+
+        /// Comment signature, instead of the role's
+        #[arg(long)]
+        signature: Option<String>,
+
+        /// Default repository (owner/repo)
+        #[arg(long, value_name = "OWNER/REPO")]
+        default_repo: Option<String>,
+
+        /// Overwrite an existing config without asking
+        #[arg(long, short)]
+        yes: bool,
+
+        /// Take the server URL and default repository from the git remote 'origin'
+        #[arg(long)]
+        from_git: bool,
+    },
+
+    /// Profile management
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+
+    /// Read and change settings in the config file
+    #[command(subcommand)]
+    Config(ConfigCommand),
+
+    /// Show which account, profile and server commands run as
+    #[command(
+        long_about = "Ask the server who the active token belongs to and print that user's name and\n\
+        email, with the profile and server the token came from. Warns when the token belongs to\n\
+        someone other than the profile's configured user.\n\n\
+        Examples:\n  \
+        gog whoami\n  \
+        gog --profile builder whoami --json"
+    )]
+    Whoami,
 
     /// Issue operations
     #[command(subcommand)]
@@ -31,6 +162,252 @@ pub enum Commands {
     /// Repository operations
     #[command(subcommand)]
     Repo(RepoCommand),
+
+    /// Organization operations
+    #[command(subcommand)]
+    Org(OrgCommand),
+
+    /// Look up users on the server
+    #[command(subcommand)]
+    User(UserCommand),
+
+    /// SSH keys of your account
+    #[command(subcommand)]
+    Key(KeyCommand),
+
+    /// Create and list API tokens by signing in with your password
+    #[command(subcommand)]
+    Token(TokenCommand),
+
+    /// Keep a profile's token in the OS keyring instead of the config file
+    #[command(subcommand)]
+    Auth(AuthCommand),
+
+    /// Provision users, organizations and repositories (site administrators only)
+    #[command(subcommand)]
+    Admin(AdminCommand),
+
+    /// Branch inspection
+    #[command(subcommand)]
+    Branch(BranchCommand),
+
+    /// Read files from a repository without cloning it
+    #[command(subcommand)]
+    File(FileCommand),
+
+    /// Release assets: publish and fetch build artifacts
+    #[command(subcommand)]
+    Release(ReleaseCommand),
+
+    /// Repository label management
+    #[command(subcommand)]
+    Label(LabelCommand),
+
+    /// Repositories skipped by --all commands after failing repeatedly
+    #[command(subcommand)]
+    Quarantine(QuarantineCommand),
+
+    /// Reports that aggregate issues
+    #[command(subcommand)]
+    Report(ReportCommand),
+
+    /// What to work on next: open items assigned to you or mentioning you
+    #[command(
+        long_about = "List open items that concern the current profile's user, most pressing first:\n\
+        those assigned to you, then those mentioning @you in the description or a comment,\n\
+        each group most recently updated first. Items are marked as issues or PRs.\n\n\
+        Covers every accessible repository unless --repo or --watched narrows it. The Gogs\n\
+        API doesn't list pull requests, so against Gogs the list holds issues only; PRs\n\
+        appear on servers that return them among issues.\n\n\
+        Examples:\n  \
+        gog open-items\n  \
+        gog open-items --watched --limit 5\n  \
+        gog open-items --repo owner/project --json"
+    )]
+    OpenItems {
+        /// Only this repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Only the repositories you watch
+        #[arg(long, conflicts_with = "repo")]
+        watched: bool,
+
+        /// Also cover every repository of your organizations
+        #[arg(long, conflicts_with_all = ["repo", "watched"])]
+        include_orgs: bool,
+
+        /// Show at most N items
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// Requests in flight at once
+        #[arg(long, value_name = "N", default_value_t = crate::commands::DEFAULT_JOBS, value_parser = crate::commands::parse_jobs)]
+        jobs: usize,
+    },
+
+    /// Run a command and file an issue if it fails
+    #[command(
+        long_about = "Run a command, passing its output through, and if it exits non-zero file an issue\n\
+        reporting the failure: the exit status, the last lines of stderr and stdout, and where it\n\
+        ran. gog then exits with the command's status, so it can wrap a CI step unchanged.\n\n\
+        Each report carries a fingerprint of the command line and exit status. When an open issue\n\
+        already has it, the failure is added there as a comment instead of filing a duplicate.\n\
+        --fingerprint sets the key yourself, e.g. to the job name.\n\n\
+        Examples:\n  \
+        gog capture --repo owner/project -- cargo test\n  \
+        gog capture --label ci --lines 100 -- make check\n  \
+        gog capture --fingerprint nightly-build -- ./build.sh --release"
+    )]
+    Capture {
+        /// Repository to file in (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Issue title (default: the command and its exit status)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Label new issues (can be repeated)
+        #[arg(long)]
+        label: Vec<String>,
+
+        /// Lines of stderr and stdout to include
+        #[arg(long, value_name = "N", default_value_t = crate::capture::DEFAULT_LINES)]
+        lines: usize,
+
+        /// Key identifying this failure (default: the command line and exit status)
+        #[arg(long, value_name = "KEY")]
+        fingerprint: Option<String>,
+
+        /// The command and its arguments, after --
+        #[arg(required = true, last = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+
+    /// Run a gog command once for each matching repository
+    #[command(
+        long_about = "Run a gog subcommand once for every repository matching --repos, for chores that\n\
+        have no cross-repo command of their own. In the command, {repo} becomes owner/name, and\n\
+        {owner} and {name} its parts. Repositories come from those you can access and those of\n\
+        your organizations.\n\n\
+        At most --jobs runs go at once. Each run's output is collected and printed per repository\n\
+        in name order, followed by a summary; with --json, each run also gets --json and its\n\
+        output is included as parsed JSON. Global options such as --profile apply to every run,\n\
+        and each run is checked against the profile's allowed commands as usual. The command\n\
+        exits non-zero if any run failed.\n\n\
+        Examples:\n  \
+        gog foreach --repos 'platform/*' --dry-run -- label create triaged --repo {repo}\n  \
+        gog foreach --repos 'platform/*' -- label create triaged --repo {repo}\n  \
+        gog foreach --repos 'alice/*' --repos '*/api-*' --jobs 8 -- issue list --count --repo {repo}\n  \
+        gog foreach --repos 'platform/*' --json -- file get {repo} Cargo.toml"
+    )]
+    Foreach {
+        /// Repositories to cover, as a glob on owner/name (can be repeated)
+        #[arg(long = "repos", value_name = "GLOB", required = true)]
+        repos: Vec<String>,
+
+        /// Runs in flight at once
+        #[arg(long, value_name = "N", default_value_t = crate::commands::DEFAULT_JOBS, value_parser = crate::commands::parse_jobs)]
+        jobs: usize,
+
+        /// List the commands that would run without running them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// The gog subcommand and its arguments, after --
+        #[arg(required = true, last = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+
+    /// Load-test read endpoints and report latency and errors
+    #[command(
+        long_about = "Send many concurrent read requests and report latency percentiles, throughput\n\
+        and error rates, for sizing a server before adding more agents. Requests are not retried.\n\n\
+        Examples:\n  \
+        gog bench --repo owner/project\n  \
+        gog bench --requests 200 --concurrency 16 --endpoint repos"
+    )]
+    Bench {
+        /// Total requests to send
+        #[arg(long, default_value_t = 100)]
+        requests: usize,
+
+        /// Requests in flight at once
+        #[arg(long, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Endpoint to exercise
+        #[arg(long, value_enum, default_value_t = Endpoint::Issues)]
+        endpoint: Endpoint,
+
+        /// Repository for the issues and labels endpoints (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Keep connections and recent responses warm between commands
+    #[command(
+        long_about = "Run a local daemon that holds the HTTP connection pool and a short-lived cache of\n\
+        GET responses. While it runs, every gog command sends its requests through it over a\n\
+        Unix socket, saving the connection and TLS set-up each command otherwise pays. Anything\n\
+        other than a GET empties the cache. Commands that can't reach the daemon talk to the\n\
+        server themselves; set GOGS_NO_DAEMON=1 to make them do so anyway.\n\n\
+        The daemon runs in the foreground; stop it with Ctrl-C or --stop.\n\n\
+        Examples:\n  \
+        gog daemon &\n  \
+        gog daemon --cache-ttl 0\n  \
+        gog daemon --status\n  \
+        gog daemon --stop"
+    )]
+    Daemon {
+        /// Stop the running daemon
+        #[arg(long, conflicts_with_all = ["status", "cache_ttl"])]
+        stop: bool,
+
+        /// Report whether a daemon is running and what it has served
+        #[arg(long, conflicts_with = "cache_ttl")]
+        status: bool,
+
+        /// Seconds to reuse a GET response (0 disables the cache; default 10)
+        #[arg(long, value_name = "SECS")]
+        cache_ttl: Option<u64>,
+    },
+
+    /// Show the version, and with --verbose build details and server compatibility
+    Version {
+        /// Include build metadata and probe the configured server
+        #[arg(long, short)]
+        verbose: bool,
+    },
+
+    /// Replace this binary with the latest published release
+    #[command(
+        long_about = "Download the latest gog release for this platform and install it in place.\n\n\
+        Releases come from the repository in [update] repo on the configured server. The\n\
+        binary is checked against its published SHA-256 checksum before it replaces the\n\
+        running one, and nothing is installed if the check fails.\n\n\
+        Examples:\n  \
+        gog self-update --check\n  \
+        gog self-update --channel beta"
+    )]
+    SelfUpdate {
+        /// Release channel (default: [update] channel, else stable)
+        #[arg(long, value_enum)]
+        channel: Option<Channel>,
+
+        /// Only report whether an update is available
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Diagnose configuration and connectivity problems
+    #[command(subcommand)]
+    Doctor(DoctorCommand),
+
+    /// Tools for working on gog itself
+    #[command(subcommand, hide = true)]
+    Dev(DevCommand),
 }
 
 #[derive(Subcommand)]
@@ -41,35 +418,153 @@ pub enum IssueCommand {
         Examples:\n  \
         gog issue list --all\n  \
         gog issue list --repo owner/project\n  \
-        gog issue list --all --label bug"
+        gog issue list --all --state all\n  \
+        gog issue list --all --label bug\n  \
+        gog issue list --all --label bug --label ready --label-all\n  \
+        gog issue list --all --label '!wontfix'\n  \
+        gog issue list --all --author @me\n  \
+        gog issue list --all --updated-since 7d\n  \
+        gog issue list --repo owner/project --created-after 2024-01-01\n  \
+        gog issue list --all --count\n  \
+        gog issue list --all --sort updated --desc\n  \
+        gog issue list --repo owner/project --limit 20"
     )]
     List {
         /// List issues across all repositories
         #[arg(long)]
         all: bool,
 
-        /// Only show open issues (default)
-        #[arg(long, conflicts_with = "closed")]
-        open: bool,
+        /// With --all, also cover every repository of your organizations
+        #[arg(long, requires = "all")]
+        include_orgs: bool,
+
+        /// List issues across the repositories you watch
+        #[arg(long, conflicts_with_all = ["all", "repo"])]
+        watched: bool,
 
-        /// Only show closed issues
+        /// Repositories queried at once with --all or --watched
+        #[arg(long, value_name = "N", default_value_t = crate::commands::DEFAULT_JOBS, value_parser = crate::commands::parse_jobs)]
+        jobs: usize,
+
+        /// Show the last cached listing without contacting the server
         #[arg(long)]
+        offline: bool,
+
+        /// Issue state to list
+        #[arg(long, value_enum, default_value_t = StateFilter::Open, conflicts_with_all = ["open", "closed"])]
+        state: StateFilter,
+
+        /// Alias for --state open
+        #[arg(long, hide = true, conflicts_with = "closed")]
+        open: bool,
+
+        /// Alias for --state closed
+        #[arg(long, hide = true)]
         closed: bool,
 
         /// Specific repository (owner/repo)
         #[arg(long)]
         repo: Option<String>,
 
-        /// Filter by label (can be repeated)
+        /// Filter by label (can be repeated, prefix with ! to exclude)
+        #[arg(long)]
+        label: Vec<String>,
+
+        /// Require every --label to match (default: any)
+        #[arg(long)]
+        label_all: bool,
+
+        /// Only issues opened by this user (@me for the current profile)
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Only issues in this milestone (title or ID)
+        #[arg(long, value_name = "NAME|ID", conflicts_with = "no_milestone")]
+        milestone: Option<String>,
+
+        /// Only issues without a milestone
+        #[arg(long)]
+        no_milestone: bool,
+
+        /// Only issues created on or after DATE (YYYY-MM-DD, RFC 3339, or age like 7d)
+        #[arg(long, value_name = "DATE", value_parser = parse_date_arg)]
+        created_after: Option<DateTime<Utc>>,
+
+        /// Only issues created before DATE
+        #[arg(long, value_name = "DATE", value_parser = parse_date_arg)]
+        created_before: Option<DateTime<Utc>>,
+
+        /// Only issues updated on or after DATE
+        #[arg(long, value_name = "DATE", value_parser = parse_date_arg)]
+        updated_since: Option<DateTime<Utc>>,
+
+        /// Sort issues (across repositories with --all)
+        #[arg(long, value_enum)]
+        sort: Option<IssueSort>,
+
+        /// Sort in descending order
+        #[arg(long, requires = "sort")]
+        desc: bool,
+
+        /// Show at most N issues in total
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// Print issue counts instead of the issues themselves
+        #[arg(long)]
+        count: bool,
+
+        /// With --count, print only the total as a single number
+        #[arg(long, short, requires = "count")]
+        quiet: bool,
+    },
+
+    /// Search issue titles and bodies
+    #[command(
+        long_about = "Search issue titles and bodies for a keyword (case-insensitive).\n\n\
+        The query is sent to the server where supported and checked locally either way.\n\
+        Words of the form label:NAME or label:!NAME act like --label.\n\n\
+        Examples:\n  \
+        gog issue search timeout --repo owner/project\n  \
+        gog issue search \"race condition\" --all --state all\n  \
+        gog issue search oauth --all --label bug\n  \
+        gog issue search \"crash label:bug label:!wontfix\" --all"
+    )]
+    Search {
+        /// Text to search for
+        query: String,
+
+        /// Search across all repositories
+        #[arg(long)]
+        all: bool,
+
+        /// Repositories queried at once with --all
+        #[arg(long, value_name = "N", default_value_t = crate::commands::DEFAULT_JOBS, value_parser = crate::commands::parse_jobs)]
+        jobs: usize,
+
+        /// Issue state to search
+        #[arg(long, value_enum, default_value_t = StateFilter::Open)]
+        state: StateFilter,
+
+        /// Specific repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Filter by label (can be repeated, prefix with ! to exclude)
         #[arg(long)]
         label: Vec<String>,
+
+        /// Require every --label to match (default: any)
+        #[arg(long)]
+        label_all: bool,
     },
 
     /// Show issue details
     #[command(
         long_about = "Show detailed information about an issue including comments.\n\n\
         Examples:\n  \
-        gog issue show 42 --repo owner/project"
+        gog issue show 42 --repo owner/project\n  \
+        gog issue show 42 --repo owner/project --web"
     )]
     Show {
         /// Issue number
@@ -78,14 +573,29 @@ pub enum IssueCommand {
         /// Repository (owner/repo)
         #[arg(long)]
         repo: Option<String>,
+
+        /// Open the issue in the browser (prints the URL with --json)
+        #[arg(long)]
+        web: bool,
+
+        /// Show the last cached copy without contacting the server
+        #[arg(long)]
+        offline: bool,
     },
 
     /// Create a new issue
     #[command(
         long_about = "Create a new issue in a repository.\n\n\
+        Without --body, an interactive terminal opens $VISUAL or $EDITOR to write one.\n\
+        --template NAME starts from a template: a [templates] entry in the config, else the\n\
+        repository's .gogs/ISSUE_TEMPLATE/NAME.md (or .gogs/ISSUE_TEMPLATE.md for \"default\").\n\n\
         Examples:\n  \
         gog issue create \"Fix bug\" --repo owner/project\n  \
-        gog issue create \"New feature\" --repo owner/project --body \"Details here\""
+        gog issue create \"New feature\" --repo owner/project --body \"Details here\"\n  \
+        generate-report | gog issue create \"Nightly report\" --repo owner/project --body-file -\n  \
+        gog issue create \"Follow-up: tests\" --repo owner/project --claim\n  \
+        gog issue create \"Login fails\" --repo owner/project --template bug\n  \
+        gog issue create \"Crash on start\" --repo owner/project --attach crash.log --attach screen.png"
     )]
     Create {
         /// Issue title
@@ -95,82 +605,1656 @@ pub enum IssueCommand {
         #[arg(long)]
         repo: Option<String>,
 
-        /// Issue body
-        #[arg(long)]
+        /// Issue body (opens $EDITOR when omitted in a terminal)
+        #[arg(long, conflicts_with = "body_file")]
         body: Option<String>,
 
+        /// Read the body from a file ("-" for stdin)
+        #[arg(long, value_name = "PATH")]
+        body_file: Option<PathBuf>,
+
+        /// Start the body from a named issue template
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["body", "body_file"])]
+        template: Option<String>,
+
         /// Add labels (can be repeated)
         #[arg(long)]
         label: Vec<String>,
+
+        /// Match --label names case-sensitively
+        #[arg(long)]
+        exact: bool,
+
+        /// Create any --label that doesn't exist in the repository yet
+        #[arg(long)]
+        create_missing: bool,
+
+        /// Check the body against configured lint rules before creating
+        #[arg(long)]
+        lint: bool,
+
+        /// Assign the issue to yourself and add the in-progress label
+        #[arg(long, conflicts_with = "assign")]
+        claim: bool,
+
+        /// Assign the issue to a user (@me for the current profile)
+        #[arg(long, value_name = "USER")]
+        assign: Option<String>,
+
+        /// Upload a file to the issue and link it from the body (can be repeated)
+        #[arg(long, value_name = "FILE")]
+        attach: Vec<PathBuf>,
+
+        /// Open the new issue in the browser
+        #[arg(long)]
+        web: bool,
+
+        /// Post the body without the profile's signature
+        #[arg(long)]
+        no_signature: bool,
     },
 
-    /// Add comment to issue
+    /// Check an issue body against lint rules
     #[command(
-        long_about = "Add a comment to an existing issue.\n\n\
+        long_about = "Check an issue body against the [lint] rules in the config file.\n\n\
+        Reports missing required sections, bodies over the maximum length, \
+        forbidden phrases and missing metadata front-matter.\n\n\
         Examples:\n  \
-        gog issue comment 42 \"Working on this\" --repo owner/project"
+        gog issue lint 42 --repo owner/project\n  \
+        gog issue lint 42 --repo owner/project --json"
     )]
-    Comment {
+    Lint {
         /// Issue number
         number: i64,
 
-        /// Comment text
-        text: String,
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
 
+    /// Export issues with their comments as JSON
+    #[command(
+        long_about = "Write a repository's issues, each with its comments, as one JSON document.\n\n\
+        --scrub pseudonymizes the export for sharing outside the team: usernames become\n\
+        user-1, user-2, ... (consistently, including @mentions in text), and email\n\
+        addresses, full names and attachment links are removed.\n\n\
+        Examples:\n  \
+        gog issue export --repo owner/project --output issues.json\n  \
+        gog issue export --repo owner/project --state open --scrub > shared.json"
+    )]
+    Export {
         /// Repository (owner/repo)
         #[arg(long)]
         repo: Option<String>,
+
+        /// Issue state to export
+        #[arg(long, value_enum, default_value_t = StateFilter::All)]
+        state: StateFilter,
+
+        /// Pseudonymize users and strip emails and attachments
+        #[arg(long)]
+        scrub: bool,
+
+        /// Write to this file instead of stdout
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<PathBuf>,
     },
 
-    /// Close an issue
-    Close {
+    /// Edit an issue's title or body
+    #[command(
+        long_about = "Update the title and/or body of an existing issue.\n\n\
+        The new body replaces the old one and is prefixed with your signature. Before it does,\n\
+        the change is shown as a diff and you are asked to confirm; scripts must pass --yes.\n\
+        The old body is recorded in the journal first.\n\n\
+        Examples:\n  \
+        gog issue edit 42 --title \"Fix login bug\" --repo owner/project\n  \
+        gog issue edit 42 --body-file notes.md --repo owner/project\n  \
+        cat notes.md | gog issue edit 42 --body-file - --repo owner/project --yes"
+    )]
+    #[command(group(ArgGroup::new("changes").required(true).multiple(true).args(["title", "body", "body_file"])))]
+    Edit {
         /// Issue number
         number: i64,
 
         /// Repository (owner/repo)
         #[arg(long)]
         repo: Option<String>,
+
+        /// New title
+        #[arg(long)]
+        title: Option<String>,
+
+        /// New body
+        #[arg(long, conflicts_with = "body_file")]
+        body: Option<String>,
+
+        /// Read the new body from a file ("-" for stdin)
+        #[arg(long, value_name = "PATH")]
+        body_file: Option<PathBuf>,
+
+        /// Replace the body without asking for confirmation
+        #[arg(long)]
+        yes: bool,
     },
 
-    /// Reopen an issue
-    Reopen {
+    /// Replace the text of a comment
+    #[command(
+        long_about = "Replace the text of a comment on an issue. Your own comments keep your signature.\n\n\
+        Comments by other users (which admin tokens can edit) are refused unless you pass\n\
+        --force-other-author. A forced edit gains a line saying who edited it, and is refused\n\
+        outright for profiles with forbid_other_authors set, as agent presets have. Every edit\n\
+        is recorded in the journal with the previous text.\n\n\
+        Examples:\n  \
+        gog issue edit-comment 42 1017 --body \"Fixed link\" --repo owner/project\n  \
+        gog issue edit-comment 42 1020 --body-file redacted.md --force-other-author"
+    )]
+    #[command(group(ArgGroup::new("text").required(true).args(["body", "body_file"])))]
+    EditComment {
         /// Issue number
         number: i64,
 
+        /// Comment id, as shown by `issue show --json`
+        comment: i64,
+
         /// Repository (owner/repo)
         #[arg(long)]
         repo: Option<String>,
+
+        /// New text
+        #[arg(long)]
+        body: Option<String>,
+
+        /// Read the new text from a file ("-" for stdin)
+        #[arg(long, value_name = "PATH")]
+        body_file: Option<PathBuf>,
+
+        /// Edit the comment even though another user wrote it
+        #[arg(long)]
+        force_other_author: bool,
     },
 
-    /// Add label to issue
-    Label {
+    /// Delete a comment
+    #[command(
+        long_about = "Delete a comment on an issue. Comments by other users are refused unless you pass\n\
+        --force-other-author, and refused outright for profiles with forbid_other_authors set.\n\
+        Every deletion is recorded in the journal with the deleted text.\n\n\
+        Examples:\n  \
+        gog issue delete-comment 42 1017 --repo owner/project\n  \
+        gog issue delete-comment 42 1020 --force-other-author"
+    )]
+    DeleteComment {
         /// Issue number
         number: i64,
 
-        /// Label name
-        label: String,
+        /// Comment id, as shown by `issue show --json`
+        comment: i64,
 
         /// Repository (owner/repo)
         #[arg(long)]
         repo: Option<String>,
+
+        /// Delete the comment even though another user wrote it
+        #[arg(long)]
+        force_other_author: bool,
     },
 
-    /// Remove label from issue
-    Unlabel {
+    /// Vote for an issue
+    #[command(
+        long_about = "Vote for an issue by commenting \"+1\" under your signature. Each user and\n\
+        signature counts once, so voting again does nothing. Votes rank issues in\n\
+        `gog report top`.\n\n\
+        Examples:\n  \
+        gog issue vote 42 --repo owner/project\n  \
+        gog issue vote 42 --reason \"Blocks the release build\""
+    )]
+    Vote {
         /// Issue number
         number: i64,
 
-        /// Label name
-        label: String,
-
         /// Repository (owner/repo)
         #[arg(long)]
         repo: Option<String>,
+
+        /// Why this matters, posted on the line after the vote
+        #[arg(long)]
+        reason: Option<String>,
     },
-}
 
-#[derive(Subcommand)]
-pub enum RepoCommand {
-    /// List repositories accessible to the current profile
-    List,
+    /// Check which comments on an issue were signed by which agent
+    #[command(
+        long_about = "Check each comment's signature line against the signing keys of your profiles\n\
+        and of [trusted_keys] in the config. A comment is verified when one of those keys\n\
+        signed exactly this text, on this issue, posted from this account; unsigned when it\n\
+        has no signature line; invalid when no key produced its signature, e.g. because it\n\
+        was edited, copied from another issue or forged.\n\n\
+        Exits non-zero if any comment is invalid.\n\n\
+        Examples:\n  \
+        gog issue verify 42\n  \
+        gog issue verify 42 --repo owner/project --json"
+    )]
+    Verify {
+        /// Issue number
+        number: i64,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Add comment to issue
+    #[command(
+        long_about = "Add a comment to an existing issue.\n\n\
+        Follow-up actions run after the comment is posted, so the comment explains them.\n\
+        Without TEXT, an interactive terminal opens $VISUAL or $EDITOR to write it.\n\n\
+        Examples:\n  \
+        gog issue comment 42 \"Working on this\" --repo owner/project\n  \
+        gog issue comment 42 --body-file results.md --repo owner/project\n  \
+        gog issue comment 42 \"Fixed in abc123\" --then-close --repo owner/project\n  \
+        gog issue comment 42 \"Ready\" --then-label needs-review --repo owner/project"
+    )]
+    Comment {
+        /// Issue number
+        number: i64,
+
+        /// Comment text (opens $EDITOR when omitted in a terminal)
+        #[arg(conflicts_with = "body_file")]
+        text: Option<String>,
+
+        /// Read the comment from a file ("-" for stdin)
+        #[arg(long, value_name = "PATH")]
+        body_file: Option<PathBuf>,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Close the issue after commenting
+        #[arg(long)]
+        then_close: bool,
+
+        /// Add a label after commenting (can be repeated)
+        #[arg(long, value_name = "LABEL")]
+        then_label: Vec<String>,
+
+        /// Post the comment without the profile's signature
+        #[arg(long)]
+        no_signature: bool,
+    },
+
+    /// Post stdin as one comment that grows while a job runs
+    #[command(
+        long_about = "Read lines from stdin and keep them in a single progress comment, a live log of a\n\
+        job in the tracker. Lines are passed through to stdout, so the stream can sit in the\n\
+        middle of a pipeline. The comment is posted with the first lines and then edited with new\n\
+        ones at most once per --interval. Once the log passes --max-bytes its oldest lines are\n\
+        dropped, so the comment shows the latest output. The last lines are written when stdin\n\
+        closes, or on Ctrl-C.\n\n\
+        Examples:\n  \
+        cargo build 2>&1 | gog issue stream 42 --repo owner/project\n  \
+        ./long-job.sh | gog issue stream 42 --interval 30 --title \"Nightly import\""
+    )]
+    Stream {
+        /// Issue number
+        number: i64,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Seconds between edits of the comment
+        #[arg(long, value_name = "SECS", default_value_t = crate::commands::stream::DEFAULT_INTERVAL)]
+        interval: u64,
+
+        /// Bytes of log to keep in the comment
+        #[arg(long, value_name = "N", default_value_t = crate::commands::stream::DEFAULT_MAX_BYTES)]
+        max_bytes: usize,
+
+        /// Heading for the comment (default: "Live log")
+        #[arg(long)]
+        title: Option<String>,
+    },
+
+    /// Hand an issue over to another user or agent
+    #[command(
+        long_about = "Reassign an issue and record why, in one step.\n\n\
+        Posts a handoff comment with your note and a digest of the issue's state,\n\
+        swaps the claim label (default \"in-progress\") for the handoff label\n\
+        (default \"handoff\"), and assigns the issue to the new owner.\n\n\
+        Examples:\n  \
+        gog issue handoff 42 --to alice --note \"Blocked on DB access\" --repo owner/project\n  \
+        gog issue handoff 42 --to bot-opus --repo owner/project"
+    )]
+    Handoff {
+        /// Issue number
+        number: i64,
+
+        /// New owner (@me for the current profile)
+        #[arg(long, value_name = "USER")]
+        to: String,
+
+        /// Context for the new owner, included in the handoff comment
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Release an issue: clear its assignee and claim label
+    UnassignAll {
+        /// Issue number
+        number: i64,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Close an issue, or every issue matching filters
+    #[command(
+        long_about = "Close an issue, optionally explaining why in a signed comment.\n\n\
+        Without a number, closes every open issue matching --label/--older-than in --repo\n\
+        (or across repositories with --all). The matching issues are listed first, and you\n\
+        are asked to confirm unless --yes is given. --dry-run only lists them.\n\n\
+        Examples:\n  \
+        gog issue close 42 --repo owner/project\n  \
+        gog issue close 42 --comment \"Duplicate of #40\" --repo owner/project\n  \
+        gog issue close --label wontfix --older-than 90d --all --dry-run\n  \
+        gog issue close --label wontfix --older-than 90d --all --yes --comment \"Closing stale issue\""
+    )]
+    #[command(group(ArgGroup::new("filters").multiple(true).args(["label", "older_than"])))]
+    Close {
+        /// Issue number (omit to close by filter)
+        #[arg(required_unless_present = "filters", conflicts_with_all = ["filters", "all"])]
+        number: Option<i64>,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Post this comment before closing
+        #[arg(long, value_name = "TEXT")]
+        comment: Option<String>,
+
+        /// Close issues carrying this label (can be repeated, all must match)
+        #[arg(long)]
+        label: Vec<String>,
+
+        /// Close issues created before DATE (YYYY-MM-DD, RFC 3339, or age like 90d)
+        #[arg(long, value_name = "DATE", value_parser = parse_date_arg)]
+        older_than: Option<DateTime<Utc>>,
+
+        /// Match issues across all repositories
+        #[arg(long, conflicts_with = "repo")]
+        all: bool,
+
+        /// Repositories queried at once with --all
+        #[arg(long, value_name = "N", default_value_t = crate::commands::DEFAULT_JOBS, value_parser = crate::commands::parse_jobs)]
+        jobs: usize,
+
+        /// List the issues that would be closed without closing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Close without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Reopen an issue
+    #[command(
+        long_about = "Reopen an issue, optionally explaining why in a signed comment.\n\n\
+        Examples:\n  \
+        gog issue reopen 42 --repo owner/project\n  \
+        gog issue reopen 42 --comment \"Still failing on ARM\" --repo owner/project"
+    )]
+    Reopen {
+        /// Issue number
+        number: i64,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Post this comment before reopening
+        #[arg(long, value_name = "TEXT")]
+        comment: Option<String>,
+    },
+
+    /// Add label to issue
+    Label {
+        /// Issue number
+        number: i64,
+
+        /// Label name
+        label: String,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Match the label name case-sensitively
+        #[arg(long)]
+        exact: bool,
+
+        /// Create the label if it doesn't exist in the repository yet
+        #[arg(long)]
+        create_missing: bool,
+    },
+
+    /// Remove label from issue
+    Unlabel {
+        /// Issue number
+        number: i64,
+
+        /// Label name
+        label: String,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Match the label name case-sensitively
+        #[arg(long)]
+        exact: bool,
+    },
+
+    /// Apply one operation to many issues read from stdin
+    #[command(
+        subcommand,
+        long_about = "Apply an operation to every issue listed on stdin.\n\n\
+        Issues are given as numbers (NUMBER or #NUMBER, in the --repo repository) or as\n\
+        owner/repo#NUMBER, separated by whitespace or commas. Operations run concurrently;\n\
+        each issue's outcome is reported, and the command fails if any of them failed.\n\n\
+        Examples:\n  \
+        echo 12 13 14 | gog issue bulk close --repo owner/project\n  \
+        gog issue list --all --label stale --json | jq -r '.[] | .repo + \"#\" + (.number|tostring)' \\\n    \
+        | gog issue bulk comment \"Closing stale issues next week\""
+    )]
+    Bulk(BulkCommand),
+}
+
+#[derive(Subcommand)]
+pub enum BulkCommand {
+    /// Close each issue
+    Close {
+        /// Repository for bare issue numbers (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Post this comment on each issue before closing it
+        #[arg(long, value_name = "TEXT")]
+        comment: Option<String>,
+
+        /// Maximum operations in flight at once
+        #[arg(long, value_name = "N", default_value_t = crate::commands::DEFAULT_JOBS, value_parser = crate::commands::parse_jobs)]
+        jobs: usize,
+    },
+
+    /// Add a label to each issue
+    Label {
+        /// Label name
+        label: String,
+
+        /// Repository for bare issue numbers (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Match the label name case-sensitively
+        #[arg(long)]
+        exact: bool,
+
+        /// Maximum operations in flight at once
+        #[arg(long, value_name = "N", default_value_t = crate::commands::DEFAULT_JOBS, value_parser = crate::commands::parse_jobs)]
+        jobs: usize,
+    },
+
+    /// Post the same comment on each issue
+    Comment {
+        /// Comment text
+        text: String,
+
+        /// Repository for bare issue numbers (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Maximum operations in flight at once
+        #[arg(long, value_name = "N", default_value_t = crate::commands::DEFAULT_JOBS, value_parser = crate::commands::parse_jobs)]
+        jobs: usize,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum IssueSort {
+    Created,
+    Updated,
+    Comments,
+    Number,
+}
+
+#[derive(Subcommand)]
+pub enum LabelCommand {
+    /// List a repository's labels
+    List {
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Create a label
+    #[command(
+        long_about = "Create a label in a repository.\n\n\
+        Colors are hex (#ee0701, ee0701 or #e01). Without --hex, one is derived from the\n\
+        name, so the same label gets the same color in every repository.\n\n\
+        Examples:\n  \
+        gog label create needs-review --repo owner/project\n  \
+        gog label create blocked --hex '#b60205' --repo owner/project"
+    )]
+    Create {
+        /// Label name
+        name: String,
+
+        /// Label color, in hex (the global --color is for output)
+        #[arg(long = "hex", value_name = "COLOR", value_parser = parse_color)]
+        color: Option<String>,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Rename or recolor a label
+    #[command(group(ArgGroup::new("changes").required(true).multiple(true).args(["new_name", "color"])))]
+    Edit {
+        /// Current label name
+        name: String,
+
+        /// New name
+        #[arg(long = "name", value_name = "NAME")]
+        new_name: Option<String>,
+
+        /// New color, in hex
+        #[arg(long = "hex", value_name = "COLOR", value_parser = parse_color)]
+        color: Option<String>,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Match the label name case-sensitively
+        #[arg(long)]
+        exact: bool,
+    },
+
+    /// Make repositories' labels match a manifest file
+    #[command(
+        long_about = "Create, rename and recolor labels so each repository matches a manifest.\n\n\
+        The manifest lists [[label]] entries with a name, an optional color and optional\n\
+        renamed_from names. Labels it doesn't mention are left alone.\n\n\
+        Examples:\n  \
+        gog label sync --from labels.toml --repos owner/a,owner/b\n  \
+        gog label sync --from labels.toml --all --dry-run"
+    )]
+    Sync {
+        /// Label manifest (TOML)
+        #[arg(long, value_name = "FILE")]
+        from: PathBuf,
+
+        /// Repositories to sync (owner/repo, comma-separated); defaults to the default repo
+        #[arg(long, value_delimiter = ',', conflicts_with = "all")]
+        repos: Vec<String>,
+
+        /// Sync every accessible repository
+        #[arg(long)]
+        all: bool,
+
+        /// Match label names case-sensitively
+        #[arg(long)]
+        exact: bool,
+
+        /// Show the changes without making them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Delete a label, removing it from every issue
+    Delete {
+        /// Label name
+        name: String,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Match the label name case-sensitively
+        #[arg(long)]
+        exact: bool,
+
+        /// Delete without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CollaboratorCommand {
+    /// List a repository's collaborators and their permissions
+    List {
+        /// Repository (owner/repo)
+        repo: String,
+    },
+
+    /// Give a user access to a repository
+    #[command(
+        long_about = "Add a user as a collaborator, e.g. to let an agent account work on a new\n\
+        repository. Running it again for an existing collaborator changes their permission.\n\n\
+        Examples:\n  \
+        gog repo collaborator add owner/project builder-bot\n  \
+        gog repo collaborator add owner/project review-bot --permission read"
+    )]
+    Add {
+        /// Repository (owner/repo)
+        repo: String,
+
+        /// Username to add
+        user: String,
+
+        /// Access to grant
+        #[arg(long, value_enum, default_value_t = CollaboratorPermission::Write)]
+        permission: CollaboratorPermission,
+    },
+
+    /// Remove a user's access to a repository
+    Remove {
+        /// Repository (owner/repo)
+        repo: String,
+
+        /// Username to remove
+        user: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print a setting
+    #[command(
+        long_about = "Print the setting at a dotted key, as written in config.toml. A section prints\n\
+        as TOML. Exits with status 2 if the setting isn't set.\n\n\
+        Examples:\n  \
+        gog config get server.url\n  \
+        gog config get profiles.builder.allowed_commands --json"
+    )]
+    Get {
+        /// Dotted key, e.g. defaults.repo
+        key: String,
+    },
+
+    /// Change a setting
+    #[command(
+        long_about = "Change the setting at a dotted key and save the config. The value is taken as\n\
+        a TOML value where the setting expects a number, boolean or list, and as text\n\
+        otherwise. Unknown keys, values of the wrong type, malformed URLs and repositories,\n\
+        and profiles or servers that don't exist are refused without changing the file.\n\
+        Comments in config.toml are not kept.\n\n\
+        Examples:\n  \
+        gog config set defaults.repo owner/project\n  \
+        gog config set cache.label_ttl 300\n  \
+        gog config set profiles.builder.allowed_commands '[\"issue\", \"label\"]'"
+    )]
+    Set {
+        /// Dotted key, e.g. defaults.repo
+        key: String,
+
+        /// New value
+        #[arg(allow_hyphen_values = true)]
+        value: String,
+    },
+
+    /// Encrypt the profiles in the config file, tokens included
+    #[command(
+        long_about = "Encrypt the [profiles] section with age (https://age-encryption.org), for shared\n\
+        machines. Without --recipient, age asks for a passphrase, and asks again whenever gog\n\
+        reads the config. With --recipient, the profiles are encrypted to those public keys and\n\
+        decrypted with --identity, a private key file. The result is decrypted once before the\n\
+        plain file is replaced. Running it again re-encrypts, e.g. to change the recipients.\n\n\
+        Examples:\n  \
+        gog config encrypt\n  \
+        gog config encrypt --recipient age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p \\\n    \
+        --identity /home/me/.config/age/key.txt"
+    )]
+    Encrypt {
+        /// age public key to encrypt to (can be repeated)
+        #[arg(long, value_name = "AGE_KEY")]
+        recipient: Vec<String>,
+
+        /// age identity file that decrypts for one of the recipients
+        #[arg(long, value_name = "PATH", requires = "recipient")]
+        identity: Option<PathBuf>,
+    },
+
+    /// Write the profiles back to the config file unencrypted
+    Decrypt,
+
+    /// Check the config file, and every profile's token against the server
+    #[command(
+        long_about = "Check that config.toml parses, flag settings gog doesn't know (they are ignored),
+        and check URLs, the default repository and profile, the servers profiles name, and any
+        .gog.toml above the working directory. Then ask the server who each profile's token
+        belongs to, and whether the default repository exists. Each problem comes with what to
+        do about it. Exits non-zero if anything failed; warnings alone don't.
+
+        Examples:
+          gog config check
+          gog config check --offline --json"
+    )]
+    Check {
+        /// Only check the files; don't contact any server
+        #[arg(long)]
+        offline: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProfileCommand {
+    /// Add a profile to the existing config
+    #[command(
+        long_about = "Add a profile to the existing config, set up from a role preset.\n\n\
+        A preset sets the role, signature, labels added to new issues, which commands the\n\
+        profile may run, and whether closing mentions the issue author. See\n\
+        'gog profile presets' for what each one sets.\n\n\
+        Examples:\n  \
+        gog profile add builder --preset builder --user builder-bot\n  \
+        gog profile add review --preset reviewer --user review-bot --signature '[Review:2]'"
+    )]
+    Add {
+        /// Profile name
+        name: String,
+
+        /// Role preset (human, planner, builder, reviewer, triage, or one from the config)
+        #[arg(long, default_value = "human")]
+        preset: String,
+
+        /// Gogs username (prompted for if omitted)
+        #[arg(long)]
+        user: Option<String>,
+
+        /// API token (prompted for if omitted)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Override the preset's signature
+        #[arg(long)]
+        signature: Option<String>,
+
+        /// Make this the default profile
+        #[arg(long)]
+        default: bool,
+
+        /// Save without checking the server and token
+        #[arg(long)]
+        skip_test: bool,
+    },
+
+    /// List the available role presets
+    Presets,
+
+    /// Give a profile a key to sign its comments with
+    #[command(
+        long_about = "Generate a random signing key for a profile and save it as signing_key. From\n\
+        then on every comment the profile posts ends with a signature line that\n\
+        'gog issue verify' checks. Prints the key so it can be added to [trusted_keys]\n\
+        on the machines that verify.\n\n\
+        Examples:\n  \
+        gog profile keygen builder\n  \
+        gog profile keygen builder --replace"
+    )]
+    Keygen {
+        /// Profile name
+        name: String,
+
+        /// Replace an existing key; comments signed with the old one stop verifying
+        #[arg(long)]
+        replace: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DoctorCommand {
+    /// Check DNS, TCP, TLS and the API endpoint step by step
+    #[command(
+        long_about = "Check each step between gog and the server: the configured URL, DNS\n\
+        resolution, the TCP connection, the TLS certificate (validity and expiry), and\n\
+        the API endpoint at server.api_prefix, with latency for each.\n\n\
+        Stops at the first failing step and suggests a fix. Exits non-zero on failure.\n\n\
+        Examples:\n  \
+        gog doctor network\n  \
+        gog doctor network --json"
+    )]
+    Network,
+}
+
+#[derive(Subcommand)]
+pub enum ReportCommand {
+    /// Rank open issues by votes, then recent activity
+    #[command(
+        long_about = "Rank open issues by score = votes + recency. Votes are \"+1\" comments\n\
+        (see `gog issue vote`), counted once per user and signature. Recency is 1.0 for an\n\
+        issue updated just now and halves every --half-life days, so it only orders issues\n\
+        with equal votes.\n\n\
+        Examples:\n  \
+        gog report top --repo owner/project\n  \
+        gog report top --all --limit 20 --json"
+    )]
+    Top {
+        /// Rank issues across all repositories
+        #[arg(long, conflicts_with = "repo")]
+        all: bool,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Show the N highest-ranked issues
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        limit: usize,
+
+        /// Days for the recency bonus to halve
+        #[arg(long, value_name = "DAYS", default_value_t = crate::votes::DEFAULT_HALF_LIFE_DAYS)]
+        half_life: f64,
+
+        /// Requests in flight at once
+        #[arg(long, value_name = "N", default_value_t = crate::commands::DEFAULT_JOBS, value_parser = crate::commands::parse_jobs)]
+        jobs: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DevCommand {
+    /// Report which server API routes gog implements
+    #[command(
+        long_about = "Compare the endpoints the API client calls against the bundled OpenAPI\n\
+        spec (spec/gogs-api-v1.json) and list the routes that aren't implemented yet.\n\n\
+        Examples:\n  \
+        gog dev coverage\n  \
+        gog dev coverage --tag issue --all"
+    )]
+    Coverage {
+        /// Only routes with this tag (e.g. repository, issue, user)
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// List implemented routes as well
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum QuarantineCommand {
+    /// List quarantined repositories and those on their way there
+    #[command(
+        long_about = "List repositories that failed with \"not found\" or \"access denied\" in commands\n\
+        covering every repository. After 3 such runs in a row a repository is quarantined:\n\
+        skipped without a warning for an hour, then tried again. Each further failure doubles\n\
+        the wait, up to a day, and a success releases it.\n\n\
+        Examples:\n  \
+        gog quarantine list\n  \
+        gog quarantine list --json"
+    )]
+    List,
+
+    /// Release repositories from quarantine so the next run tries them
+    #[command(
+        long_about = "Forget a repository's failures, or every repository's, so the next command\n\
+        covering every repository tries it again.\n\n\
+        Examples:\n  \
+        gog quarantine clear owner/sandbox\n  \
+        gog quarantine clear"
+    )]
+    Clear {
+        /// Repository (owner/repo); default: all of them
+        repo: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FileCommand {
+    /// Print a file's contents
+    #[command(
+        long_about = "Fetch a file through the contents API and write its bytes, decoded, to stdout,\n\
+        e.g. to read a project manifest without cloning. Binary files come out unchanged.\n\
+        With --json, prints the API's entry instead: name, path, SHA, size and base64 content.\n\n\
+        Examples:\n  \
+        gog file get owner/project Cargo.toml\n  \
+        gog file get owner/project docs/setup.md --ref v1.2.0\n  \
+        gog file get owner/project assets/logo.png > logo.png"
+    )]
+    Get {
+        /// Repository (owner/repo)
+        repo: String,
+
+        /// Path of the file in the repository
+        path: String,
+
+        /// Branch, tag or commit (default: the default branch)
+        #[arg(long = "ref", value_name = "REF")]
+        git_ref: Option<String>,
+    },
+
+    /// Commit a file's new contents, read from stdin
+    #[command(
+        long_about = "Create or replace one file in a single commit through the contents API, reading\n\
+        the new contents from stdin, e.g. to bump a version without a clone. The commit message\n\
+        starts with the profile's signature. If the file already has exactly these contents,\n\
+        nothing is committed.\n\n\
+        Examples:\n  \
+        gog file put owner/project VERSION --message \"Bump to 1.3.0\" < VERSION\n  \
+        gog file get owner/project Cargo.toml | sed 's/^version = .*/version = \"1.3.0\"/' \\\n    \
+        | gog file put owner/project Cargo.toml --message \"Bump to 1.3.0\" --branch release"
+    )]
+    Put {
+        /// Repository (owner/repo)
+        repo: String,
+
+        /// Path of the file in the repository
+        path: String,
+
+        /// Commit message
+        #[arg(long, short)]
+        message: String,
+
+        /// Branch to commit to (default: the default branch)
+        #[arg(long)]
+        branch: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReleaseCommand {
+    /// Upload files as assets of a release
+    #[command(
+        long_about = "Upload files as assets of an existing release, e.g. build artifacts from CI.\n\
+        Files are streamed from disk, so their size doesn't matter. A file whose name the\n\
+        release already has is not uploaded again. Every file is tried; the command fails\n\
+        if any of them did.\n\n\
+        Examples:\n  \
+        gog release upload v1.2.0 target/release/gog-linux-x86_64 --repo tools/gogs-cli\n  \
+        gog release upload v1.2.0 dist/* --json"
+    )]
+    Upload {
+        /// Release tag
+        tag: String,
+
+        /// Files to upload
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Download a release's assets
+    #[command(
+        long_about = "Download a release's assets into a directory, streaming each to disk. Existing\n\
+        files of the same name are replaced. --pattern picks assets by name, with * matching\n\
+        any run of characters and ? any one; give it more than once to match any of several.\n\n\
+        Examples:\n  \
+        gog release download v1.2.0 --repo tools/gogs-cli\n  \
+        gog release download v1.2.0 --pattern 'gog-linux-*' --dir bin"
+    )]
+    Download {
+        /// Release tag
+        tag: String,
+
+        /// Only assets whose names match this glob
+        #[arg(long, value_name = "GLOB")]
+        pattern: Vec<String>,
+
+        /// Directory to write into (default: the current one)
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        dir: PathBuf,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BranchCommand {
+    /// List a repository's branches with their head commits
+    #[command(
+        long_about = "List a repository's branches with the SHA and first line of each head commit,\n\
+        e.g. to check whether a work branch already exists before creating it.\n\n\
+        Examples:\n  \
+        gog branch list --repo owner/project\n  \
+        gog branch list --json | jq -r '.[].name'"
+    )]
+    List {
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Show a branch's head commit
+    #[command(
+        long_about = "Show the commit at the head of a branch: full SHA, author, committer, date and\n\
+        the whole message. Fails with \"not found\" if the branch doesn't exist.\n\n\
+        Examples:\n  \
+        gog branch show main --repo owner/project\n  \
+        gog branch show agent/fix-login --json"
+    )]
+    Show {
+        /// Branch name
+        name: String,
+
+        /// Repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum OrgCommand {
+    /// List organizations you belong to
+    List,
+
+    /// Show an organization
+    Show {
+        /// Organization name
+        org: String,
+    },
+
+    /// List an organization's repositories
+    #[command(
+        long_about = "List every repository an organization owns that you can see, including ones\n\
+        `gog repo list` misses because you only have access through a team.\n\n\
+        Examples:\n  \
+        gog org repos platform\n  \
+        gog org repos platform --limit 20 --json"
+    )]
+    Repos {
+        /// Organization name
+        org: String,
+
+        /// Show at most N repositories
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
+
+    /// List an organization's members
+    Members {
+        /// Organization name
+        org: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum UserCommand {
+    /// Show a user, or suggest similar names if there's no such user
+    #[command(
+        long_about = "Show a user's name and email. If the user doesn't exist, usernames close to\n\
+        the one given are suggested and the exit code is 2.\n\n\
+        Examples:\n  \
+        gog user show alice\n  \
+        gog user show @me --json"
+    )]
+    Show {
+        /// Username, or @me
+        name: String,
+    },
+
+    /// Search users by username or full name
+    #[command(
+        long_about = "Search users whose username or full name contains the query.\n\n\
+        Examples:\n  \
+        gog user search ali\n  \
+        gog user search builder --limit 50 --json"
+    )]
+    Search {
+        /// Text to look for in usernames and full names
+        query: String,
+
+        /// Show at most N users
+        #[arg(long, value_name = "N", default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TokenCommand {
+    /// Create an API token
+    #[command(
+        long_about = "Create an API token by signing in with your username and password, instead\n\
+        of through the web UI. The password is asked for without echo, or read from\n\
+        GOGS_PASSWORD. Accounts with two-factor authentication can't do this and need a token\n\
+        made in the web UI.\n\n\
+        Examples:\n  \
+        gog token create\n  \
+        gog token create builder-03 --user builder-bot\n  \
+        gog --server https://gogs.example.com token create --user alice --json"
+    )]
+    Create {
+        /// Name for the token (default: gog-PROFILE)
+        name: Option<String>,
+
+        /// Account to create it for (default: the profile's user)
+        #[arg(long)]
+        user: Option<String>,
+    },
+
+    /// List the names of your API tokens
+    List {
+        /// Account whose tokens to list (default: the profile's user)
+        #[arg(long)]
+        user: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum KeyCommand {
+    /// List your SSH keys with their fingerprints
+    List,
+
+    /// Register an SSH public key
+    #[command(
+        long_about = "Register an SSH public key for your account, e.g. when setting up a new agent\n\
+        machine. The key is named after its comment (usually user@host) unless --title is given.\n\
+        A key that is already registered is reported and left alone, so this is safe to rerun.\n\n\
+        Examples:\n  \
+        gog key add ~/.ssh/id_ed25519.pub\n  \
+        gog key add ~/.ssh/id_ed25519.pub --title \"builder-03\"\n  \
+        ssh-keygen -y -f agent_key | gog key add - --title agent"
+    )]
+    Add {
+        /// Public key file ("-" for stdin)
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+
+        /// Name for the key (default: the key's comment)
+        #[arg(long)]
+        title: Option<String>,
+    },
+
+    /// Remove an SSH key
+    #[command(
+        long_about = "Remove an SSH key from your account. Machines using it lose SSH access, so you\n\
+        are asked to confirm unless --yes is given. IDs are shown by `gog key list`.\n\n\
+        Examples:\n  \
+        gog key delete 12\n  \
+        gog key delete 12 --yes"
+    )]
+    Delete {
+        /// Key ID
+        id: i64,
+
+        /// Delete without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuthCommand {
+    /// Store the profile's token in the OS keyring
+    #[command(
+        long_about = "Store the profile's API token in the OS keyring (macOS Keychain, or the Secret\n\
+        Service through secret-tool on Linux) and leave only the entry's name in the config.\n\
+        The token is --token or GOGS_TOKEN if given, else the one in the config, which is\n\
+        moved, else asked for. It is checked against the server first.\n\n\
+        Examples:\n  \
+        gog auth login                          # Move the default profile's token\n  \
+        gog --profile builder auth login\n  \
+        GOGS_TOKEN=... gog --profile builder auth login --skip-test"
+    )]
+    Login {
+        /// Store the token without checking it against the server
+        #[arg(long)]
+        skip_test: bool,
+    },
+
+    /// Remove the profile's token from the OS keyring
+    #[command(
+        long_about = "Delete the profile's token from the OS keyring and forget the entry in the config.\n\
+        The profile has no token afterwards until `gog auth login` stores one again. The token\n\
+        stays valid on the server; revoke it in the web UI (Settings > Applications) if needed.\n\n\
+        Examples:\n  \
+        gog --profile builder auth logout"
+    )]
+    Logout,
+}
+
+#[derive(Subcommand)]
+pub enum AdminCommand {
+    /// Create and delete user accounts
+    #[command(subcommand)]
+    User(AdminUserCommand),
+
+    /// Create organizations for any user
+    #[command(subcommand)]
+    Org(AdminOrgCommand),
+
+    /// Create repositories for any user or organization
+    #[command(subcommand)]
+    Repo(AdminRepoCommand),
+}
+
+#[derive(Subcommand)]
+pub enum AdminUserCommand {
+    /// Create a user account
+    #[command(
+        long_about = "Create a user account, e.g. for a new agent. The password is asked for, or taken\n\
+        from GOGS_PASSWORD in scripts. With --create-token, an API token is made for the new\n\
+        account as well and printed, ready for `gog init` or a profile's `token`.\n\n\
+        Examples:\n  \
+        gog admin user create builder-03 --email builder-03@agents.example.com\n  \
+        GOGS_PASSWORD=... gog admin user create builder-03 --email b3@example.com --create-token gog-builder --json"
+    )]
+    Create {
+        /// Username for the account
+        username: String,
+
+        /// Email address (required by Gogs)
+        #[arg(long)]
+        email: String,
+
+        /// Display name
+        #[arg(long)]
+        full_name: Option<String>,
+
+        /// Email the new user their account details
+        #[arg(long)]
+        notify: bool,
+
+        /// Also create an API token with this name for the new account
+        #[arg(long, value_name = "NAME")]
+        create_token: Option<String>,
+    },
+
+    /// Delete a user account
+    #[command(
+        long_about = "Delete a user account. Gogs refuses while the user still owns repositories or\n\
+        belongs to organizations. You are asked to type the username to confirm; scripts must\n\
+        pass --yes.\n\n\
+        Examples:\n  \
+        gog admin user delete builder-03\n  \
+        gog admin user delete builder-03 --yes"
+    )]
+    Delete {
+        /// Username of the account
+        username: String,
+
+        /// Delete without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AdminOrgCommand {
+    /// Create an organization
+    #[command(
+        long_about = "Create an organization owned by --owner, or by the current profile's user.\n\n\
+        Examples:\n  \
+        gog admin org create agents --full-name \"Agent fleet\"\n  \
+        gog admin org create platform --owner alice --description \"Platform team\""
+    )]
+    Create {
+        /// Organization name
+        name: String,
+
+        /// User who owns it (default: you)
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Display name
+        #[arg(long)]
+        full_name: Option<String>,
+
+        /// Short description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Website URL
+        #[arg(long)]
+        website: Option<String>,
+
+        /// Location
+        #[arg(long)]
+        location: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AdminRepoCommand {
+    /// Create a repository for another user or organization
+    #[command(
+        long_about = "Create a repository owned by any user or organization, without being a member.\n\n\
+        Examples:\n  \
+        gog admin repo create sandbox --owner builder-03 --private\n  \
+        gog admin repo create service-x --owner platform --init --gitignore Rust"
+    )]
+    Create {
+        /// Repository name
+        name: String,
+
+        /// User or organization to create it for
+        #[arg(long)]
+        owner: String,
+
+        /// Short description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Make the repository private
+        #[arg(long)]
+        private: bool,
+
+        /// Create an initial commit with a README
+        #[arg(long)]
+        init: bool,
+
+        /// .gitignore templates for the initial commit, comma-separated (e.g. Rust,Python)
+        #[arg(long, requires = "init", value_name = "NAMES")]
+        gitignore: Option<String>,
+
+        /// License for the initial commit (e.g. "MIT License")
+        #[arg(long, requires = "init")]
+        license: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RepoCommand {
+    /// List repositories accessible to the current profile
+    List {
+        /// Show at most N repositories
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// Only repositories you have starred
+        #[arg(long, conflicts_with = "watched")]
+        starred: bool,
+
+        /// Only repositories you watch
+        #[arg(long)]
+        watched: bool,
+    },
+
+    /// Search repositories on the server by name
+    #[command(
+        long_about = "Search all repositories visible to you on the server, not just your own.\n\n\
+        Examples:\n  \
+        gog repo search infra\n  \
+        gog repo search api --limit 50 --json"
+    )]
+    Search {
+        /// Text to look for in repository names
+        query: String,
+
+        /// Show at most N repositories
+        #[arg(long, value_name = "N", default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Show repository details
+    #[command(
+        long_about = "Show a repository's settings, size, fork status, counts and your permissions.\n\n\
+        Examples:\n  \
+        gog repo show owner/project\n  \
+        gog repo show owner/project --web\n  \
+        gog repo show --json owner/project"
+    )]
+    Show {
+        /// Repository (owner/repo; default: from config)
+        repo: Option<String>,
+
+        /// Open the repository in the browser (prints the URL with --json)
+        #[arg(long)]
+        web: bool,
+    },
+
+    /// Create a repository
+    #[command(
+        long_about = "Create a repository for the current user, or in an organization with --org.\n\n\
+        Examples:\n  \
+        gog repo create scratch --private\n  \
+        gog repo create service-x --org platform --description \"Service X\" --init --gitignore Rust"
+    )]
+    Create {
+        /// Repository name
+        name: String,
+
+        /// Create it in this organization instead of under your user
+        #[arg(long)]
+        org: Option<String>,
+
+        /// Short description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Make the repository private
+        #[arg(long)]
+        private: bool,
+
+        /// Create an initial commit with a README
+        #[arg(long)]
+        init: bool,
+
+        /// .gitignore templates for the initial commit, comma-separated (e.g. Rust,Python)
+        #[arg(long, requires = "init", value_name = "NAMES")]
+        gitignore: Option<String>,
+
+        /// License for the initial commit (e.g. "MIT License")
+        #[arg(long, requires = "init")]
+        license: Option<String>,
+    },
+
+    /// Import a repository from another server
+    #[command(
+        long_about = "Import a repository from a URL into this server, optionally as a mirror that keeps\n\
+        pulling from the source. The name defaults to the last part of the URL.\n\n\
+        For private sources, give --auth-user. The password or access token for it is read from\n\
+        GOGS_MIGRATE_PASSWORD, or asked for without echo, so it never appears on the command line.\n\n\
+        Examples:\n  \
+        gog repo migrate https://github.com/foo/bar --mirror\n  \
+        gog repo migrate https://github.com/foo/bar --name bar-import --org platform --private\n  \
+        GOGS_MIGRATE_PASSWORD=ghp_... gog repo migrate https://github.com/foo/secret --auth-user foo"
+    )]
+    Migrate {
+        /// URL of the repository to import
+        url: String,
+
+        /// Name on this server (default: from the URL)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Import into this organization instead of under your user
+        #[arg(long)]
+        org: Option<String>,
+
+        /// Keep the repository in sync with the source
+        #[arg(long)]
+        mirror: bool,
+
+        /// Make the repository private
+        #[arg(long)]
+        private: bool,
+
+        /// Short description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Username for a private source; its password comes from GOGS_MIGRATE_PASSWORD or a prompt
+        #[arg(long, value_name = "USER")]
+        auth_user: Option<String>,
+    },
+
+    /// Change a repository's description, website, default branch or visibility
+    #[command(
+        long_about = "Change repository settings. Only the options given are changed.\n\n\
+        Examples:\n  \
+        gog repo edit owner/service --description \"Service X\" --website https://x.example\n  \
+        gog repo edit owner/service --default-branch main --private\n  \
+        gog repo edit owner/service --website \"\"        # Clear the website"
+    )]
+    #[command(group(ArgGroup::new("changes").required(true).multiple(true).args(["description", "website", "default_branch", "private", "public"])))]
+    Edit {
+        /// Repository (owner/repo)
+        repo: String,
+
+        /// New description ("" clears it)
+        #[arg(long)]
+        description: Option<String>,
+
+        /// New website URL ("" clears it)
+        #[arg(long)]
+        website: Option<String>,
+
+        /// Branch that clones check out and pull requests target
+        #[arg(long, value_name = "BRANCH")]
+        default_branch: Option<String>,
+
+        /// Make the repository private
+        #[arg(long, conflicts_with = "public")]
+        private: bool,
+
+        /// Make the repository public
+        #[arg(long)]
+        public: bool,
+    },
+
+    /// Fork a repository
+    #[command(
+        long_about = "Fork a repository into your account, or into an organization with --org.\n\
+        Prints the fork's clone URLs so work can be pushed there instead of upstream.\n\n\
+        Examples:\n  \
+        gog repo fork upstream/project\n  \
+        gog repo fork upstream/project --org agents"
+    )]
+    Fork {
+        /// Repository to fork (owner/repo)
+        repo: String,
+
+        /// Fork into this organization instead of under your user
+        #[arg(long)]
+        org: Option<String>,
+    },
+
+    /// List a repository's forks
+    #[command(
+        long_about = "List the forks of a repository. With --tree, also the forks of those forks,\n\
+        showing the whole network below the repository.\n\n\
+        Examples:\n  \
+        gog repo forks upstream/project\n  \
+        gog repo forks upstream/project --tree --json"
+    )]
+    Forks {
+        /// Repository (owner/repo)
+        repo: String,
+
+        /// Follow forks of forks
+        #[arg(long)]
+        tree: bool,
+    },
+
+    /// Show the repository a fork was made from
+    #[command(
+        long_about = "Show the repository a fork was made from, e.g. to send an agent's work from its\n\
+        fork back upstream. With --root, follow the chain to the original repository.\n\
+        Fails for a repository that isn't a fork.\n\n\
+        Examples:\n  \
+        gog repo parent agent-bot/project\n  \
+        gog repo parent agent-bot/project --root --json"
+    )]
+    Parent {
+        /// Repository (owner/repo; default: the configured repository)
+        repo: Option<String>,
+
+        /// Follow parents up to the repository that isn't a fork
+        #[arg(long)]
+        root: bool,
+    },
+
+    /// Watch a repository, adding it to `issue list --watched`
+    Watch {
+        /// Repository (owner/repo)
+        repo: String,
+    },
+
+    /// Stop watching a repository
+    Unwatch {
+        /// Repository (owner/repo)
+        repo: String,
+    },
+
+    /// Download a snapshot of a repository's files
+    #[command(
+        long_about = "Download a repository at a branch, tag or commit as a tar.gz or zip archive, for\n\
+        working with its source where git isn't installed. The archive is streamed to disk, with\n\
+        progress shown on a terminal, and written under a .part name until it is complete.\n\n\
+        Examples:\n  \
+        gog repo archive owner/project\n  \
+        gog repo archive owner/project --ref v1.2.0 --format zip\n  \
+        gog repo archive owner/project --ref main -o src.tar.gz"
+    )]
+    Archive {
+        /// Repository (owner/repo)
+        repo: String,
+
+        /// Branch, tag or commit (default: the default branch)
+        #[arg(long = "ref", value_name = "REF")]
+        git_ref: Option<String>,
+
+        /// Archive format
+        #[arg(long, value_enum, default_value_t = ArchiveFormat::TarGz)]
+        format: ArchiveFormat,
+
+        /// File to write (default: REPO-REF.tar.gz or .zip in the current directory)
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+
+    /// Star a repository
+    Star {
+        /// Repository (owner/repo)
+        repo: String,
+    },
+
+    /// Remove your star from a repository
+    Unstar {
+        /// Repository (owner/repo)
+        repo: String,
+    },
+
+    /// List repositories you have starred (same as `repo list --starred`)
+    Starred {
+        /// Show at most N repositories
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
+
+    /// Manage who has access to a repository
+    #[command(subcommand)]
+    Collaborator(CollaboratorCommand),
+
+    /// Delete a repository permanently
+    #[command(
+        long_about = "Delete a repository with all its issues, wiki and releases. This cannot be undone.\n\n\
+        You are asked to type the repository's full name to confirm. Scripts must pass --yes,\n\
+        since without a terminal the command refuses to run.\n\n\
+        Examples:\n  \
+        gog repo delete owner/scratch\n  \
+        gog repo delete owner/scratch --yes"
+    )]
+    Delete {
+        /// Repository (owner/repo)
+        repo: String,
+
+        /// Delete without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
 }
@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+
+use crate::api::{CreateOrgParams, CreateRepoParams, CreateUserParams, GogsClient};
+use crate::cli::{AdminCommand, AdminOrgCommand, AdminRepoCommand, AdminUserCommand};
+use crate::commands::{prompt, prompt_password};
+use crate::config::Profile;
+use crate::editor;
+use crate::output::{
+    format_created_org, format_created_repo, format_created_user, format_deleted_user, OutputFormat,
+};
+
+pub async fn handle(cmd: AdminCommand, client: &GogsClient, profile: &Profile, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        AdminCommand::User(AdminUserCommand::Create {
+            username,
+            email,
+            full_name,
+            notify,
+            create_token,
+        }) => {
+            let password = new_password(&username)?;
+            let params = CreateUserParams {
+                username,
+                email,
+                password: password.clone(),
+                full_name,
+                send_notify: notify,
+            };
+            let user = client.admin_create_user(&params).await?;
+
+            let token = match create_token {
+                Some(name) => Some(
+                    client
+                        .create_token(&user.username, &password, &name)
+                        .await
+                        .with_context(|| {
+                            format!(
+                                "Created user {}, but not its token; run `gog token create --user {}`",
+                                user.username, user.username
+                            )
+                        })?,
+                ),
+                None => None,
+            };
+            print!("{}", format_created_user(&user, token.as_ref(), &format));
+        }
+
+        AdminCommand::User(AdminUserCommand::Delete { username, yes }) => {
+            if !yes {
+                if !editor::is_interactive() {
+                    anyhow::bail!("Refusing to delete user {} without confirmation. Pass --yes", username);
+                }
+                println!("This permanently deletes the account {}.", username);
+                let typed = prompt(&format!("Type '{}' to confirm: ", username))?;
+                if typed != username {
+                    println!("Aborted.");
+                    return Ok(());
+                }
+            }
+
+            client.admin_delete_user(&username).await?;
+            print!("{}", format_deleted_user(&username, &format));
+        }
+
+        AdminCommand::Org(AdminOrgCommand::Create {
+            name,
+            owner,
+            full_name,
+            description,
+            website,
+            location,
+        }) => {
+            let owner = owner.unwrap_or_else(|| profile.gogs_user.clone());
+            let params = CreateOrgParams {
+                username: name,
+                full_name,
+                description,
+                website,
+                location,
+            };
+            let org = client.admin_create_org(&owner, &params).await?;
+            print!("{}", format_created_org(&org, &owner, &format));
+        }
+
+        AdminCommand::Repo(AdminRepoCommand::Create {
+            name,
+            owner,
+            description,
+            private,
+            init,
+            gitignore,
+            license,
+        }) => {
+            let params = CreateRepoParams {
+                name,
+                description,
+                private,
+                auto_init: init,
+                gitignores: gitignore,
+                license,
+                readme: init.then(|| "Default".to_string()),
+            };
+            let repo = client.admin_create_repo(&owner, &params).await?;
+            print!("{}", format_created_repo(&repo, &format));
+        }
+    }
+    Ok(())
+}
+
+/// The new account's password, asked for twice at a terminal since there is
+/// no old one to check it against.
+fn new_password(username: &str) -> Result<String> {
+    let password = prompt_password(&format!("Password for {}: ", username))?;
+    if password.is_empty() {
+        anyhow::bail!("Password cannot be empty");
+    }
+    if std::env::var("GOGS_PASSWORD").is_err() && prompt_password("Again: ")? != password {
+        anyhow::bail!("Passwords don't match");
+    }
+    Ok(password)
+}
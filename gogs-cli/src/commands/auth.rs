@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+
+use crate::cli::AuthCommand;
+use crate::commands::init::test_connection;
+use crate::commands::{load_config, prompt_hidden};
+use crate::config::Config;
+use crate::editor;
+use crate::keyring;
+
+pub async fn handle(cmd: AuthCommand, profile: Option<&str>, token: Option<&str>) -> Result<()> {
+    let mut config = Config::load()?;
+    let name = config.profile_name(profile).to_string();
+    // Connect using a copy pointed at the profile's server; `config` is saved as it was
+    let active = load_config(None, Some(&name))?;
+    let entry = keyring::entry_name(&name, &active.server.url);
+    let mut updated = config
+        .profiles
+        .get(&name)
+        .with_context(|| format!("Profile '{}' not found in config", name))?
+        .clone();
+
+    match cmd {
+        AuthCommand::Login { skip_test } => {
+            let token = match token {
+                Some(token) => token.to_string(),
+                None if !updated.token.is_empty() => updated.token.clone(),
+                None if editor::is_interactive() => {
+                    prompt_hidden(&format!("API token for {}: ", updated.gogs_user))?
+                }
+                None => anyhow::bail!("No token to store: pass --token or set GOGS_TOKEN"),
+            };
+            if token.is_empty() {
+                anyhow::bail!("Token cannot be empty");
+            }
+
+            if !skip_test {
+                updated.token = token.clone();
+                println!("Testing connection to {}...", active.server.url);
+                if !test_connection(&active.client(&updated), &updated.gogs_user).await? {
+                    println!("Aborted.");
+                    return Ok(());
+                }
+            }
+
+            keyring::set(&entry, &token)?;
+            // Only drop the plaintext token once the keyring has given it back
+            if keyring::get(&entry).ok().as_deref() != Some(token.as_str()) {
+                anyhow::bail!(
+                    "The OS keyring didn't return the token just stored under '{}'; the config is unchanged",
+                    entry
+                );
+            }
+            updated.token.clear();
+            updated.keyring = Some(entry.clone());
+            config.profiles.insert(name.clone(), updated);
+            config.save().context("Failed to save configuration")?;
+
+            println!(
+                "Token for profile '{}' stored in the OS keyring as '{}'; the config keeps only that name.",
+                name, entry
+            );
+        }
+
+        AuthCommand::Logout => {
+            let Some(entry) = updated.keyring.take() else {
+                if updated.token.is_empty() {
+                    anyhow::bail!("Profile '{}' has no token to log out of", name);
+                }
+                anyhow::bail!(
+                    "Profile '{}' keeps its token in the config file, not the keyring; remove it there",
+                    name
+                );
+            };
+
+            let existed = keyring::delete(&entry)?;
+            config.profiles.insert(name.clone(), updated);
+            config.save().context("Failed to save configuration")?;
+
+            if existed {
+                println!("Removed the token for profile '{}' from the OS keyring.", name);
+            } else {
+                println!(
+                    "The OS keyring had no token under '{}'; removed the reference from profile '{}'.",
+                    entry, name
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+
+use crate::api::GogsClient;
+use crate::bench::{self, Endpoint};
+use crate::config::Config;
+use crate::output::{format_bench, OutputFormat};
+
+pub async fn handle(
+    endpoint: Endpoint,
+    requests: usize,
+    concurrency: usize,
+    repo: Option<String>,
+    client: &GogsClient,
+    config: &Config,
+    json: bool,
+) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+    if requests == 0 {
+        anyhow::bail!("--requests must be at least 1");
+    }
+
+    let repo = if endpoint.needs_repo() {
+        Some(config.get_repo(repo.as_deref())?)
+    } else {
+        None
+    };
+
+    // Retries would hide exactly the failures a benchmark is looking for, and
+    // cached or revalidated responses would time the cache, not the server
+    let client = client.clone().with_retry_attempts(1).without_response_cache();
+    let report = bench::run(&client, endpoint, repo.as_ref(), requests, concurrency).await;
+    print!("{}", format_bench(&report, &format));
+
+    if report.succeeded == 0 {
+        anyhow::bail!("Every request failed");
+    }
+    Ok(())
+}
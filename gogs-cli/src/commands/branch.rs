@@ -0,0 +1,25 @@
+use anyhow::Result;
+
+use crate::api::GogsClient;
+use crate::cli::BranchCommand;
+use crate::config::Config;
+use crate::output::{format_branch, format_branch_list, OutputFormat};
+
+pub async fn handle(cmd: BranchCommand, client: &GogsClient, config: &Config, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        BranchCommand::List { repo } => {
+            let (owner, name) = config.get_repo(repo.as_deref())?;
+            let mut branches = client.list_branches(&owner, &name).await?;
+            branches.sort_by(|a, b| a.name.cmp(&b.name));
+            print!("{}", format_branch_list(&format!("{}/{}", owner, name), &branches, &format));
+        }
+        BranchCommand::Show { name, repo } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            let branch = client.get_branch(&owner, &repo_name, &name).await?;
+            print!("{}", format_branch(&branch, &format));
+        }
+    }
+    Ok(())
+}
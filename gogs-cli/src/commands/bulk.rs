@@ -0,0 +1,227 @@
+use anyhow::{Context, Result};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::api::{GogsClient, IssueState, UpdateIssueParams};
+use crate::cli::BulkCommand;
+use crate::commands::issue::find_label;
+use crate::commands::DEFAULT_JOBS;
+use crate::config::{parse_repo, Config, Profile};
+use crate::output::{format_bulk_results, OutputFormat};
+use crate::provenance::Signer;
+use crate::shutdown;
+
+/// One issue to operate on: owner, repo, number.
+pub(crate) type Target = (String, String, i64);
+
+enum Operation {
+    Close { comment: Option<String> },
+    /// Label IDs per "owner/repo", or why the label couldn't be resolved there
+    Label { ids: HashMap<String, Result<i64, String>> },
+    Comment { body: String },
+}
+
+pub async fn handle(
+    cmd: BulkCommand,
+    client: &GogsClient,
+    config: &Config,
+    profile: &Profile,
+    format: &OutputFormat,
+) -> Result<()> {
+    let (repo, jobs) = match &cmd {
+        BulkCommand::Close { repo, jobs, .. }
+        | BulkCommand::Label { repo, jobs, .. }
+        | BulkCommand::Comment { repo, jobs, .. } => (repo.as_deref(), *jobs),
+    };
+
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .context("Failed to read issues from stdin")?;
+    // Only needed when bare numbers appear, so don't fail early without one
+    let default_repo = config.get_repo(repo).ok();
+    let targets = parse_targets(&input, default_repo.as_ref())?;
+
+    let (action, operation) = match cmd {
+        BulkCommand::Close { comment, .. } => {
+            let comment = comment.map(|text| profile.signed(&text));
+            ("close", Operation::Close { comment })
+        }
+        BulkCommand::Label { label, exact, .. } => {
+            let exact = exact || config.exact_labels();
+            let mut ids = HashMap::new();
+            for (owner, repo, _) in &targets {
+                let key = format!("{}/{}", owner, repo);
+                if let Entry::Vacant(entry) = ids.entry(key) {
+                    let id = find_label(client, owner, repo, &label, exact)
+                        .await
+                        .map(|l| l.id)
+                        .map_err(|e| format!("{:#}", e));
+                    entry.insert(id);
+                }
+            }
+            ("label", Operation::Label { ids })
+        }
+        BulkCommand::Comment { text, .. } => {
+            let body = profile.signed(&text);
+            ("comment", Operation::Comment { body })
+        }
+    };
+
+    let results = run_all(client, targets, Arc::new(operation), profile.signer()?, jobs).await;
+
+    let output = format_bulk_results(action, &results, format);
+    print!("{}", output);
+
+    if shutdown::requested() {
+        return Err(shutdown::interrupted(progress(&results)));
+    }
+    let failed = results.iter().filter(|(_, _, r)| r.is_err()).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} operations failed", failed, results.len());
+    }
+    Ok(())
+}
+
+/// Close every target, posting `comment` (already prefixed with the signature)
+/// on each first, signed by `signer` if given.
+pub(crate) async fn close_all(
+    client: &GogsClient,
+    targets: Vec<Target>,
+    comment: Option<String>,
+    signer: Option<Signer>,
+) -> Vec<(String, i64, Result<()>)> {
+    run_all(client, targets, Arc::new(Operation::Close { comment }), signer, DEFAULT_JOBS).await
+}
+
+/// How far an interrupted run got, e.g. "3 of 10 operations done".
+pub(crate) fn progress(results: &[(String, i64, Result<()>)]) -> String {
+    let done = results.iter().filter(|(_, _, r)| r.is_ok()).count();
+    format!("{} of {} operations done", done, results.len())
+}
+
+/// Read `NUMBER`, `#NUMBER` or `owner/repo#NUMBER` items, dropping duplicates.
+fn parse_targets(input: &str, default_repo: Option<&(String, String)>) -> Result<Vec<Target>> {
+    let mut targets: Vec<Target> = Vec::new();
+
+    for token in input.split(|c: char| c.is_whitespace() || c == ',') {
+        if token.is_empty() {
+            continue;
+        }
+
+        let (repo, number) = match token.rsplit_once('#') {
+            Some((repo, number)) if !repo.is_empty() => (Some(parse_repo(repo)?), number),
+            Some((_, number)) => (None, number),
+            None => (None, token),
+        };
+        let number: i64 = number
+            .parse()
+            .with_context(|| format!("Invalid issue '{}': expected NUMBER or owner/repo#NUMBER", token))?;
+        let (owner, repo) = match repo {
+            Some(repo) => repo,
+            None => default_repo.cloned().with_context(|| {
+                format!("No repository for issue '{}'. Use owner/repo#NUMBER or --repo", token)
+            })?,
+        };
+
+        let target = (owner, repo, number);
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+
+    if targets.is_empty() {
+        anyhow::bail!("No issues given on stdin");
+    }
+    Ok(targets)
+}
+
+/// Apply `operation` to every target with at most `jobs` in flight, returning
+/// results in input order. Comments are signed per issue by `signer`. On
+/// SIGINT or SIGTERM, operations already started finish and the rest are
+/// skipped, each with an `Interrupted` error.
+async fn run_all(
+    client: &GogsClient,
+    targets: Vec<Target>,
+    operation: Arc<Operation>,
+    signer: Option<Signer>,
+    jobs: usize,
+) -> Vec<(String, i64, Result<()>)> {
+    shutdown::graceful();
+    let permits = Arc::new(Semaphore::new(jobs));
+    let mut tasks = JoinSet::new();
+
+    for (index, (owner, repo, number)) in targets.iter().cloned().enumerate() {
+        let client = client.clone();
+        let operation = operation.clone();
+        let signer = signer.clone();
+        let permits = permits.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            if shutdown::requested() {
+                return (index, Err(shutdown::interrupted("not attempted")));
+            }
+            let result = apply(&client, &operation, signer.as_ref(), &owner, &repo, number).await;
+            (index, result)
+        });
+    }
+
+    let mut results: Vec<Option<Result<()>>> = targets.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((index, result)) = joined {
+            results[index] = Some(result);
+        }
+    }
+
+    targets
+        .into_iter()
+        .zip(results)
+        .map(|((owner, repo, number), result)| {
+            let result = result.unwrap_or_else(|| Err(anyhow::anyhow!("Task failed")));
+            (format!("{}/{}", owner, repo), number, result)
+        })
+        .collect()
+}
+
+async fn apply(
+    client: &GogsClient,
+    operation: &Operation,
+    signer: Option<&Signer>,
+    owner: &str,
+    repo: &str,
+    number: i64,
+) -> Result<()> {
+    let sign = |body: &str| match signer {
+        Some(signer) => signer.sign(&format!("{}/{}", owner, repo), number, body),
+        None => body.to_string(),
+    };
+
+    match operation {
+        Operation::Close { comment } => {
+            if let Some(body) = comment {
+                client.create_comment(owner, repo, number, &sign(body)).await?;
+            }
+            let params = UpdateIssueParams {
+                state: Some(IssueState::Closed),
+                ..Default::default()
+            };
+            client.update_issue(owner, repo, number, &params).await?;
+        }
+        Operation::Label { ids } => {
+            let id = match ids.get(&format!("{}/{}", owner, repo)) {
+                Some(Ok(id)) => *id,
+                Some(Err(e)) => anyhow::bail!("{}", e),
+                None => anyhow::bail!("Label was not resolved for {}/{}", owner, repo),
+            };
+            client.add_labels_to_issue(owner, repo, number, vec![id]).await?;
+        }
+        Operation::Comment { body } => {
+            client.create_comment(owner, repo, number, &sign(body)).await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use anyhow::{Context, Result};
+use std::process::Stdio;
+use std::time::Instant;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::process::Command;
+
+use crate::api::{CreateIssueParams, GogsClient, Issue, ListIssuesParams};
+use crate::capture::{self, Captured};
+use crate::commands::issue::find_label;
+use crate::config::{Config, Profile};
+use crate::error::GogsError;
+use crate::output::{format_capture, OutputFormat};
+
+pub struct Options {
+    pub title: Option<String>,
+    pub labels: Vec<String>,
+    pub lines: usize,
+    pub fingerprint: Option<String>,
+    pub exact_labels: bool,
+}
+
+pub async fn handle(
+    command: Vec<String>,
+    repo: Option<String>,
+    options: Options,
+    client: &GogsClient,
+    config: &Config,
+    profile: &Profile,
+    json: bool,
+) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+    // Settled before the command runs, so a bad --repo doesn't cost a build
+    let (owner, repo) = config.get_repo(repo.as_deref())?;
+
+    let captured = run(command).await?;
+    if captured.status == 0 {
+        return Ok(());
+    }
+
+    // A failure to file must not hide the command's own status
+    match file(client, &owner, &repo, &captured, &options, profile).await {
+        Ok((issue, action)) => {
+            // On stderr, so the command's own stdout stays clean for whatever reads it
+            let output = format_capture(&format!("{}/{}", owner, repo), &issue, action, captured.status, &format);
+            eprint!("{}", output);
+            if !output.ends_with('\n') {
+                eprintln!();
+            }
+        }
+        Err(e) => eprintln!("Warning: Failed to report the failure in {}/{}: {:#}", owner, repo, e),
+    }
+    Err(GogsError::CommandFailed(captured.status).into())
+}
+
+/// Run `command` with its output passed through and kept.
+async fn run(command: Vec<String>) -> Result<Captured> {
+    let started = Instant::now();
+    let mut child = Command::new(&command[0])
+        .args(&command[1..])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", command[0]))?;
+
+    let stdout = tokio::spawn(tee(child.stdout.take().expect("stdout is piped"), tokio::io::stdout()));
+    let stderr = tokio::spawn(tee(child.stderr.take().expect("stderr is piped"), tokio::io::stderr()));
+    let status = child.wait().await?;
+
+    Ok(Captured {
+        command,
+        status: exit_status(status),
+        stdout: stdout.await?,
+        stderr: stderr.await?,
+        duration: started.elapsed(),
+    })
+}
+
+/// Copy `from` to `to` until it closes, returning everything copied.
+async fn tee(mut from: impl AsyncRead + Unpin, mut to: impl AsyncWrite + Unpin) -> Vec<u8> {
+    let mut kept = Vec::new();
+    let mut buf = [0u8; 8192];
+    while let Ok(n) = from.read(&mut buf).await {
+        if n == 0 {
+            break;
+        }
+        // A closed terminal shouldn't stop the output being kept for the report
+        let _ = to.write_all(&buf[..n]).await;
+        let _ = to.flush().await;
+        kept.extend_from_slice(&buf[..n]);
+    }
+    kept
+}
+
+fn exit_status(status: std::process::ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
+
+/// Comment on the open issue already reporting this failure, else file one.
+async fn file(
+    client: &GogsClient,
+    owner: &str,
+    repo: &str,
+    captured: &Captured,
+    options: &Options,
+    profile: &Profile,
+) -> Result<(Issue, &'static str)> {
+    let fingerprint = captured.fingerprint(options.fingerprint.as_deref());
+    let report = captured.report(options.lines);
+
+    let open = client.list_issues(owner, repo, &ListIssuesParams::default()).await?;
+    let existing = open
+        .into_iter()
+        .find(|issue| issue.body.as_deref().is_some_and(|body| capture::has_marker(body, &fingerprint)));
+
+    if let Some(issue) = existing {
+        let body = profile.signed(&format!("Failed again.\n\n{}", report));
+        let body = profile.sign_comment(owner, repo, issue.number, body)?;
+        client.create_comment(owner, repo, issue.number, &body).await?;
+        return Ok((issue, "updated"));
+    }
+
+    let mut label_ids = Vec::new();
+    for name in &options.labels {
+        label_ids.push(find_label(client, owner, repo, name, options.exact_labels).await?.id);
+    }
+    let params = CreateIssueParams {
+        title: options.title.clone().unwrap_or_else(|| captured.title()),
+        body: Some(profile.signed(&format!("{}\n{}\n", report, capture::marker(&fingerprint)))),
+        assignee: None,
+        labels: label_ids,
+    };
+    Ok((client.create_issue(owner, repo, &params).await?, "created"))
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+
+use crate::api::GogsClient;
+use crate::cli::CollaboratorCommand;
+use crate::config::parse_repo;
+use crate::output::{format_collaborator_change, format_collaborators, OutputFormat};
+
+pub async fn handle(cmd: CollaboratorCommand, client: &GogsClient, format: &OutputFormat) -> Result<()> {
+    match cmd {
+        CollaboratorCommand::List { repo } => {
+            let (owner, name) = parse_repo(&repo)?;
+            let collaborators = client.list_collaborators(&owner, &name).await?;
+            print!("{}", format_collaborators(&repo, &collaborators, format));
+        }
+        CollaboratorCommand::Add { repo, user, permission } => {
+            let (owner, name) = parse_repo(&repo)?;
+            client.add_collaborator(&owner, &name, &user, permission).await?;
+            print!("{}", format_collaborator_change(&repo, &user, Some(permission), format));
+        }
+        CollaboratorCommand::Remove { repo, user } => {
+            let (owner, name) = parse_repo(&repo)?;
+            client.remove_collaborator(&owner, &name, &user).await?;
+            print!("{}", format_collaborator_change(&repo, &user, None, format));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,401 @@
+use anyhow::{Context, Result};
+use std::fs;
+use toml::Value;
+
+use crate::api::GogsClient;
+use crate::cli::ConfigCommand;
+use crate::config::{parse_repo, Config, Profile, ServerConfig};
+use crate::doctor::{Check, Status};
+use crate::encryption::EncryptionConfig;
+use crate::error::GogsError;
+use crate::output::{
+    format_checks, format_config_change, format_config_encryption, format_config_value, OutputFormat,
+};
+
+pub fn handle(cmd: ConfigCommand, config: Config, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        ConfigCommand::Get { key } => {
+            let tree = Value::try_from(&config)?;
+            let value = lookup(&tree, &split_key(&key)?)
+                .ok_or_else(|| GogsError::NotFound(format!("config key '{}' is not set", key)))?;
+            print!("{}", format_config_value(&key, value, &format));
+        }
+        ConfigCommand::Set { key, value } => {
+            let path = split_key(&key)?;
+            let (updated, value) = set(&config, &path, &value)?;
+            check(&updated, &path)?;
+            updated.save()?;
+            print!("{}", format_config_change(&key, &value, &format));
+        }
+        ConfigCommand::Encrypt { recipient, identity } => {
+            if !recipient.is_empty() && identity.is_none() {
+                anyhow::bail!("--recipient needs --identity, the key file gog decrypts the profiles with");
+            }
+            let mut updated = config;
+            updated.encryption = Some(EncryptionConfig {
+                recipients: recipient,
+                identity,
+                profiles: String::new(),
+            });
+            let contents = updated.to_toml()?;
+            // A file that can't be decrypted would lose every token, so try before replacing the plain one
+            let stored = Config::from_toml(&contents).context("The config is unchanged")?;
+            if stored.profiles.len() != updated.profiles.len() {
+                anyhow::bail!("The encrypted profiles didn't decrypt to the same profiles; the config is unchanged");
+            }
+            Config::write(&contents)?;
+            let encryption = updated.encryption.as_ref().expect("set above");
+            print!("{}", format_config_encryption(updated.profiles.len(), Some(&encryption.describe()), &format));
+        }
+        ConfigCommand::Decrypt => {
+            if config.encryption.is_none() {
+                anyhow::bail!("The profiles in the config aren't encrypted");
+            }
+            let mut updated = config;
+            updated.encryption = None;
+            updated.save()?;
+            print!("{}", format_config_encryption(updated.profiles.len(), None, &format));
+        }
+        ConfigCommand::Check { .. } => unreachable!("config check reads the file itself"),
+    }
+    Ok(())
+}
+
+/// Run every check on the config file and, unless `offline`, on each
+/// profile's token; fail if any check did.
+pub async fn handle_check(name: Option<&str>, command: &str, offline: bool, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+    let checks = check_all(name, command, offline).await?;
+    print!("{}", format_checks(&checks, &format));
+
+    if checks.iter().any(|c| c.status == Status::Fail) {
+        anyhow::bail!("Config check failed");
+    }
+    Ok(())
+}
+
+/// The file first, since nothing else can be checked if it doesn't parse;
+/// then its settings; then the server, once per profile.
+async fn check_all(name: Option<&str>, command: &str, offline: bool) -> Result<Vec<Check>> {
+    let mut checks = Vec::new();
+    let path = Config::config_path()?;
+    let shown = path.display();
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            let detail = format!("Cannot read {}: {}", shown, e);
+            checks.push(Check::fail("file", detail, "Run 'gog init' to create it, or point GOGS_CONFIG at it"));
+            return Ok(checks);
+        }
+    };
+    let mut tree = match contents.parse::<Value>() {
+        Ok(tree) => tree,
+        Err(e) => {
+            let detail = format!("{} is not valid TOML: {}", shown, first_line(&e));
+            checks.push(Check::fail("file", detail, "Fix the syntax at the line and column shown"));
+            return Ok(checks);
+        }
+    };
+    let mut config = match tree.clone().try_into::<Config>() {
+        Ok(config) => config,
+        Err(e) => {
+            let detail = format!("{}: {}", shown, first_line(&e));
+            let hint = "Fix the setting named; 'gog config set' checks a value before saving it";
+            checks.push(Check::fail("file", detail, hint));
+            return Ok(checks);
+        }
+    };
+    checks.push(Check::ok("file", shown.to_string()));
+
+    match config.decrypt_profiles() {
+        // Check the decrypted settings like the rest
+        Ok(Some(profiles)) => {
+            if let Some(table) = tree.as_table_mut() {
+                table.insert("profiles".to_string(), profiles);
+            }
+            let protection = config.encryption.as_ref().map(|e| e.describe()).unwrap_or_default();
+            checks.push(Check::ok("encryption", format!("Profiles encrypted with {}", protection)));
+        }
+        Ok(None) => {}
+        Err(e) => {
+            let hint = "Check the passphrase, or that encryption.identity holds a key for one of the recipients";
+            checks.push(Check::fail("encryption", format!("{:#}", e), hint));
+            return Ok(checks);
+        }
+    }
+
+    // Like the other config commands, and before any token is used
+    if let Ok(profile) = config.get_profile(name) {
+        profile.check_allowed(config.profile_name(name), command)?;
+    }
+
+    let mut unknown = Vec::new();
+    for key in leaves(&tree, "") {
+        if !is_known(&config, &split_key(&key)?)? {
+            unknown.push(key);
+        }
+    }
+    for key in &unknown {
+        checks.push(Check::warn(
+            "keys",
+            format!("Unknown setting '{}' is ignored", key),
+            "Check its spelling and section against the README, or remove it",
+        ));
+    }
+    if unknown.is_empty() {
+        checks.push(Check::ok("keys", "Every setting is one gog reads".to_string()));
+    }
+
+    let failed = checks.len();
+    for key in checked_keys(&config) {
+        if let Err(e) = check(&config, &split_key(&key)?) {
+            let hint = format!("Change it with 'gog config set {} VALUE'", key);
+            checks.push(Check::fail("value", format!("{}: {:#}", key, e), &hint));
+        }
+    }
+    if config.profiles.is_empty() {
+        let hint = "Add one with 'gog profile add' or 'gog init'";
+        checks.push(Check::fail("value", "No profiles defined".to_string(), hint));
+    }
+    if checks.len() == failed {
+        let detail = "URLs, the default repository and profile names are valid".to_string();
+        checks.push(Check::ok("value", detail));
+    }
+
+    if let Ok(dir) = std::env::current_dir() {
+        match config.apply_project(&dir) {
+            Ok(()) => {
+                if let Some((path, _)) = &config.project {
+                    checks.push(Check::ok("project", path.display().to_string()));
+                }
+            }
+            Err(e) => checks.push(Check::fail("project", format!("{:#}", e), "Fix or remove the .gog.toml")),
+        }
+        config.apply_remote(&dir);
+    }
+
+    if offline {
+        return Ok(checks);
+    }
+
+    let mut names: Vec<&String> = config.profiles.keys().collect();
+    names.sort();
+    let mut working = Vec::new();
+    for profile_name in names {
+        let mut profile = config.profiles[profile_name].clone();
+        // A missing server was reported above
+        let Ok(server) = config.server_for(&profile) else {
+            continue;
+        };
+        let login = format!("Store one with 'gog --profile {} auth login'", profile_name);
+        if let Err(e) = profile.load_token(profile_name) {
+            checks.push(Check::fail("token", format!("{}: {:#}", profile_name, e), &login));
+            continue;
+        }
+        if profile.token.is_empty() {
+            checks.push(Check::fail("token", format!("{}: no token", profile_name), &login));
+            continue;
+        }
+
+        match client_for(server, &profile).get_current_user().await {
+            Ok(user) if user.username.eq_ignore_ascii_case(&profile.gogs_user) => {
+                let detail = format!("{}: authenticated as {} on {}", profile_name, user.username, server.url);
+                checks.push(Check::ok("token", detail));
+                working.push(profile_name.clone());
+            }
+            Ok(user) => {
+                let detail = format!(
+                    "{}: the token belongs to '{}', not '{}'",
+                    profile_name, user.username, profile.gogs_user
+                );
+                let hint = format!(
+                    "Replace the token, or set profiles.{}.gogs_user to '{}'",
+                    profile_name, user.username
+                );
+                checks.push(Check::warn("token", detail, &hint));
+                working.push(profile_name.clone());
+            }
+            Err(e) => {
+                let hint = match e.downcast_ref::<GogsError>() {
+                    Some(GogsError::AuthError(_)) => format!(
+                        "Create a new token in the Gogs user settings, then run 'gog --profile {} auth login'",
+                        profile_name
+                    ),
+                    _ => format!("Run 'gog --profile {} doctor network' to see which step fails", profile_name),
+                };
+                checks.push(Check::fail("token", format!("{}: {:#}", profile_name, e), &hint));
+            }
+        }
+    }
+
+    // The repository commands use when given no --repo, as the profile they run as sees it
+    let profile_name = config.profile_name(name).to_string();
+    if let (Some(repo), true) = (&config.defaults.repo, working.contains(&profile_name)) {
+        let profile = &config.profiles[&profile_name];
+        if let (Ok((owner, repo_name)), Ok(server)) = (parse_repo(repo), config.server_for(profile)) {
+            let mut profile = profile.clone();
+            profile.load_token(&profile_name)?;
+            match client_for(server, &profile).get_repo(&owner, &repo_name).await {
+                Ok(_) => checks.push(Check::ok("repo", format!("{} (as {})", repo, profile_name))),
+                Err(e) if matches!(e.downcast_ref::<GogsError>(), Some(GogsError::NotFound(_))) => {
+                    let detail = format!("{} doesn't exist, or {} can't see it", repo, profile_name);
+                    let hint = "Fix defaults.repo (or the repo in .gog.toml), or give the profile's user access";
+                    checks.push(Check::fail("repo", detail, hint));
+                }
+                Err(e) => {
+                    let hint = "Check the repository's permissions on the server";
+                    checks.push(Check::fail("repo", format!("{}: {:#}", repo, e), hint));
+                }
+            }
+        }
+    }
+
+    Ok(checks)
+}
+
+/// A client for `server` with only what the checks need: no cache, mirror
+/// or comment limits.
+fn client_for(server: &ServerConfig, profile: &Profile) -> GogsClient {
+    let client = GogsClient::new(server.url.clone(), profile.token.clone());
+    match &server.api_prefix {
+        Some(prefix) => client.with_api_prefix(prefix),
+        None => client,
+    }
+}
+
+/// Dotted keys of every setting in `tree`. Tables are walked into; arrays
+/// and plain values are settings.
+fn leaves(tree: &Value, prefix: &str) -> Vec<String> {
+    let Some(table) = tree.as_table() else {
+        return vec![prefix.to_string()];
+    };
+    table
+        .iter()
+        .flat_map(|(key, value)| {
+            let key = match prefix {
+                "" => key.clone(),
+                _ => format!("{}.{}", prefix, key),
+            };
+            leaves(value, &key)
+        })
+        .collect()
+}
+
+/// Keys of the settings `check` has more to say about than their type.
+fn checked_keys(config: &Config) -> Vec<String> {
+    let mut keys = vec!["server.url".to_string(), "defaults.repo".to_string(), "defaults.profile".to_string()];
+    let mut servers: Vec<&String> = config.servers.keys().collect();
+    servers.sort();
+    keys.extend(servers.iter().map(|name| format!("servers.{}.url", name)));
+    let mut profiles: Vec<&String> = config.profiles.keys().collect();
+    profiles.sort();
+    keys.extend(profiles.iter().map(|name| format!("profiles.{}.server", name)));
+    keys
+}
+
+/// The first line of a TOML error, which goes on to repeat the input.
+fn first_line(e: &impl std::fmt::Display) -> String {
+    e.to_string().lines().next().unwrap_or_default().to_string()
+}
+
+fn split_key(key: &str) -> Result<Vec<&str>> {
+    let path: Vec<&str> = key.split('.').collect();
+    if path.iter().any(|part| part.is_empty()) {
+        anyhow::bail!("Invalid config key '{}'; expected e.g. defaults.repo", key);
+    }
+    Ok(path)
+}
+
+fn lookup<'a>(tree: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(tree, |value, part| value.get(part))
+}
+
+/// Put `value` at `path`, creating tables on the way.
+fn insert(tree: &mut Value, path: &[&str], value: Value) -> Result<()> {
+    let mut table = tree
+        .as_table_mut()
+        .ok_or_else(|| anyhow::anyhow!("The config is not a table"))?;
+    let (last, parents) = path.split_last().expect("keys have at least one part");
+    for part in parents {
+        table = table
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Table(Default::default()))
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("'{}' is a setting, not a section", part))?;
+    }
+    table.insert(last.to_string(), value);
+    Ok(())
+}
+
+/// The config with `raw` at `path`, typed by what the config accepts there:
+/// `raw` as a TOML value (300, true, ["a", "b"]) if that fits, else as a string.
+fn set(config: &Config, path: &[&str], raw: &str) -> Result<(Config, Value)> {
+    let mut candidates = Vec::new();
+    if let Ok(Value::Table(mut parsed)) = format!("value = {}", raw).parse::<Value>() {
+        candidates.extend(parsed.remove("value"));
+    }
+    candidates.push(Value::String(raw.to_string()));
+
+    let key = path.join(".");
+    let mut error = None;
+    for candidate in candidates {
+        match with_value(config, path, candidate.clone()) {
+            Ok(updated) => {
+                if !is_known(config, path)? {
+                    anyhow::bail!("Unknown config key '{}'", key);
+                }
+                return Ok((updated, candidate));
+            }
+            Err(e) => error = Some(e),
+        }
+    }
+    Err(error.expect("there is always a string candidate"))
+        // The TOML error goes on to repeat the key over several lines
+        .map_err(|e| anyhow::anyhow!("Invalid value for {}: {}", key, first_line(&e)))
+}
+
+fn with_value(config: &Config, path: &[&str], value: Value) -> Result<Config> {
+    let mut tree = Value::try_from(config)?;
+    insert(&mut tree, path, value)?;
+    Ok(tree.try_into::<Config>()?)
+}
+
+/// Whether the config has a setting at `path`. Unknown keys are dropped
+/// silently when the config is read, so probe with a value no setting
+/// accepts: only a real one makes reading fail.
+fn is_known(config: &Config, path: &[&str]) -> Result<bool> {
+    let probe = Value::Array(vec![Value::Table(Default::default())]);
+    Ok(with_value(config, path, probe).is_err())
+}
+
+/// Checks the types alone don't catch, for the setting just changed.
+fn check(config: &Config, path: &[&str]) -> Result<()> {
+    match path {
+        ["server", "url"] => check_url(&config.server.url),
+        ["servers", name, "url"] => check_url(&config.servers[*name].url),
+        ["defaults", "repo"] => config.defaults.repo.as_deref().map_or(Ok(()), |r| parse_repo(r).map(|_| ())),
+        ["defaults", "profile"] => match &config.defaults.profile {
+            Some(name) if !config.profiles.contains_key(name) => {
+                anyhow::bail!("There is no profile '{}' to make the default", name)
+            }
+            _ => Ok(()),
+        },
+        ["profiles", name, "server"] => match &config.profiles[*name].server {
+            Some(server) if !config.servers.contains_key(server) => {
+                anyhow::bail!("There is no server '{}' in [servers]", server)
+            }
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+fn check_url(url: &str) -> Result<()> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        anyhow::bail!("'{}' is not an http:// or https:// URL", url);
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anyhow::Result;
+
+#[cfg(unix)]
+pub async fn handle(stop: bool, status: bool, cache_ttl: Option<u64>, json: bool) -> Result<()> {
+    use crate::api::daemon;
+    use crate::output::{format_daemon_status, OutputFormat};
+    use std::time::Duration;
+
+    let path = daemon::socket_path().ok_or_else(|| anyhow::anyhow!("No runtime or cache directory for the socket"))?;
+
+    if status {
+        let status = daemon::status(&path).await;
+        print!("{}", format_daemon_status(status.as_ref(), &OutputFormat::from_json_flag(json)));
+        return Ok(());
+    }
+
+    if stop {
+        match daemon::stop(&path).await {
+            Some(pid) => println!("Stopped the daemon (pid {}).", pid),
+            None => println!("No daemon is running."),
+        }
+        return Ok(());
+    }
+
+    let ttl = cache_ttl.unwrap_or(daemon::DEFAULT_CACHE_TTL);
+    eprintln!(
+        "Listening on {} (GET responses kept {}s). Stop with Ctrl-C or `gog daemon --stop`.",
+        path.display(),
+        ttl
+    );
+    daemon::serve(&path, Duration::from_secs(ttl)).await
+}
+
+#[cfg(not(unix))]
+pub async fn handle(_stop: bool, _status: bool, _cache_ttl: Option<u64>, _json: bool) -> Result<()> {
+    anyhow::bail!("gog daemon needs Unix domain sockets, which this platform doesn't offer")
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+
+use crate::cli::DoctorCommand;
+use crate::config::{Config, Profile};
+use crate::doctor::{self, Status};
+use crate::output::{format_checks, OutputFormat};
+
+pub async fn handle(
+    cmd: DoctorCommand,
+    config: &Config,
+    profile: Option<&Profile>,
+    json: bool,
+) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        DoctorCommand::Network => {
+            let checks = doctor::network_checks(config, profile).await;
+            print!("{}", format_checks(&checks, &format));
+
+            if checks.iter().any(|c| c.status == Status::Fail) {
+                anyhow::bail!("Network check failed");
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::{Context, Result};
+use std::io::{IsTerminal, Read, Write};
+
+use crate::api::{GogsClient, PutFileParams};
+use crate::cli::FileCommand;
+use crate::config::{parse_repo, Profile};
+use crate::error::GogsError;
+use crate::output::{format_file_contents, format_file_put, OutputFormat};
+
+pub async fn handle(cmd: FileCommand, client: &GogsClient, profile: &Profile, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        FileCommand::Get { repo, path, git_ref } => {
+            let (owner, name) = parse_repo(&repo)?;
+            let file = client
+                .get_file_contents(&owner, &name, &path, git_ref.as_deref())
+                .await?
+                .ok_or_else(|| {
+                    let at = git_ref.as_deref().map(|r| format!(" at {}", r)).unwrap_or_default();
+                    GogsError::NotFound(format!("{} in {}/{}{}", path, owner, name, at))
+                })?;
+
+            if json {
+                print!("{}", format_file_contents(&file, &format));
+                return Ok(());
+            }
+            let bytes = file.decode()?;
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(&bytes).context("Failed to write to stdout")?;
+            stdout.flush()?;
+        }
+        FileCommand::Put {
+            repo,
+            path,
+            message,
+            branch,
+        } => {
+            let (owner, name) = parse_repo(&repo)?;
+            if std::io::stdin().is_terminal() {
+                anyhow::bail!(
+                    "Pipe the new contents in on stdin, e.g. gog file put {} {} --message '...' < FILE",
+                    repo,
+                    path
+                );
+            }
+            let mut content = Vec::new();
+            std::io::stdin()
+                .read_to_end(&mut content)
+                .context("Failed to read contents from stdin")?;
+
+            let existing = client
+                .get_file_contents(&owner, &name, &path, branch.as_deref())
+                .await?;
+            if let Some(file) = &existing {
+                if file.decode()? == content {
+                    print!("{}", format_file_put(&path, "unchanged", None, &format));
+                    return Ok(());
+                }
+            }
+
+            let message = profile.signed(&message);
+            let params = PutFileParams {
+                branch: branch.clone(),
+                sha: existing.as_ref().map(|file| file.sha.clone()),
+                ..PutFileParams::new(&content, &message)
+            };
+            let commit = client.put_file_contents(&owner, &name, &path, &params).await?;
+            let action = if existing.is_some() { "updated" } else { "created" };
+            print!("{}", format_file_put(&path, action, Some(&commit), &format));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::api::GogsClient;
+use crate::foreach::{expand, has_placeholder, matches, Run, PLACEHOLDERS};
+use crate::output::{format_foreach_plan, format_foreach_results, OutputFormat};
+use crate::shutdown;
+
+pub struct Options {
+    /// Globs selecting repositories by full name
+    pub patterns: Vec<String>,
+    pub jobs: usize,
+    pub dry_run: bool,
+    /// The gog subcommand and its arguments, with placeholders
+    pub command: Vec<String>,
+    /// Global options to repeat for each run, e.g. --profile
+    pub global_args: Vec<String>,
+    /// Environment for each run, e.g. the --token given to this one
+    pub env: Vec<(String, String)>,
+}
+
+pub async fn handle(client: &GogsClient, options: Options, format: &OutputFormat) -> Result<()> {
+    if options.command.first().map(String::as_str) == Some("foreach") {
+        anyhow::bail!("gog foreach can't run foreach");
+    }
+    if !has_placeholder(&options.command) {
+        anyhow::bail!(
+            "The command doesn't name the repository; use {} (e.g. gog foreach --repos 'org/*' -- label list --repo {{repo}})",
+            PLACEHOLDERS.join(", ")
+        );
+    }
+
+    let mut repos = client.list_accessible_repos(true).await?;
+    repos.retain(|repo| matches(&options.patterns, &repo.full_name));
+    repos.sort_by(|a, b| a.full_name.cmp(&b.full_name));
+    repos.dedup_by(|a, b| a.full_name == b.full_name);
+    if repos.is_empty() {
+        anyhow::bail!("No repositories match {}", options.patterns.join(", "));
+    }
+
+    let plan: Vec<(String, Vec<String>)> = repos
+        .iter()
+        .map(|repo| (repo.full_name.clone(), expand(&options.command, repo)))
+        .collect();
+    if options.dry_run {
+        print!("{}", format_foreach_plan(&plan, format));
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe().context("Failed to find the gog executable")?;
+    let runs = run_all(exe, plan, &options).await;
+
+    print!("{}", format_foreach_results(&runs, format));
+
+    if shutdown::requested() {
+        let done = runs.iter().filter(|run| run.ok()).count();
+        return Err(shutdown::interrupted(format!("{} of {} repositories done", done, runs.len())));
+    }
+    let failed = runs.iter().filter(|run| !run.ok()).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} repositories failed", failed, runs.len());
+    }
+    Ok(())
+}
+
+/// Run every planned command with at most `jobs` at once, returning runs in
+/// plan order. After SIGINT or SIGTERM, runs already started finish (the
+/// children get the signal too) and the rest are skipped.
+async fn run_all(exe: PathBuf, plan: Vec<(String, Vec<String>)>, options: &Options) -> Vec<Run> {
+    shutdown::graceful();
+    let permits = Arc::new(Semaphore::new(options.jobs));
+    let global_args = Arc::new(options.global_args.clone());
+    let env = Arc::new(options.env.clone());
+    let mut tasks = JoinSet::new();
+
+    for (index, (repo, args)) in plan.iter().cloned().enumerate() {
+        let exe = exe.clone();
+        let permits = permits.clone();
+        let global_args = global_args.clone();
+        let env = env.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let mut run = Run {
+                repo,
+                args,
+                status: None,
+                stdout: String::new(),
+                stderr: String::new(),
+                skipped: false,
+            };
+            if shutdown::requested() {
+                run.skipped = true;
+                return (index, run);
+            }
+
+            let output = Command::new(&exe)
+                .args(global_args.iter())
+                .args(&run.args)
+                // GOGS_REPO would win over the run's own --repo {repo}
+                .env_remove("GOGS_REPO")
+                .envs(env.iter().map(|(k, v)| (k, v)))
+                .stdin(Stdio::null())
+                .kill_on_drop(true)
+                .output()
+                .await;
+            match output {
+                Ok(output) => {
+                    run.status = output.status.code();
+                    run.stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+                    run.stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+                }
+                Err(e) => run.stderr = format!("Failed to run {}: {}", exe.display(), e),
+            }
+            (index, run)
+        });
+    }
+
+    let mut runs: Vec<Option<Run>> = plan.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((index, run)) = joined {
+            runs[index] = Some(run);
+        }
+    }
+
+    plan.into_iter()
+        .zip(runs)
+        .map(|((repo, args), run)| {
+            run.unwrap_or_else(|| Run {
+                repo,
+                args,
+                status: None,
+                stdout: String::new(),
+                stderr: "Task failed".to_string(),
+                skipped: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn runs_ignore_gogs_repo() {
+        std::env::set_var("GOGS_REPO", "ci/pinned");
+        let options = Options {
+            patterns: Vec::new(),
+            jobs: 2,
+            dry_run: false,
+            command: Vec::new(),
+            global_args: vec!["-c".to_string(), "printf '%s %s' \"${GOGS_REPO:-}\" \"$0\"".to_string()],
+            env: Vec::new(),
+        };
+        let plan = ["a/one", "b/two"].map(|repo| (repo.to_string(), vec![repo.to_string()])).to_vec();
+
+        let runs = run_all(PathBuf::from("/bin/sh"), plan, &options).await;
+
+        let targets: Vec<&str> = runs.iter().map(|run| run.stdout.as_str()).collect();
+        assert_eq!(targets, [" a/one", " b/two"]);
+    }
+}
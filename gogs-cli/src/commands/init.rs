@@ -1,167 +1,369 @@
 use anyhow::{Context, Result};
 use std::collections::HashMap;
-use std::io::{self, Write};
 
 use crate::api::GogsClient;
-use crate::config::{Config, Defaults, Profile, ServerConfig};
+use crate::commands::{confirm, prompt, token};
+use crate::editor;
+use crate::config::{CacheConfig, Config, Defaults, Profile, RetryConfig, ServerConfig, StorageConfig};
+use crate::presets::{self, RolePreset};
+use crate::remote;
 
-pub async fn handle_init() -> Result<()> {
-    println!("Gogs CLI Configuration Setup");
-    println!("=============================\n");
+/// Answers given as flags, so provisioning scripts can run `init` without a
+/// terminal. Anything left out is asked for.
+#[derive(Default)]
+pub struct Options {
+    pub url: Option<String>,
+    pub profile: Option<String>,
+    pub user: Option<String>,
+    pub token: Option<String>,
+    pub role: Option<String>,
+    pub signature: Option<String>,
+    pub default_repo: Option<String>,
+    pub yes: bool,
+    pub skip_test: bool,
+    /// Fill in the server and default repository from the checkout's origin
+    pub from_git: bool,
+}
+
+impl Options {
+    /// Whether the flags cover everything a profile needs, in which case
+    /// nothing optional is asked for either.
+    fn complete(&self) -> bool {
+        self.url.is_some() && self.user.is_some() && self.token.is_some()
+    }
+}
+
+pub async fn handle_init(mut options: Options) -> Result<()> {
+    if options.from_git {
+        let dir = std::env::current_dir()?;
+        let remote = remote::origin(&dir).with_context(|| {
+            format!("No git remote 'origin' pointing at a hosted repository in {}", dir.display())
+        })?;
+        println!("From git remote origin: {} ({})", remote.server_url, remote.full_name());
+        options.url.get_or_insert(remote.server_url.clone());
+        options.default_repo.get_or_insert(remote.full_name());
+    }
+
+    let complete = options.complete();
+    if !complete && !editor::is_interactive() {
+        let missing: Vec<&str> = [
+            ("--url", options.url.is_none()),
+            ("--user", options.user.is_none()),
+            ("--token", options.token.is_none()),
+        ]
+        .into_iter()
+        .filter_map(|(flag, missing)| missing.then_some(flag))
+        .collect();
+        anyhow::bail!(
+            "No terminal to run the setup wizard on; pass {} (the token can also come from GOGS_TOKEN)",
+            missing.join(", ")
+        );
+    }
+
+    // Settled before anything is asked or tested
+    if let Some(repo) = options.default_repo.as_deref().filter(|repo| !repo.contains('/')) {
+        anyhow::bail!("Invalid --default-repo '{}'. Should be 'owner/repo'", repo);
+    }
+
+    if !complete {
+        println!("Gogs CLI Configuration Setup");
+        println!("=============================\n");
+    }
 
     // Check if config already exists
     let config_path = Config::config_path()?;
-    if config_path.exists() {
+    if config_path.exists() && !options.yes {
+        if complete || !editor::is_interactive() {
+            anyhow::bail!("Config file already exists at {:?}. Pass --yes to overwrite it", config_path);
+        }
         println!("Config file already exists at {:?}", config_path);
-        print!("Overwrite? [y/N]: ");
-        io::stdout().flush()?;
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        if !input.trim().eq_ignore_ascii_case("y") {
+        if !confirm("Overwrite?")? {
             println!("Aborted.");
             return Ok(());
         }
     }
 
     // Get server URL
-    print!("Gogs server URL (e.g., https://gogs.example.com): ");
-    io::stdout().flush()?;
-    let mut server_url = String::new();
-    io::stdin().read_line(&mut server_url)?;
-    let server_url = server_url.trim().to_string();
-
+    let server_url = match &options.url {
+        Some(url) => url.clone(),
+        None => prompt("Gogs server URL (e.g., https://gogs.example.com): ")?,
+    };
     if server_url.is_empty() {
         anyhow::bail!("Server URL cannot be empty");
     }
-
-    // Get profile name
-    print!("Profile name [default]: ");
-    io::stdout().flush()?;
-    let mut profile_name = String::new();
-    io::stdin().read_line(&mut profile_name)?;
-    let profile_name = profile_name.trim();
-    let profile_name = if profile_name.is_empty() {
-        "default".to_string()
-    } else {
-        profile_name.to_string()
-    };
-
-    // Get Gogs username
-    print!("Gogs username: ");
-    io::stdout().flush()?;
-    let mut gogs_user = String::new();
-    io::stdin().read_line(&mut gogs_user)?;
-    let gogs_user = gogs_user.trim().to_string();
-
-    if gogs_user.is_empty() {
-        anyhow::bail!("Username cannot be empty");
+    if !server_url.contains("://") {
+        anyhow::bail!("Server URL '{}' needs a scheme, e.g. https://{}", server_url, server_url);
     }
 
-    // Get API token
-    print!("API token (from Gogs settings): ");
-    io::stdout().flush()?;
-    let mut token = String::new();
-    io::stdin().read_line(&mut token)?;
-    let token = token.trim().to_string();
+    // One profile per identity (you, and each agent role), all on the same server
+    let mut profiles = HashMap::new();
+    let mut first_profile = None;
+    // Flags describe the first profile only
+    let unanswered = Options::default();
+    loop {
+        let given = if profiles.is_empty() { &options } else { &unanswered };
+        if let Some((name, profile)) = prompt_profile(&server_url, &profiles, given, options.skip_test).await? {
+            first_profile.get_or_insert_with(|| name.clone());
+            profiles.insert(name.clone(), profile);
+            println!("Profile '{}' added.", name);
+        }
 
-    if token.is_empty() {
-        anyhow::bail!("API token cannot be empty");
+        if complete || !confirm("\nAdd another profile?")? {
+            break;
+        }
+        println!();
     }
 
-    // Get role description
-    print!("Role description (e.g., 'Human Developer' or 'Planning Agent') [Human]: ");
-    io::stdout().flush()?;
-    let mut role = String::new();
-    io::stdin().read_line(&mut role)?;
-    let role = role.trim();
-    let role = if role.is_empty() {
-        "Human".to_string()
-    } else {
-        role.to_string()
+    let Some(first_profile) = first_profile else {
+        println!("No profiles defined. Aborted.");
+        return Ok(());
     };
-
-    // Get signature
-    let default_sig = format!("[{}]", role);
-    print!("Comment signature [{}]: ", default_sig);
-    io::stdout().flush()?;
-    let mut signature = String::new();
-    io::stdin().read_line(&mut signature)?;
-    let signature = signature.trim();
-    let signature = if signature.is_empty() {
-        default_sig
+    let default_profile = if profiles.len() > 1 {
+        let name = prompt(&format!("Default profile [{}]: ", first_profile))?;
+        if name.is_empty() {
+            first_profile
+        } else if profiles.contains_key(&name) {
+            name
+        } else {
+            println!("Warning: No profile named '{}'. Using '{}'.", name, first_profile);
+            first_profile
+        }
     } else {
-        signature.to_string()
+        first_profile
     };
 
-    // Test connection
-    println!("\nTesting connection to {}...", server_url);
-    let client = GogsClient::new(server_url.clone(), token.clone());
-
-    match client.list_user_repos().await {
-        Ok(repos) => {
-            println!("Connection successful! Found {} accessible repositories.", repos.len());
-        }
-        Err(e) => {
-            println!("Warning: Connection test failed: {}", e);
-            print!("Save config anyway? [y/N]: ");
-            io::stdout().flush()?;
-
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            if !input.trim().eq_ignore_ascii_case("y") {
-                println!("Aborted.");
-                return Ok(());
-            }
-        }
-    }
-
     // Get default repo (optional)
-    print!("Default repository (owner/repo, optional): ");
-    io::stdout().flush()?;
-    let mut default_repo = String::new();
-    io::stdin().read_line(&mut default_repo)?;
-    let default_repo = default_repo.trim();
-    let default_repo = if default_repo.is_empty() {
-        None
-    } else {
-        // Validate format
-        if !default_repo.contains('/') {
-            println!("Warning: Invalid repo format. Should be 'owner/repo'. Skipping default.");
-            None
-        } else {
-            Some(default_repo.to_string())
+    let default_repo = match options.default_repo {
+        Some(repo) => Some(repo),
+        None if complete => None,
+        None => {
+            let default_repo = prompt("Default repository (owner/repo, optional): ")?;
+            if default_repo.is_empty() {
+                None
+            } else if !default_repo.contains('/') {
+                // Validate format
+                println!("Warning: Invalid repo format. Should be 'owner/repo'. Skipping default.");
+                None
+            } else {
+                Some(default_repo)
+            }
         }
     };
 
-    // Create config
-    let profile = Profile {
-        gogs_user,
-        token,
-        role,
-        signature,
+    let profile_names: Vec<String> = {
+        let mut names: Vec<String> = profiles.keys().cloned().collect();
+        names.sort();
+        names
     };
 
-    let mut profiles = HashMap::new();
-    profiles.insert(profile_name.clone(), profile);
-
     let config = Config {
-        server: ServerConfig { url: server_url },
+        server: ServerConfig {
+            url: server_url,
+            max_pages: None,
+            api_prefix: None,
+            rate_limit: None,
+        },
+        servers: HashMap::new(),
         defaults: Defaults {
             repo: default_repo,
-            profile: Some(profile_name.clone()),
+            profile: Some(default_profile.clone()),
+            claim_label: None,
+            exact_labels: None,
+            handoff_label: None,
+            include_org_repos: None,
         },
         profiles,
+        encryption: None,
+        lint: None,
+        cache: CacheConfig::default(),
+        retry: RetryConfig::default(),
+        storage: StorageConfig::default(),
+        templates: HashMap::new(),
+        presets: HashMap::new(),
+        update: None,
+        trusted_keys: HashMap::new(),
+        project: None,
     };
 
     // Save config
     config.save().context("Failed to save configuration")?;
 
     println!("\nConfiguration saved to {:?}", config_path);
-    println!("Profile '{}' created.", profile_name);
+    println!("Profiles: {} (default: {})", profile_names.join(", "), default_profile);
     println!("\nYou can now use gog commands. Try:");
     println!("  gog repo list");
     println!("  gog issue list --all");
+    if profile_names.len() > 1 {
+        println!("  gog --profile {} issue list --all", profile_names[0]);
+    }
 
     Ok(())
 }
+
+/// Ask for one profile's details, other than those `given` as flags. Returns
+/// None if the user discards it after a failed connection test.
+async fn prompt_profile(
+    server_url: &str,
+    existing: &HashMap<String, Profile>,
+    given: &Options,
+    skip_test: bool,
+) -> Result<Option<(String, Profile)>> {
+    let complete = given.complete();
+
+    // Get profile name
+    let profile_name = match &given.profile {
+        Some(name) => name.clone(),
+        None if complete => "default".to_string(),
+        None => loop {
+            let suggested = if existing.is_empty() { " [default]" } else { "" };
+            let name = prompt(&format!("Profile name{}: ", suggested))?;
+            let name = match name.as_str() {
+                "" if existing.is_empty() => "default".to_string(),
+                "" => {
+                    println!("Profile name cannot be empty.");
+                    continue;
+                }
+                _ => name,
+            };
+            if existing.contains_key(&name) {
+                println!("Profile '{}' is already defined.", name);
+                continue;
+            }
+            break name;
+        },
+    };
+
+    // Get Gogs username
+    let gogs_user = match &given.user {
+        Some(user) => user.clone(),
+        None => prompt("Gogs username: ")?,
+    };
+    if gogs_user.is_empty() {
+        anyhow::bail!("Username cannot be empty");
+    }
+
+    // Get API token, or make one by signing in
+    let token = match &given.token {
+        Some(token) => token.clone(),
+        None => loop {
+            let token = prompt("API token (from Gogs settings; empty to create one with your password): ")?;
+            if !token.is_empty() {
+                break token;
+            }
+            let client = GogsClient::new(server_url.to_string(), String::new());
+            match token::create_with_password(&client, &gogs_user, &format!("gog-{}", profile_name)).await {
+                Ok(created) => {
+                    println!("Created API token '{}' for {}.", created.name, gogs_user);
+                    break created.sha1;
+                }
+                // Without a terminal, asking again would only fail the same way
+                Err(e) if !editor::is_interactive() => return Err(e),
+                Err(e) => println!("Couldn't create a token: {:#}", e),
+            }
+        },
+    };
+
+    // Get role, from a preset or typed out
+    let builtin = presets::builtin();
+    let choice = match &given.role {
+        Some(role) => role.clone(),
+        None if complete => String::new(),
+        None => {
+            println!("Role presets:");
+            for (i, (name, preset)) in builtin.iter().enumerate() {
+                println!("  {}) {:<9} {} {}", i + 1, name, preset.role, preset.signature);
+            }
+            prompt("Role (number, preset name, or a description of your own) [1]: ")?
+        }
+    };
+    let preset = match choice.parse::<usize>() {
+        Ok(n) if (1..=builtin.len()).contains(&n) => builtin[n - 1].1.clone(),
+        _ if choice.is_empty() => builtin[0].1.clone(),
+        _ => match builtin.iter().find(|(name, _)| name.eq_ignore_ascii_case(&choice)) {
+            Some((_, preset)) => preset.clone(),
+            None => RolePreset {
+                signature: format!("[{}]", choice),
+                role: choice,
+                default_labels: Vec::new(),
+                allowed_commands: Vec::new(),
+                notify_author: false,
+                comment_limit: None,
+                forbid_other_authors: false,
+            },
+        },
+    };
+    let mut profile = preset.profile(gogs_user, token);
+    print_preset_summary(&profile);
+
+    // Get signature
+    let signature = match &given.signature {
+        Some(signature) => signature.clone(),
+        None if complete => String::new(),
+        None => prompt(&format!("Comment signature [{}]: ", profile.signature))?,
+    };
+    if !signature.is_empty() {
+        profile.signature = signature;
+    }
+
+    // Test connection with a single cheap call, rather than listing every repository
+    if !skip_test {
+        println!("\nTesting connection to {}...", server_url);
+        let client = GogsClient::new(server_url.to_string(), profile.token.clone());
+        if !test_connection(&client, &profile.gogs_user).await? {
+            println!("Profile '{}' discarded.", profile_name);
+            return Ok(None);
+        }
+    }
+
+    Ok(Some((profile_name, profile)))
+}
+
+/// Check the token works and belongs to `gogs_user`. On a problem, warn and
+/// ask whether to keep the profile anyway; returns whether to keep it.
+pub(crate) async fn test_connection(client: &GogsClient, gogs_user: &str) -> Result<bool> {
+    let problem = match client.get_current_user().await {
+        Ok(user) if user.username.eq_ignore_ascii_case(gogs_user) => {
+            println!("Connection successful! Authenticated as {}.", user.username);
+            None
+        }
+        Ok(user) => Some(format!(
+            "The token belongs to '{}', not '{}'. Did you paste the wrong token?",
+            user.username, gogs_user
+        )),
+        Err(e) => Some(format!(
+            "Connection test failed: {}\nAfter saving, run 'gog doctor network' to see which step fails.",
+            e
+        )),
+    };
+
+    match problem {
+        None => Ok(true),
+        // A script can't answer, and saving a profile that doesn't work would hide the problem
+        Some(problem) if !editor::is_interactive() => {
+            anyhow::bail!("{}\nPass --skip-test to save it anyway.", problem)
+        }
+        Some(problem) => {
+            println!("Warning: {}", problem);
+            confirm("Keep this profile anyway?")
+        }
+    }
+}
+
+/// Describe what a preset set up beyond role and signature, if anything.
+pub(crate) fn print_preset_summary(profile: &Profile) {
+    if !profile.default_labels.is_empty() {
+        println!("  Labels on new issues: {}", profile.default_labels.join(", "));
+    }
+    if !profile.allowed_commands.is_empty() {
+        println!("  Allowed commands: {}", profile.allowed_commands.join(", "));
+    }
+    if profile.notify_author {
+        println!("  Mentions the issue author when closing with a comment");
+    }
+    if let Some(limit) = &profile.comment_limit {
+        println!("  At most {} comments per issue every {} minutes", limit.max, limit.window_minutes);
+    }
+    if profile.forbid_other_authors {
+        println!("  Never edits or deletes other users' comments");
+    }
+}
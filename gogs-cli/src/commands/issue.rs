@@ -1,11 +1,29 @@
 use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
-use crate::api::GogsClient;
-use crate::cli::IssueCommand;
+use crate::api::{
+    default_label_color, label_name_matches, Attachment, Comment, CreateIssueParams, GogsClient, Issue, IssueState, Label, LabelFilter,
+    ListIssuesParams, MilestoneFilter, StateFilter, UpdateIssueParams,
+};
+use crate::cli::{IssueCommand, IssueSort};
+use crate::commands::{bulk, confirm, stream, for_each_repo, open_web, user, RepoScope};
 use crate::config::{Config, Profile};
+use crate::editor;
+use crate::error::GogsError;
+use crate::journal::JournalEntry;
+use crate::lint::lint_body;
+use crate::provenance::{self, Provenance};
+use crate::scrub::Scrubber;
+use crate::shutdown;
+use crate::signature::Position;
+use crate::votes;
 use crate::output::{
-    format_created_comment, format_created_issue, format_issue_detail, format_issue_list,
-    format_issue_updated, OutputFormat,
+    format_body_diff, format_bulk_results, format_comment_change, format_created_comment, format_created_issue, format_export, format_issue_counts, format_issue_detail,
+    format_issue_list, format_issue_updated, format_lint_result, format_ownership_change,
+    format_sorted_issue_list, format_state_change, format_verification, format_vote, OutputFormat,
 };
 
 pub async fn handle(
@@ -16,148 +34,560 @@ pub async fn handle(
     json: bool,
 ) -> Result<()> {
     let format = OutputFormat::from_json_flag(json);
+    let exact_labels = config.exact_labels();
 
     match cmd {
         IssueCommand::List {
             all,
-            open,
+            include_orgs,
+            watched,
+            jobs,
+            offline,
+            state,
+            open: _,
             closed,
             repo,
             label,
+            label_all,
+            author,
+            milestone,
+            no_milestone,
+            created_after,
+            created_before,
+            updated_since,
+            sort,
+            desc,
+            limit,
+            count,
+            quiet,
         } => {
-            let state = if closed {
-                "closed"
-            } else if open {
-                "open"
+            // --open matches the default, so only --closed changes anything
+            let params = ListIssuesParams {
+                state: if closed { StateFilter::Closed } else { state },
+                labels: LabelFilter {
+                    exact: exact_labels,
+                    ..LabelFilter::parse(&label, label_all)
+                },
+                author: author.map(|a| resolve_user(&a, profile)),
+                milestone: match milestone {
+                    Some(name) => Some(MilestoneFilter::Matching(name)),
+                    None if no_milestone => Some(MilestoneFilter::None),
+                    None => None,
+                },
+                created_after,
+                created_before,
+                updated_since,
+                // Sorting needs every issue before the top N can be picked
+                limit: if sort.is_some() { None } else { limit },
+                ..Default::default()
+            };
+
+            let scope = if watched {
+                RepoScope::Watched
             } else {
-                "open" // default
+                RepoScope::accessible(include_orgs || config.include_org_repos())
+            };
+            let client = &client.clone().with_offline(offline);
+            if count {
+                let repo = if all || watched { None } else { Some(config.get_repo(repo.as_deref())?) };
+                handle_count(client, repo, scope, jobs, &params, quiet, &format).await
+            } else if all || watched {
+                let sort = sort.map(|key| (key, desc));
+                handle_list_all(client, &params, scope, jobs, sort, limit, &format).await
+            } else {
+                let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+                let sort = sort.map(|key| (key, desc));
+                handle_list_repo(client, &owner, &repo_name, &params, sort, limit, &format).await
+            }
+        }
+
+        IssueCommand::Search {
+            query,
+            all,
+            jobs,
+            state,
+            repo,
+            mut label,
+            label_all,
+        } => {
+            let query = parse_search_query(&query, &mut label);
+            let params = ListIssuesParams {
+                state,
+                labels: LabelFilter {
+                    exact: exact_labels,
+                    ..LabelFilter::parse(&label, label_all)
+                },
+                query,
+                ..Default::default()
             };
 
             if all {
-                handle_list_all(client, state, &label, &format).await
+                let scope = RepoScope::accessible(config.include_org_repos());
+                handle_list_all(client, &params, scope, jobs, None, None, &format).await
             } else {
                 let (owner, repo_name) = config.get_repo(repo.as_deref())?;
-                handle_list_repo(client, &owner, &repo_name, state, &label, &format).await
+                handle_list_repo(client, &owner, &repo_name, &params, None, None, &format).await
             }
         }
 
-        IssueCommand::Show { number, repo } => {
+        IssueCommand::Show {
+            number,
+            repo,
+            web,
+            offline,
+        } => {
             let (owner, repo_name) = config.get_repo(repo.as_deref())?;
-            handle_show(client, &owner, &repo_name, number, &format).await
+            let client = &client.clone().with_offline(offline);
+            if web {
+                let issue = client.get_issue(&owner, &repo_name, number).await?;
+                open_web(&issue.html_url, &format)
+            } else {
+                handle_show(client, &owner, &repo_name, number, &format).await
+            }
         }
 
         IssueCommand::Create {
             title,
             repo,
             body,
-            label,
+            body_file,
+            template,
+            mut label,
+            exact,
+            create_missing,
+            lint,
+            claim,
+            assign,
+            attach,
+            web,
+            no_signature,
+        } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            // Catch typos before anything is created; upload errors are reported per file
+            if let Some(missing) = attach.iter().find(|f| !f.is_file()) {
+                anyhow::bail!("Attachment not found: {}", missing.display());
+            }
+            let template = match template {
+                Some(name) => Some(load_template(client, config, &owner, &repo_name, &name).await?),
+                None => None,
+            };
+            let body = match (body, body_file) {
+                (_, Some(path)) => Some(read_body_file(&path)?),
+                (Some(body), None) => Some(body),
+                (None, None) if editor::is_interactive() => Some(editor::compose(
+                    template.as_deref().unwrap_or_default(),
+                    &format!("Write the body of issue \"{}\" above this line.", title),
+                )?),
+                (None, None) => template,
+            };
+            if lint {
+                check_lint(config, None, body.as_deref().unwrap_or(""), &format)?;
+            }
+
+            let assignee = if claim {
+                label.push(config.claim_label().to_string());
+                Some(profile.gogs_user.clone())
+            } else {
+                assign.map(|user| resolve_user(&user, profile))
+            };
+            if let Some(assignee) = assignee.as_deref().filter(|_| !claim) {
+                user::require_user(client, assignee, Some((&owner, &repo_name))).await?;
+            }
+
+            let draft = IssueDraft {
+                title,
+                body,
+                labels: label,
+                exact_labels: exact || exact_labels,
+                create_missing,
+                assignee,
+                attach,
+                web,
+            };
+            let profile = &unsigned_if(profile, no_signature);
+            handle_create(client, &owner, &repo_name, draft, profile, &format).await
+        }
+
+        IssueCommand::Edit {
+            number,
+            repo,
+            title,
+            body,
+            body_file,
+            yes,
+        } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            let body = match body_file {
+                Some(path) => Some(read_body_file(&path)?),
+                None => body,
+            };
+            handle_edit(client, config, &owner, &repo_name, number, title, body, yes, profile, &format).await
+        }
+
+        IssueCommand::EditComment {
+            number,
+            comment,
+            repo,
+            body,
+            body_file,
+            force_other_author,
+        } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            let text = match body_file {
+                Some(path) => read_body_file(&path)?,
+                None => body.unwrap_or_default(),
+            };
+            let target = CommentTarget::find(client, &owner, &repo_name, number, comment, profile).await?;
+            target.check_author(profile, force_other_author)?;
+
+            let body = if target.other_author {
+                format!(
+                    "{}\n\n_Edited by @{} {}; originally written by @{}._",
+                    text, profile.gogs_user, profile.current_signature(), target.comment.user.username
+                )
+            } else {
+                profile.sign_comment(&owner, &repo_name, number, profile.signed(&text))?
+            };
+            target.record(config, profile, "edit comment", Some(&body))?;
+            client.edit_comment(&owner, &repo_name, comment, &body).await?;
+
+            print!("{}", format_comment_change(&target.repo, number, &target.comment, "edited", &format));
+            Ok(())
+        }
+
+        IssueCommand::DeleteComment {
+            number,
+            comment,
+            repo,
+            force_other_author,
+        } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            let target = CommentTarget::find(client, &owner, &repo_name, number, comment, profile).await?;
+            target.check_author(profile, force_other_author)?;
+
+            target.record(config, profile, "delete comment", None)?;
+            client.delete_comment(&owner, &repo_name, comment).await?;
+
+            print!("{}", format_comment_change(&target.repo, number, &target.comment, "deleted", &format));
+            Ok(())
+        }
+
+        IssueCommand::Export {
+            repo,
+            state,
+            scrub,
+            output,
         } => {
             let (owner, repo_name) = config.get_repo(repo.as_deref())?;
-            handle_create(client, &owner, &repo_name, &title, body.as_deref(), label, profile, &format).await
+            handle_export(client, &owner, &repo_name, state, scrub, output.as_deref()).await
+        }
+
+        IssueCommand::Lint { number, repo } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            handle_lint(client, &owner, &repo_name, number, config, profile, &format).await
+        }
+
+        IssueCommand::Vote { number, repo, reason } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            handle_vote(client, &owner, &repo_name, number, reason.as_deref(), profile, &format).await
+        }
+
+        IssueCommand::Verify { number, repo } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            handle_verify(client, config, &owner, &repo_name, number, &format).await
+        }
+
+        IssueCommand::Comment {
+            number,
+            text,
+            body_file,
+            repo,
+            then_close,
+            then_label,
+            no_signature,
+        } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            let text = match (text, body_file) {
+                (Some(text), _) => text,
+                (None, Some(path)) => read_body_file(&path)?,
+                (None, None) if editor::is_interactive() => editor::compose(
+                    "",
+                    &format!("Write your comment on {}/{}#{} above this line.", owner, repo_name, number),
+                )?,
+                (None, None) => anyhow::bail!(
+                    "Comment text is required when not running in a terminal (or use --body-file)"
+                ),
+            };
+            let follow_up = FollowUp {
+                close: then_close,
+                labels: then_label,
+                exact_labels,
+            };
+            let profile = &unsigned_if(profile, no_signature);
+            handle_comment(
+                client, &owner, &repo_name, number, &text, &follow_up, profile, &format,
+            )
+            .await
+        }
+
+        IssueCommand::Handoff {
+            number,
+            to,
+            note,
+            repo,
+        } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            let to = resolve_user(&to, profile);
+            user::require_user(client, &to, Some((&owner, &repo_name))).await?;
+            handle_handoff(
+                client, config, &owner, &repo_name, number, &to, note.as_deref(), profile, &format,
+            )
+            .await
+        }
+
+        IssueCommand::UnassignAll { number, repo } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            handle_unassign_all(client, config, &owner, &repo_name, number, &format).await
         }
 
-        IssueCommand::Comment { number, text, repo } => {
+        IssueCommand::Bulk(cmd) => bulk::handle(cmd, client, config, profile, &format).await,
+
+        IssueCommand::Stream {
+            number,
+            repo,
+            interval,
+            max_bytes,
+            title,
+        } => {
             let (owner, repo_name) = config.get_repo(repo.as_deref())?;
-            handle_comment(client, &owner, &repo_name, number, &text, profile, &format).await
+            let log = stream::Log::new(title, max_bytes);
+            stream::handle(client, &owner, &repo_name, number, log, interval, profile, &format).await
+        }
+
+        IssueCommand::Close {
+            number: None,
+            repo,
+            comment,
+            label,
+            older_than,
+            all,
+            jobs,
+            dry_run,
+            yes,
+        } => {
+            let params = ListIssuesParams {
+                state: StateFilter::Open,
+                labels: LabelFilter {
+                    exact: exact_labels,
+                    ..LabelFilter::parse(&label, true)
+                },
+                created_before: older_than,
+                ..Default::default()
+            };
+            let repo = if all { None } else { Some(config.get_repo(repo.as_deref())?) };
+            let scope = RepoScope::accessible(config.include_org_repos());
+            let selection = Selection {
+                params,
+                repo,
+                scope,
+                jobs,
+                dry_run,
+                yes,
+            };
+            handle_close_matching(client, selection, comment.as_deref(), profile, &format).await
         }
 
-        IssueCommand::Close { number, repo } => {
+        IssueCommand::Close {
+            number: Some(number),
+            repo,
+            comment,
+            ..
+        } => {
             let (owner, repo_name) = config.get_repo(repo.as_deref())?;
-            handle_state_change(client, &owner, &repo_name, number, "closed", &format).await
+            handle_state_change(
+                client,
+                &owner,
+                &repo_name,
+                number,
+                IssueState::Closed,
+                comment.as_deref(),
+                profile,
+                &format,
+            )
+            .await
         }
 
-        IssueCommand::Reopen { number, repo } => {
+        IssueCommand::Reopen {
+            number,
+            repo,
+            comment,
+        } => {
             let (owner, repo_name) = config.get_repo(repo.as_deref())?;
-            handle_state_change(client, &owner, &repo_name, number, "open", &format).await
+            handle_state_change(
+                client,
+                &owner,
+                &repo_name,
+                number,
+                IssueState::Open,
+                comment.as_deref(),
+                profile,
+                &format,
+            )
+            .await
         }
 
-        IssueCommand::Label { number, label, repo } => {
+        IssueCommand::Label {
+            number,
+            label,
+            repo,
+            exact,
+            create_missing,
+        } => {
             let (owner, repo_name) = config.get_repo(repo.as_deref())?;
-            handle_add_label(client, &owner, &repo_name, number, &label, &format).await
+            let exact = exact || exact_labels;
+            handle_add_label(
+                client, &owner, &repo_name, number, &label, exact, create_missing, &format,
+            )
+            .await
         }
 
-        IssueCommand::Unlabel { number, label, repo } => {
+        IssueCommand::Unlabel {
+            number,
+            label,
+            repo,
+            exact,
+        } => {
             let (owner, repo_name) = config.get_repo(repo.as_deref())?;
-            handle_remove_label(client, &owner, &repo_name, number, &label, &format).await
+            let exact = exact || exact_labels;
+            handle_remove_label(client, &owner, &repo_name, number, &label, exact, &format).await
+        }
+    }
+}
+
+/// Resolve `@me` to the profile's Gogs user; other names pass through unchanged.
+fn resolve_user(name: &str, profile: &Profile) -> String {
+    if name == "@me" {
+        profile.gogs_user.clone()
+    } else {
+        name.trim_start_matches('@').to_string()
+    }
+}
+
+/// Split `label:NAME` terms out of a search query into `labels`, returning the remaining text.
+fn parse_search_query(query: &str, labels: &mut Vec<String>) -> Option<String> {
+    let mut words = Vec::new();
+    for word in query.split_whitespace() {
+        match word.strip_prefix("label:") {
+            Some(name) if !name.is_empty() => labels.push(name.to_string()),
+            _ => words.push(word),
         }
     }
+
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" "))
+    }
+}
+
+/// Order issues by `key`, ascending unless `desc`. Ties keep their existing order.
+fn sort_issues<T>(items: &mut [T], key: IssueSort, desc: bool, issue: impl Fn(&T) -> &Issue) {
+    items.sort_by(|a, b| {
+        let (a, b) = (issue(a), issue(b));
+        let ordering = match key {
+            IssueSort::Created => a.created_at.cmp(&b.created_at),
+            IssueSort::Updated => a.updated_at.cmp(&b.updated_at),
+            IssueSort::Comments => a.comments.cmp(&b.comments),
+            IssueSort::Number => a.number.cmp(&b.number),
+        };
+        if desc {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
 }
 
 async fn handle_list_all(
     client: &GogsClient,
-    state: &str,
-    labels: &[String],
+    params: &ListIssuesParams,
+    scope: RepoScope,
+    jobs: usize,
+    sort: Option<(IssueSort, bool)>,
+    limit: Option<usize>,
     format: &OutputFormat,
 ) -> Result<()> {
-    let repos = client.list_user_repos().await?;
+    let mut all_issues = fetch_all_repo_issues(client, params, scope, jobs).await?;
 
-    // Spawn parallel tasks for each repo
-    let state = state.to_string();
-    let handles: Vec<_> = repos
-        .into_iter()
-        .map(|repo| {
-            let client = client.clone();
-            let state = state.clone();
-            let full_name = repo.full_name.clone();
-
-            tokio::spawn(async move {
-                let result = client
-                    .list_issues(&repo.owner.username, &repo.name, &state)
-                    .await;
-                (full_name, result)
-            })
-        })
-        .collect();
-
-    // Collect results
-    let mut all_issues = Vec::new();
-    for handle in handles {
-        match handle.await {
-            Ok((repo_name, Ok(mut issues))) => {
-                // Filter by labels if specified
-                if !labels.is_empty() {
-                    issues.retain(|issue| {
-                        labels.iter().any(|label| {
-                            issue.labels.iter().any(|l| l.name.eq_ignore_ascii_case(label))
-                        })
-                    });
-                }
-                all_issues.push((repo_name, issues));
+    let output = match sort {
+        // A global order can't be grouped by repo, so list issues flat
+        Some((key, desc)) => {
+            let mut flat: Vec<(String, Issue)> = all_issues
+                .into_iter()
+                .flat_map(|(repo, issues)| issues.into_iter().map(move |i| (repo.clone(), i)))
+                .collect();
+            sort_issues(&mut flat, key, desc, |(_, issue)| issue);
+            if let Some(limit) = limit {
+                flat.truncate(limit);
             }
-            Ok((repo_name, Err(e))) => {
-                eprintln!("Warning: Failed to list issues for {}: {}", repo_name, e);
-            }
-            Err(e) => {
-                eprintln!("Warning: Task failed: {}", e);
+            format_sorted_issue_list(flat, format)
+        }
+        None => {
+            if let Some(limit) = limit {
+                truncate_groups(&mut all_issues, limit);
             }
+            format_issue_list(all_issues, format)
         }
-    }
-
-    // Sort by repo name for consistent output
-    all_issues.sort_by(|a, b| a.0.cmp(&b.0));
-
-    let output = format_issue_list(all_issues, format);
+    };
     print!("{}", output);
     Ok(())
 }
 
+/// Keep only the first `limit` issues across all groups, in order.
+fn truncate_groups(groups: &mut Vec<(String, Vec<Issue>)>, limit: usize) {
+    let mut remaining = limit;
+    for (_, issues) in groups.iter_mut() {
+        issues.truncate(remaining);
+        remaining -= issues.len();
+    }
+    groups.retain(|(_, issues)| !issues.is_empty());
+}
+
+/// List issues from every repository in `scope`, `jobs` at a time, sorted
+/// by repo name.
+async fn fetch_all_repo_issues(
+    client: &GogsClient,
+    params: &ListIssuesParams,
+    scope: RepoScope,
+    jobs: usize,
+) -> Result<Vec<(String, Vec<Issue>)>> {
+    let params = params.clone();
+    for_each_repo(client, scope, jobs, move |client, repo| {
+        let params = params.clone();
+        async move {
+            client
+                .list_issues(&repo.owner.username, &repo.name, &params)
+                .await
+        }
+    })
+    .await
+}
+
 async fn handle_list_repo(
     client: &GogsClient,
     owner: &str,
     repo: &str,
-    state: &str,
-    labels: &[String],
+    params: &ListIssuesParams,
+    sort: Option<(IssueSort, bool)>,
+    limit: Option<usize>,
     format: &OutputFormat,
 ) -> Result<()> {
-    let mut issues = client.list_issues(owner, repo, state).await?;
-
-    // Filter by labels if specified
-    if !labels.is_empty() {
-        issues.retain(|issue| {
-            labels.iter().any(|label| {
-                issue.labels.iter().any(|l| l.name.eq_ignore_ascii_case(label))
-            })
-        });
+    let mut issues = client.list_issues(owner, repo, params).await?;
+    if let Some((key, desc)) = sort {
+        sort_issues(&mut issues, key, desc, |issue| issue);
+    }
+    if let Some(limit) = limit {
+        issues.truncate(limit);
     }
 
     let repo_name = format!("{}/{}", owner, repo);
@@ -166,6 +596,40 @@ async fn handle_list_repo(
     Ok(())
 }
 
+/// Count issues in one repository, or in all of `scope` when `repo` is None.
+async fn handle_count(
+    client: &GogsClient,
+    repo: Option<(String, String)>,
+    scope: RepoScope,
+    jobs: usize,
+    params: &ListIssuesParams,
+    quiet: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    let counts = match repo {
+        Some((owner, repo_name)) => {
+            let count = client.count_issues(&owner, &repo_name, params).await?;
+            vec![(format!("{}/{}", owner, repo_name), count)]
+        }
+        None => {
+            let params = params.clone();
+            for_each_repo(client, scope, jobs, move |client, repo| {
+                let params = params.clone();
+                async move {
+                    client
+                        .count_issues(&repo.owner.username, &repo.name, &params)
+                        .await
+                }
+            })
+            .await?
+        }
+    };
+
+    let output = format_issue_counts(&counts, quiet, format);
+    print!("{}", output);
+    Ok(())
+}
+
 async fn handle_show(
     client: &GogsClient,
     owner: &str,
@@ -181,81 +645,860 @@ async fn handle_show(
     Ok(())
 }
 
+/// `profile`, posting without a signature when `no_signature` is set.
+fn unsigned_if(profile: &Profile, no_signature: bool) -> Profile {
+    let mut profile = profile.clone();
+    if no_signature {
+        profile.signature_position = Position::None;
+    }
+    profile
+}
+
+/// A new issue as given on the command line, before labels are resolved to IDs.
+struct IssueDraft {
+    title: String,
+    body: Option<String>,
+    labels: Vec<String>,
+    exact_labels: bool,
+    create_missing: bool,
+    assignee: Option<String>,
+    attach: Vec<PathBuf>,
+    web: bool,
+}
+
 async fn handle_create(
     client: &GogsClient,
     owner: &str,
     repo: &str,
-    title: &str,
-    body: Option<&str>,
-    labels: Vec<String>,
+    draft: IssueDraft,
     profile: &Profile,
     format: &OutputFormat,
 ) -> Result<()> {
     // Prepend signature to body
-    let body_with_sig = match body {
-        Some(b) => format!("{} {}", profile.signature, b),
-        None => profile.signature.clone(),
+    let body_with_sig = profile.signed(draft.body.as_deref().unwrap_or_default());
+
+    let mut label_ids = Vec::new();
+    for name in &draft.labels {
+        let label = if draft.create_missing {
+            find_or_create_label(client, owner, repo, name, draft.exact_labels).await?
+        } else {
+            find_label(client, owner, repo, name, draft.exact_labels).await?
+        };
+        label_ids.push(label.id);
+    }
+
+    // The profile's own labels are a convenience, so a repository without them isn't an error
+    for name in &profile.default_labels {
+        if draft.labels.iter().any(|l| label_name_matches(l, name, draft.exact_labels)) {
+            continue;
+        }
+        let label = if draft.create_missing {
+            Some(find_or_create_label(client, owner, repo, name, draft.exact_labels).await?)
+        } else {
+            lookup_label(client, owner, repo, name, draft.exact_labels).await?
+        };
+        match label {
+            Some(label) if !label_ids.contains(&label.id) => label_ids.push(label.id),
+            Some(_) => {}
+            None => eprintln!(
+                "Warning: Profile label '{}' not found in {}/{}; skipped",
+                name, owner, repo
+            ),
+        }
+    }
+
+    let params = CreateIssueParams {
+        title: draft.title,
+        body: Some(body_with_sig.clone()),
+        assignee: draft.assignee,
+        labels: label_ids,
     };
+    let mut issue = client.create_issue(owner, repo, &params).await?;
 
-    let issue = client
-        .create_issue(owner, repo, title, Some(&body_with_sig), labels)
-        .await?;
+    // Assets need an issue to hang off, so upload afterwards and link them in
+    let mut uploaded = Vec::new();
+    let mut failed = Vec::new();
+    for file in &draft.attach {
+        match client.upload_issue_attachment(owner, repo, issue.number, file).await {
+            Ok(attachment) => uploaded.push(attachment),
+            Err(e) => failed.push((file.display().to_string(), format!("{:#}", e))),
+        }
+    }
+
+    if !uploaded.is_empty() {
+        let params = UpdateIssueParams {
+            body: Some(format!("{}{}", body_with_sig, attachment_links(&uploaded))),
+            ..Default::default()
+        };
+        match client.update_issue(owner, repo, issue.number, &params).await {
+            Ok(updated) => issue = updated,
+            Err(e) => eprintln!("Warning: Failed to link attachments from issue body: {:#}", e),
+        }
+    }
+
+    let output = format_created_issue(&issue, &uploaded, &failed, format);
+    print!("{}", output);
+
+    // The JSON output already carries html_url, so only open a browser for humans
+    if draft.web && matches!(format, OutputFormat::Human) {
+        open_web(&issue.html_url, format)?;
+    }
+    Ok(())
+}
+
+/// Markdown appended to an issue body for its uploaded attachments.
+fn attachment_links(attachments: &[Attachment]) -> String {
+    let mut links = String::from("\n\n### Attachments\n");
+    for attachment in attachments {
+        let is_image = Path::new(&attachment.name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                matches!(
+                    ext.to_ascii_lowercase().as_str(),
+                    "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg"
+                )
+            });
+        let bang = if is_image { "!" } else { "" };
+        links.push_str(&format!(
+            "- {}[{}]({})\n",
+            bang, attachment.name, attachment.browser_download_url
+        ));
+    }
+    links
+}
+
+async fn handle_export(
+    client: &GogsClient,
+    owner: &str,
+    repo: &str,
+    state: StateFilter,
+    scrub: bool,
+    output: Option<&Path>,
+) -> Result<()> {
+    let params = ListIssuesParams {
+        state,
+        ..Default::default()
+    };
+    let issues = client.list_issues(owner, repo, &params).await?;
+
+    let mut exported = Vec::with_capacity(issues.len());
+    for issue in issues {
+        let comments = if issue.comments > 0 {
+            client.list_comments(owner, repo, issue.number).await?
+        } else {
+            Vec::new()
+        };
+        exported.push((issue, comments));
+    }
+
+    if scrub {
+        let mut scrubber = Scrubber::new();
+        for (issue, comments) in &mut exported {
+            scrubber.issue(issue);
+            for comment in comments {
+                scrubber.comment(comment);
+            }
+        }
+    }
+
+    let json = format_export(&format!("{}/{}", owner, repo), &exported, scrub);
+    match output {
+        Some(path) => {
+            fs::write(path, json + "\n").with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("Exported {} issue(s) to {}", exported.len(), path.display());
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_handoff(
+    client: &GogsClient,
+    config: &Config,
+    owner: &str,
+    repo: &str,
+    number: i64,
+    to: &str,
+    note: Option<&str>,
+    profile: &Profile,
+    format: &OutputFormat,
+) -> Result<()> {
+    let issue = client.get_issue(owner, repo, number).await?;
+    let comments = client.list_comments(owner, repo, number).await?;
+
+    // Comment first so the new owner sees why before the notification for the reassignment
+    let body = profile.signed(&handoff_comment(&issue, &comments, to, note));
+    let body = profile.sign_comment(owner, repo, number, body)?;
+    let comment = client.create_comment(owner, repo, number, &body).await?;
+
+    let exact = config.exact_labels();
+    let labels_removed = remove_issue_label(client, owner, repo, &issue, config.claim_label(), exact).await?;
+
+    let mut labels_added = Vec::new();
+    let handoff_label = config.handoff_label();
+    if !issue.labels.iter().any(|l| label_name_matches(&l.name, handoff_label, exact)) {
+        let label = find_or_create_label(client, owner, repo, handoff_label, exact).await?;
+        client.add_labels_to_issue(owner, repo, number, vec![label.id]).await?;
+        labels_added.push(label.name);
+    }
+
+    let params = UpdateIssueParams {
+        assignee: Some(to.to_string()),
+        ..Default::default()
+    };
+    let issue = client.update_issue(owner, repo, number, &params).await?;
+
+    let action = format!("handed off to @{}", to);
+    let output = format_ownership_change(&issue, &action, Some(&comment), &labels_removed, &labels_added, format);
+    print!("{}", output);
+    Ok(())
+}
+
+async fn handle_unassign_all(
+    client: &GogsClient,
+    config: &Config,
+    owner: &str,
+    repo: &str,
+    number: i64,
+    format: &OutputFormat,
+) -> Result<()> {
+    let issue = client.get_issue(owner, repo, number).await?;
+    let labels_removed =
+        remove_issue_label(client, owner, repo, &issue, config.claim_label(), config.exact_labels()).await?;
+
+    // An empty assignee clears it
+    let params = UpdateIssueParams {
+        assignee: Some(String::new()),
+        ..Default::default()
+    };
+    let issue = client.update_issue(owner, repo, number, &params).await?;
+
+    let output = format_ownership_change(&issue, "unassigned", None, &labels_removed, &[], format);
+    print!("{}", output);
+    Ok(())
+}
+
+/// Remove every label on `issue` matching `name`, returning the names removed.
+async fn remove_issue_label(
+    client: &GogsClient,
+    owner: &str,
+    repo: &str,
+    issue: &Issue,
+    name: &str,
+    exact: bool,
+) -> Result<Vec<String>> {
+    let mut removed = Vec::new();
+    for label in issue.labels.iter().filter(|l| label_name_matches(&l.name, name, exact)) {
+        client.remove_label_from_issue(owner, repo, issue.number, label.id).await?;
+        removed.push(label.name.clone());
+    }
+    Ok(removed)
+}
+
+/// The body of a handoff comment: the note plus a digest of where the issue stands,
+/// so the new owner doesn't have to reconstruct it from the whole thread.
+fn handoff_comment(issue: &Issue, comments: &[Comment], to: &str, note: Option<&str>) -> String {
+    let mut body = format!("**Handoff to @{}**\n\n", to);
+    if let Some(note) = note {
+        body.push_str(note.trim());
+        body.push_str("\n\n");
+    }
+
+    body.push_str("Context:\n");
+    body.push_str(&format!("- State: {}\n", issue.state));
+    if let Some(assignee) = &issue.assignee {
+        body.push_str(&format!("- Previously assigned to: @{}\n", assignee.username));
+    }
+    if !issue.labels.is_empty() {
+        let names: Vec<&str> = issue.labels.iter().map(|l| l.name.as_str()).collect();
+        body.push_str(&format!("- Labels: {}\n", names.join(", ")));
+    }
+    // Absolute times, since the comment will be read long after it is written
+    body.push_str(&format!(
+        "- Comments: {} (last activity {})\n",
+        comments.len(),
+        issue.updated_at.format("%Y-%m-%d %H:%M UTC")
+    ));
+    if let Some(last) = comments.last() {
+        let first_line = last.body.lines().find(|l| !l.trim().is_empty()).unwrap_or_default();
+        let excerpt: String = first_line.chars().take(120).collect();
+        let ellipsis = if excerpt.len() < first_line.len() { "…" } else { "" };
+        body.push_str(&format!(
+            "- Last comment by @{}: {}{}\n",
+            last.user.username, excerpt, ellipsis
+        ));
+    }
+    body
+}
+
+/// Find issue template `name`: a `[templates]` entry in the config, then the
+/// repository's `.gogs/ISSUE_TEMPLATE/<name>.md`, then `.gogs/ISSUE_TEMPLATE.md`
+/// when asking for "default".
+async fn load_template(
+    client: &GogsClient,
+    config: &Config,
+    owner: &str,
+    repo: &str,
+    name: &str,
+) -> Result<String> {
+    if let Some(path) = config.templates.get(name) {
+        return fs::read_to_string(path)
+            .context(format!("Failed to read template '{}' from {:?}", name, path));
+    }
+
+    let stem = name.trim_end_matches(".md");
+    let mut candidates = vec![format!(".gogs/ISSUE_TEMPLATE/{}.md", stem)];
+    if stem == "default" {
+        candidates.push(".gogs/ISSUE_TEMPLATE.md".to_string());
+    }
+
+    for path in &candidates {
+        if let Some(file) = client.get_file_contents(owner, repo, path, None).await? {
+            let bytes = file.decode()?;
+            return String::from_utf8(bytes).context(format!("Template {} is not valid UTF-8", path));
+        }
+    }
+
+    anyhow::bail!(
+        "Template '{}' not found: no [templates] entry and no {} in {}/{}",
+        name,
+        candidates.join(" or "),
+        owner,
+        repo
+    )
+}
+
+/// Read a body from `path`, or from stdin when it is `-`.
+fn read_body_file(path: &Path) -> Result<String> {
+    if path == Path::new("-") {
+        let mut body = String::new();
+        std::io::stdin()
+            .read_to_string(&mut body)
+            .context("Failed to read body from stdin")?;
+        return Ok(body);
+    }
+    fs::read_to_string(path).context(format!("Failed to read body from {:?}", path))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_edit(
+    client: &GogsClient,
+    config: &Config,
+    owner: &str,
+    repo: &str,
+    number: i64,
+    title: Option<String>,
+    body: Option<String>,
+    yes: bool,
+    profile: &Profile,
+    format: &OutputFormat,
+) -> Result<()> {
+    // Keep the signature on rewritten bodies, as on create
+    let body = body.map(|b| profile.signed(&b));
+    if let Some(new) = &body {
+        if !confirm_body_change(client, config, owner, repo, number, new, yes, profile, format).await? {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
 
-    let output = format_created_issue(&issue, format);
+    let params = UpdateIssueParams {
+        title,
+        body,
+        ..Default::default()
+    };
+
+    let issue = client.update_issue(owner, repo, number, &params).await?;
+    let output = format_issue_updated(&issue, "updated", format);
     print!("{}", output);
     Ok(())
 }
 
+/// Show how `new` changes the issue's body and ask before replacing it, then
+/// journal the old body. A body often holds context a person wrote that
+/// exists nowhere else, so scripts must pass --yes to replace one.
+#[allow(clippy::too_many_arguments)]
+async fn confirm_body_change(
+    client: &GogsClient,
+    config: &Config,
+    owner: &str,
+    repo: &str,
+    number: i64,
+    new: &str,
+    yes: bool,
+    profile: &Profile,
+    format: &OutputFormat,
+) -> Result<bool> {
+    let issue = client.get_issue(owner, repo, number).await?;
+    let old = issue.body.as_deref().unwrap_or("");
+    if old == new {
+        return Ok(true);
+    }
+
+    if matches!(format, OutputFormat::Human) {
+        print!("{}", format_body_diff(old, new));
+    }
+    if !yes {
+        if !editor::is_interactive() {
+            anyhow::bail!(
+                "Refusing to replace the body of {}/{}#{} without confirmation. Pass --yes",
+                owner,
+                repo,
+                number
+            );
+        }
+        if !confirm(&format!("Replace the body of {}/{}#{}?", owner, repo, number))? {
+            return Ok(false);
+        }
+    }
+
+    config.journal().record(&JournalEntry {
+        at: chrono::Utc::now(),
+        user: profile.gogs_user.clone(),
+        signature: profile.current_signature(),
+        action: "edit issue".to_string(),
+        repo: format!("{}/{}", owner, repo),
+        issue: number,
+        comment: None,
+        author: issue.user.username.clone(),
+        other_author: false,
+        before: old.to_string(),
+        before_sha256: format!("{:x}", Sha256::digest(old.as_bytes())),
+        after: Some(new.to_string()),
+    })?;
+    Ok(true)
+}
+
+async fn handle_lint(
+    client: &GogsClient,
+    owner: &str,
+    repo: &str,
+    number: i64,
+    config: &Config,
+    profile: &Profile,
+    format: &OutputFormat,
+) -> Result<()> {
+    let issue = client.get_issue(owner, repo, number).await?;
+    let body = issue.body.as_deref().unwrap_or("");
+
+    // Bodies created by gog carry the signature prefix; lint what the author wrote
+    let body = profile.unsigned(body);
+
+    check_lint(config, Some(number), body, format)
+}
+
+/// Print lint results and fail if any rule is violated.
+fn check_lint(config: &Config, number: Option<i64>, body: &str, format: &OutputFormat) -> Result<()> {
+    let rules = config.lint.clone().unwrap_or_default();
+    let violations = lint_body(body, &rules);
+
+    if violations.is_empty() {
+        // Stay quiet on success when linting ahead of a create
+        if number.is_some() {
+            print!("{}", format_lint_result(number, &violations, format));
+        }
+        return Ok(());
+    }
+
+    print!("{}", format_lint_result(number, &violations, format));
+    anyhow::bail!("{} lint violation(s) found", violations.len())
+}
+
+/// Actions to apply to an issue once a comment has been posted.
+struct FollowUp {
+    close: bool,
+    labels: Vec<String>,
+    exact_labels: bool,
+}
+
+async fn handle_vote(
+    client: &GogsClient,
+    owner: &str,
+    repo: &str,
+    number: i64,
+    reason: Option<&str>,
+    profile: &Profile,
+    format: &OutputFormat,
+) -> Result<()> {
+    let mut comments = client.list_comments(owner, repo, number).await?;
+    let already = votes::has_voted(&comments, &profile.gogs_user, &profile.signature_pattern());
+
+    if !already {
+        let body = match reason {
+            Some(reason) => format!("{} {}\n\n{}", profile.current_signature(), votes::VOTE, reason),
+            None => format!("{} {}", profile.current_signature(), votes::VOTE),
+        };
+        let body = profile.sign_comment(owner, repo, number, body)?;
+        comments.push(client.create_comment(owner, repo, number, &body).await?);
+    }
+
+    let repo_name = format!("{}/{}", owner, repo);
+    print!("{}", format_vote(&repo_name, number, !already, votes::count(&comments), format));
+    Ok(())
+}
+
+async fn handle_verify(
+    client: &GogsClient,
+    config: &Config,
+    owner: &str,
+    repo: &str,
+    number: i64,
+    format: &OutputFormat,
+) -> Result<()> {
+    let keys = config.verification_keys();
+    if keys.is_empty() {
+        eprintln!("Warning: No signing keys configured; every signed comment will show as invalid");
+    }
+
+    let repo_name = format!("{}/{}", owner, repo);
+    let comments = client.list_comments(owner, repo, number).await?;
+    let results: Vec<(Comment, Provenance)> = comments
+        .into_iter()
+        .map(|c| {
+            let provenance = provenance::verify(&c, &repo_name, number, &keys);
+            (c, provenance)
+        })
+        .collect();
+
+    print!("{}", format_verification(&repo_name, number, &results, format));
+
+    let invalid = results.iter().filter(|(_, p)| *p == Provenance::Invalid).count();
+    if invalid > 0 {
+        anyhow::bail!("{} comment(s) on #{} have a signature that doesn't verify", invalid, number);
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn handle_comment(
     client: &GogsClient,
     owner: &str,
     repo: &str,
     number: i64,
     text: &str,
+    follow_up: &FollowUp,
     profile: &Profile,
     format: &OutputFormat,
 ) -> Result<()> {
+    // Resolve labels up front so a typo fails before anything is posted
+    let mut label_ids = Vec::new();
+    for name in &follow_up.labels {
+        label_ids.push(find_label(client, owner, repo, name, follow_up.exact_labels).await?.id);
+    }
+
     // Prepend signature to comment
-    let comment_with_sig = format!("{} {}", profile.signature, text);
+    let comment_with_sig = profile.signed(text);
+    let comment_with_sig = profile.sign_comment(owner, repo, number, comment_with_sig)?;
 
     let comment = client
         .create_comment(owner, repo, number, &comment_with_sig)
         .await?;
 
-    let output = format_created_comment(&comment, format);
+    let closed = if follow_up.close {
+        let params = UpdateIssueParams {
+            state: Some(IssueState::Closed),
+            ..Default::default()
+        };
+        Some(client.update_issue(owner, repo, number, &params).await?)
+    } else {
+        None
+    };
+
+    if !label_ids.is_empty() {
+        client.add_labels_to_issue(owner, repo, number, label_ids).await?;
+    }
+
+    let repo_name = format!("{}/{}", owner, repo);
+    let output = format_created_comment(
+        &repo_name,
+        number,
+        &comment,
+        closed.as_ref(),
+        &follow_up.labels,
+        format,
+    );
     print!("{}", output);
     Ok(())
 }
 
+/// A comment about to be edited or deleted.
+struct CommentTarget {
+    repo: String,
+    number: i64,
+    comment: Comment,
+    /// Someone other than the profile's user wrote it
+    other_author: bool,
+}
+
+impl CommentTarget {
+    /// Gogs has no endpoint for a single comment, so find it among the issue's.
+    async fn find(client: &GogsClient, owner: &str, repo: &str, number: i64, id: i64, profile: &Profile) -> Result<Self> {
+        let repo_name = format!("{}/{}", owner, repo);
+        let comment = client
+            .list_comments(owner, repo, number)
+            .await?
+            .into_iter()
+            .find(|c| c.id == id)
+            .ok_or_else(|| GogsError::NotFound(format!("comment {} on {}#{}", id, repo_name, number)))?;
+        Ok(Self {
+            repo: repo_name,
+            number,
+            other_author: !comment.user.username.eq_ignore_ascii_case(&profile.gogs_user),
+            comment,
+        })
+    }
+
+    /// Refuse to touch someone else's comment unless forced, and forcing
+    /// unless the profile allows it.
+    fn check_author(&self, profile: &Profile, force: bool) -> Result<()> {
+        let author = &self.comment.user.username;
+        if !self.other_author {
+            return Ok(());
+        }
+        if profile.forbid_other_authors {
+            anyhow::bail!(
+                "Comment {} was written by @{}, and this profile never changes other users' comments (forbid_other_authors)",
+                self.comment.id,
+                author
+            );
+        }
+        if !force {
+            anyhow::bail!(
+                "Comment {} was written by @{}, not you. Pass --force-other-author to change it anyway",
+                self.comment.id,
+                author
+            );
+        }
+        Ok(())
+    }
+
+    /// Journal the change before it is made; a forced change also says so on stderr.
+    fn record(&self, config: &Config, profile: &Profile, action: &str, after: Option<&str>) -> Result<()> {
+        let journal = config.journal();
+        journal.record(&JournalEntry {
+            at: chrono::Utc::now(),
+            user: profile.gogs_user.clone(),
+            signature: profile.current_signature(),
+            action: action.to_string(),
+            repo: self.repo.clone(),
+            issue: self.number,
+            comment: Some(self.comment.id),
+            author: self.comment.user.username.clone(),
+            other_author: self.other_author,
+            before: self.comment.body.clone(),
+            before_sha256: format!("{:x}", Sha256::digest(self.comment.body.as_bytes())),
+            after: after.map(str::to_string),
+        })?;
+        if self.other_author {
+            eprintln!(
+                "Warning: Changing @{}'s comment {} on {}#{} (journal: {})",
+                self.comment.user.username,
+                self.comment.id,
+                self.repo,
+                self.number,
+                journal.location().unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Look up a repository label by name.
+///
+/// Without `exact`, case is ignored, but an exact match wins and several
+/// case-insensitive matches are reported as ambiguous.
+pub(crate) async fn find_label(
+    client: &GogsClient,
+    owner: &str,
+    repo: &str,
+    name: &str,
+    exact: bool,
+) -> Result<Label> {
+    lookup_label(client, owner, repo, name, exact)
+        .await?
+        .with_context(|| format!("Label '{}' not found in repository", name))
+}
+
+/// Like `find_label`, but creates the label when the repository doesn't have it.
+async fn find_or_create_label(
+    client: &GogsClient,
+    owner: &str,
+    repo: &str,
+    name: &str,
+    exact: bool,
+) -> Result<Label> {
+    if let Some(label) = lookup_label(client, owner, repo, name, exact).await? {
+        return Ok(label);
+    }
+
+    let color = default_label_color(name);
+    let label = client
+        .create_label(owner, repo, name, &color)
+        .await
+        .with_context(|| format!("Failed to create label '{}'", name))?;
+    eprintln!("Created label '{}' ({}) in {}/{}", label.name, label.color, owner, repo);
+    Ok(label)
+}
+
+async fn lookup_label(
+    client: &GogsClient,
+    owner: &str,
+    repo: &str,
+    name: &str,
+    exact: bool,
+) -> Result<Option<Label>> {
+    let repo_labels = client.list_repo_labels(owner, repo).await?;
+
+    if let Some(label) = repo_labels.iter().find(|l| l.name == name) {
+        return Ok(Some(label.clone()));
+    }
+
+    let mut matches: Vec<Label> = repo_labels
+        .into_iter()
+        .filter(|l| label_name_matches(&l.name, name, exact))
+        .collect();
+
+    match matches.len() {
+        0 => Ok(None),
+        1 => Ok(Some(matches.remove(0))),
+        _ => {
+            let names: Vec<&str> = matches.iter().map(|l| l.name.as_str()).collect();
+            anyhow::bail!(
+                "Label '{}' is ambiguous: matches {}. Use the exact name",
+                name,
+                names.join(", ")
+            )
+        }
+    }
+}
+
+/// Which issues a filter-based operation applies to, and how to confirm it.
+struct Selection {
+    params: ListIssuesParams,
+    /// A single repository, or every one in `scope` when None
+    repo: Option<(String, String)>,
+    scope: RepoScope,
+    /// Repositories queried at once when `repo` is None
+    jobs: usize,
+    dry_run: bool,
+    yes: bool,
+}
+
+/// Filter, preview, confirm, then close every matching issue.
+async fn handle_close_matching(
+    client: &GogsClient,
+    selection: Selection,
+    comment: Option<&str>,
+    profile: &Profile,
+    format: &OutputFormat,
+) -> Result<()> {
+    let matches = match &selection.repo {
+        Some((owner, repo)) => {
+            let issues = client.list_issues(owner, repo, &selection.params).await?;
+            vec![(format!("{}/{}", owner, repo), issues)]
+        }
+        None => fetch_all_repo_issues(client, &selection.params, selection.scope, selection.jobs).await?,
+    };
+    let total: usize = matches.iter().map(|(_, issues)| issues.len()).sum();
+
+    // With --json, only the dry run shows the selection; otherwise the results describe it
+    if selection.dry_run || matches!(format, OutputFormat::Human) {
+        print!("{}", format_issue_list(matches.clone(), format));
+    }
+    if selection.dry_run || total == 0 {
+        return Ok(());
+    }
+
+    if !selection.yes {
+        if !editor::is_interactive() {
+            anyhow::bail!("Refusing to close {} issue(s) without confirmation. Pass --yes", total);
+        }
+        if !confirm(&format!("Close {} issue(s)?", total))? {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    let targets = matches
+        .into_iter()
+        .flat_map(|(repo, issues)| {
+            let (owner, name) = repo.split_once('/').map(|(o, n)| (o.to_string(), n.to_string())).unwrap_or_default();
+            issues.into_iter().map(move |i| (owner.clone(), name.clone(), i.number))
+        })
+        .collect();
+    let comment = comment.map(|text| profile.signed(text));
+    let results = bulk::close_all(client, targets, comment, profile.signer()?).await;
+
+    print!("{}", format_bulk_results("close", &results, format));
+    if shutdown::requested() {
+        return Err(shutdown::interrupted(bulk::progress(&results)));
+    }
+    let failed = results.iter().filter(|(_, _, r)| r.is_err()).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} issues could not be closed", failed, results.len());
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn handle_state_change(
     client: &GogsClient,
     owner: &str,
     repo: &str,
     number: i64,
-    state: &str,
+    state: IssueState,
+    comment: Option<&str>,
+    profile: &Profile,
     format: &OutputFormat,
 ) -> Result<()> {
-    let issue = client.update_issue(owner, repo, number, Some(state)).await?;
-    let action = if state == "closed" { "closed" } else { "reopened" };
-    let output = format_issue_updated(&issue, action, format);
+    // Comment first, so the reason is in the thread even if the state change fails
+    let comment = match comment {
+        Some(text) => {
+            let mut body = profile.signed(text);
+            if profile.notify_author && state == IssueState::Closed {
+                let issue = client.get_issue(owner, repo, number).await?;
+                if !issue.user.username.eq_ignore_ascii_case(&profile.gogs_user) {
+                    body.push_str(&format!("\n\ncc @{}", issue.user.username));
+                }
+            }
+            let body = profile.sign_comment(owner, repo, number, body)?;
+            Some(client.create_comment(owner, repo, number, &body).await?)
+        }
+        None => None,
+    };
+
+    let params = UpdateIssueParams {
+        state: Some(state),
+        ..Default::default()
+    };
+    let issue = client.update_issue(owner, repo, number, &params).await?;
+    let action = match state {
+        IssueState::Closed => "closed",
+        IssueState::Open => "reopened",
+    };
+    let output = format_state_change(&issue, action, comment.as_ref(), format);
     print!("{}", output);
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_add_label(
     client: &GogsClient,
     owner: &str,
     repo: &str,
     number: i64,
     label_name: &str,
+    exact: bool,
+    create_missing: bool,
     format: &OutputFormat,
 ) -> Result<()> {
-    // Get all labels from repo to find the label ID
-    let repo_labels = client.list_repo_labels(owner, repo).await?;
-    let label = repo_labels
-        .iter()
-        .find(|l| l.name.eq_ignore_ascii_case(label_name))
-        .context(format!("Label '{}' not found in repository", label_name))?;
+    let label = if create_missing {
+        find_or_create_label(client, owner, repo, label_name, exact).await?
+    } else {
+        find_label(client, owner, repo, label_name, exact).await?
+    };
 
     let _labels = client.add_labels_to_issue(owner, repo, number, vec![label.id]).await?;
 
@@ -276,14 +1519,10 @@ async fn handle_remove_label(
     repo: &str,
     number: i64,
     label_name: &str,
+    exact: bool,
     format: &OutputFormat,
 ) -> Result<()> {
-    // Get all labels from repo to find the label ID
-    let repo_labels = client.list_repo_labels(owner, repo).await?;
-    let label = repo_labels
-        .iter()
-        .find(|l| l.name.eq_ignore_ascii_case(label_name))
-        .context(format!("Label '{}' not found in repository", label_name))?;
+    let label = find_label(client, owner, repo, label_name, exact).await?;
 
     client.remove_label_from_issue(owner, repo, number, label.id).await?;
 
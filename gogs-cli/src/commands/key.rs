@@ -0,0 +1,100 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::api::{key_fingerprint, GogsClient};
+use crate::cli::KeyCommand;
+use crate::commands::confirm;
+use crate::editor;
+use crate::error::GogsError;
+use crate::output::{format_key_change, format_key_list, OutputFormat};
+
+pub async fn handle(cmd: KeyCommand, client: &GogsClient, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        KeyCommand::List => {
+            let keys = client.list_keys().await?;
+            print!("{}", format_key_list(&keys, &format));
+        }
+        KeyCommand::Add { path, title } => {
+            let key = read_public_key(&path)?;
+            let fingerprint = key_fingerprint(&key);
+
+            // Provisioning scripts rerun; the server refuses a key it already has
+            let keys = client.list_keys().await?;
+            if let Some(existing) = keys.iter().find(|k| k.fingerprint() == fingerprint) {
+                print!("{}", format_key_change(existing, "already registered", &format));
+                return Ok(());
+            }
+
+            let title = title
+                .or_else(|| key_comment(&key))
+                .or_else(|| path.file_name().map(|n| n.to_string_lossy().into_owned()))
+                .unwrap_or_else(|| "gog".to_string());
+            let added = client.add_key(&title, &key).await?;
+            print!("{}", format_key_change(&added, "added", &format));
+        }
+        KeyCommand::Delete { id, yes } => {
+            let keys = client.list_keys().await?;
+            let key = keys
+                .into_iter()
+                .find(|k| k.id == id)
+                .ok_or_else(|| GogsError::NotFound(format!("SSH key {}", id)))?;
+
+            if !yes {
+                if !editor::is_interactive() {
+                    anyhow::bail!("Refusing to delete SSH key {} without confirmation. Pass --yes", id);
+                }
+                // Machines using the key lose access, so say which key it is
+                if !confirm(&format!("Delete SSH key {} '{}'?", key.id, key.title))? {
+                    println!("Aborted.");
+                    return Ok(());
+                }
+            }
+
+            client.delete_key(id).await?;
+            print!("{}", format_key_change(&key, "deleted", &format));
+        }
+    }
+    Ok(())
+}
+
+/// Read the key line from a `.pub` file, or from stdin for "-".
+fn read_public_key(path: &Path) -> Result<String> {
+    let contents = if path == Path::new("-") {
+        let mut contents = String::new();
+        std::io::stdin()
+            .read_to_string(&mut contents)
+            .context("Failed to read key from stdin")?;
+        contents
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Failed to read key from {}", path.display()))?
+    };
+
+    if contents.contains("PRIVATE KEY") {
+        anyhow::bail!(
+            "{} is a private key; pass the public key instead (usually the same name ending in .pub)",
+            path.display()
+        );
+    }
+    let key = contents
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .with_context(|| format!("{} is empty", path.display()))?;
+    if key_fingerprint(key).is_none() {
+        anyhow::bail!(
+            "{} is not an OpenSSH public key (expected e.g. \"ssh-ed25519 AAAA... user@host\")",
+            path.display()
+        );
+    }
+    Ok(key.to_string())
+}
+
+/// The comment after the key data, usually user@host.
+fn key_comment(key: &str) -> Option<String> {
+    let comment: Vec<&str> = key.split_whitespace().skip(2).collect();
+    (!comment.is_empty()).then(|| comment.join(" "))
+}
@@ -0,0 +1,179 @@
+use anyhow::Result;
+
+use crate::api::{default_label_color, GogsClient};
+use crate::cli::LabelCommand;
+use crate::commands::confirm;
+use crate::commands::issue::find_label;
+use crate::config::{parse_repo, Config};
+use crate::editor;
+use crate::labels::{LabelChange, LabelManifest};
+use crate::output::{format_label, format_label_list, format_label_sync, OutputFormat};
+use crate::shutdown;
+
+pub async fn handle(
+    cmd: LabelCommand,
+    client: &GogsClient,
+    config: &Config,
+    json: bool,
+) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        LabelCommand::List { repo } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            let mut labels = client.list_repo_labels(&owner, &repo_name).await?;
+            labels.sort_by_key(|l| l.name.to_lowercase());
+            print!("{}", format_label_list(&labels, &format));
+            Ok(())
+        }
+
+        LabelCommand::Create { name, color, repo } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            let color = color.unwrap_or_else(|| default_label_color(&name));
+            let label = client
+                .create_label(&owner, &repo_name, &name, &color)
+                .await?;
+            print!("{}", format_label(&label, "created", &format));
+            Ok(())
+        }
+
+        LabelCommand::Edit {
+            name,
+            new_name,
+            color,
+            repo,
+            exact,
+        } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            let exact = exact || config.exact_labels();
+            let label = find_label(client, &owner, &repo_name, &name, exact).await?;
+            let label = client
+                .edit_label(
+                    &owner,
+                    &repo_name,
+                    label.id,
+                    new_name.as_deref(),
+                    color.as_deref(),
+                )
+                .await?;
+            print!("{}", format_label(&label, "updated", &format));
+            Ok(())
+        }
+
+        LabelCommand::Sync {
+            from,
+            repos,
+            all,
+            exact,
+            dry_run,
+        } => {
+            let manifest = LabelManifest::load(&from)?;
+            let exact = exact || config.exact_labels();
+
+            let targets = if all {
+                let mut names: Vec<String> = client
+                    .list_user_repos()
+                    .await?
+                    .into_iter()
+                    .map(|r| r.full_name)
+                    .collect();
+                names.sort();
+                names
+            } else if repos.is_empty() {
+                let (owner, repo_name) = config.get_repo(None)?;
+                vec![format!("{}/{}", owner, repo_name)]
+            } else {
+                repos
+            };
+
+            // A repository is only left half-synced by an error, never by an interrupt
+            shutdown::graceful();
+            let total = targets.len();
+            let mut results = Vec::new();
+            for target in targets {
+                if shutdown::requested() {
+                    break;
+                }
+                let result = sync_repo(client, &manifest, &target, exact, dry_run).await;
+                results.push((target, result));
+            }
+
+            print!("{}", format_label_sync(&results, dry_run, &format));
+            if shutdown::requested() {
+                let progress = format!("{} of {} repositories done", results.len(), total);
+                return Err(shutdown::interrupted(progress));
+            }
+
+            let failed = results.iter().filter(|(_, r)| r.is_err()).count();
+            if failed > 0 {
+                anyhow::bail!("{} of {} repositories failed to sync", failed, results.len());
+            }
+            Ok(())
+        }
+
+        LabelCommand::Delete {
+            name,
+            repo,
+            exact,
+            yes,
+        } => {
+            let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+            let exact = exact || config.exact_labels();
+            let label = find_label(client, &owner, &repo_name, &name, exact).await?;
+
+            if !yes {
+                if !editor::is_interactive() {
+                    anyhow::bail!(
+                        "Refusing to delete label '{}' without confirmation. Pass --yes",
+                        label.name
+                    );
+                }
+                let question = format!(
+                    "Delete label '{}' from {}/{} and all its issues?",
+                    label.name, owner, repo_name
+                );
+                if !confirm(&question)? {
+                    println!("Aborted.");
+                    return Ok(());
+                }
+            }
+
+            client.delete_label(&owner, &repo_name, label.id).await?;
+            print!("{}", format_label(&label, "deleted", &format));
+            Ok(())
+        }
+    }
+}
+
+/// Plan and (unless `dry_run`) apply the changes for one repository, stopping
+/// at the first failed request.
+async fn sync_repo(
+    client: &GogsClient,
+    manifest: &LabelManifest,
+    target: &str,
+    exact: bool,
+    dry_run: bool,
+) -> Result<Vec<LabelChange>> {
+    let (owner, repo) = parse_repo(target)?;
+    let existing = client.list_repo_labels(&owner, &repo).await?;
+    let changes = manifest.plan(&existing, exact);
+    if dry_run {
+        return Ok(changes);
+    }
+
+    for change in &changes {
+        match change {
+            LabelChange::Create { name, color } => {
+                client.create_label(&owner, &repo, name, color).await?;
+            }
+            LabelChange::Update {
+                id, name, color, ..
+            } => {
+                client
+                    .edit_label(&owner, &repo, *id, name.as_deref(), color.as_deref())
+                    .await?;
+            }
+        }
+    }
+    Ok(changes)
+}
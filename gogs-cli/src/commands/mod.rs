@@ -1,31 +1,734 @@
 use anyhow::Result;
+use std::future::Future;
+use std::io::{self, Write};
+use tokio::task::JoinSet;
 
-use crate::api::GogsClient;
-use crate::cli::{Cli, Commands};
-use crate::config::Config;
+use crate::api::quarantine;
+use crate::api::{GogsClient, Repository};
+use crate::browser;
+use crate::cli::{Cli, Commands, ConfigCommand, DevCommand, QuarantineCommand};
+use crate::config::{Config, Profile};
+use crate::coverage;
+use crate::editor;
+use crate::output::{
+    self, format_coverage, format_quarantine, format_quarantine_cleared, format_version, format_whoami, OutputFormat,
+};
+use crate::version::{self, BuildInfo};
+use crate::time::{self, TimeDisplay};
 
+pub mod admin;
+pub mod auth;
+pub mod bench;
+pub mod branch;
+pub mod bulk;
+pub mod capture;
+pub mod collaborator;
+pub mod config;
+pub mod daemon;
+pub mod doctor;
+pub mod file;
+pub mod foreach;
 pub mod init;
 pub mod issue;
+pub mod key;
+pub mod label;
+pub mod open_items;
+pub mod org;
+pub mod profile;
+pub mod release;
 pub mod repo;
+pub mod report;
+pub mod stream;
+pub mod token;
+pub mod update;
+pub mod user;
 
 pub async fn dispatch(cli: Cli) -> Result<()> {
+    time::configure(TimeDisplay {
+        // Asking for a timezone only makes sense with absolute timestamps
+        absolute: cli.absolute || cli.tz.is_some(),
+        locale: time::locale_from_env(),
+        tz: cli.tz.as_deref().map(time::parse_tz).transpose()?,
+    });
+
+    output::configure_color(cli.color);
+    output::configure_canonical(cli.canonical);
+    if let Some(path) = &cli.labels_file {
+        output::configure_captions(path)?;
+    }
+
+    let target = Target {
+        server: cli.server.as_deref(),
+        profile: cli.profile.as_deref(),
+        token: cli.token.as_deref(),
+        command_path: &cli.command_path,
+    };
+
     match cli.command {
-        Commands::Init => init::handle_init().await,
+        Commands::Init {
+            skip_test,
+            url,
+            user,
+            role,
+            signature,
+            default_repo,
+            yes,
+            from_git,
+        } => {
+            let options = init::Options {
+                url,
+                profile: cli.profile,
+                user,
+                token: cli.token,
+                role,
+                signature,
+                default_repo,
+                yes,
+                skip_test,
+                from_git,
+            };
+            init::handle_init(options).await
+        }
 
-        Commands::Issue(cmd) => {
+        Commands::Profile(cmd) => profile::handle(cmd, cli.json).await,
+
+        Commands::Config(ConfigCommand::Check { offline }) => {
+            config::handle_check(cli.profile.as_deref(), &cli.command_path, offline, cli.json).await
+        }
+
+        Commands::Config(cmd) => {
+            // The file as written, not pointed at --server or the profile's server
             let config = Config::load()?;
-            let profile = config.get_profile(cli.profile.as_deref())?;
-            let client = GogsClient::new(config.server.url.clone(), profile.token.clone());
+            let name = cli.profile.as_deref();
+            // A profile limited to some commands mustn't be able to widen its own limits
+            if let Ok(profile) = config.get_profile(name) {
+                profile.check_allowed(config.profile_name(name), &cli.command_path)?;
+            }
+            config::handle(cmd, config, cli.json)
+        }
 
-            issue::handle(cmd, &client, &config, profile, cli.json).await
+        Commands::Whoami => {
+            let config = load_config(cli.server.as_deref(), cli.profile.as_deref())?;
+            let name = cli.profile.as_deref();
+            let profile = authenticated_profile(&config, name, cli.server.as_deref(), cli.token.as_deref())?;
+            // Confirming identity is always allowed, like doctor
+            let user = config.client(&profile).get_current_user().await?;
+            let profile_name = config.get_profile(name).is_ok().then(|| config.profile_name(name));
+            // A token written in the config is used before the keyring or token_cmd
+            let in_file = config.get_profile(name).is_ok_and(|p| !p.token.is_empty());
+            let token = match (&cli.token, &profile.keyring, &profile.token_cmd) {
+                (Some(_), _, _) => "--token",
+                _ if in_file => "profile",
+                (None, Some(_), _) => "keyring",
+                (None, None, Some(_)) => "token_cmd",
+                (None, None, None) => "profile",
+            };
+
+            print!(
+                "{}",
+                format_whoami(
+                    &user,
+                    profile_name,
+                    &profile.gogs_user,
+                    &config.server.url,
+                    token,
+                    &OutputFormat::from_json_flag(cli.json)
+                )
+            );
+            Ok(())
+        }
+
+        Commands::Issue(cmd) => {
+            let (config, profile) = target.open(true).await?;
+            let profile = lift_comment_limit(profile, cli.override_limit)?;
+            let client = target.client(&config, &profile);
+
+            let result = issue::handle(cmd, &client, &config, &profile, cli.json).await;
+            note_stale(&client);
+            result
         }
 
         Commands::Repo(cmd) => {
-            let config = Config::load()?;
-            let profile = config.get_profile(cli.profile.as_deref())?;
-            let client = GogsClient::new(config.server.url.clone(), profile.token.clone());
+            let (config, _, client) = target.client_for(true).await?;
+            repo::handle(cmd, &client, &config, cli.json).await
+        }
+
+        Commands::Org(cmd) => {
+            let (_, _, client) = target.client_for(false).await?;
+            org::handle(cmd, &client, cli.json).await
+        }
+
+        Commands::User(cmd) => {
+            let (_, profile, client) = target.client_for(true).await?;
+            user::handle(cmd, &client, &profile, cli.json).await
+        }
+
+        Commands::Auth(cmd) => {
+            if cli.server.is_some() {
+                anyhow::bail!("auth stores tokens for configured profiles; it can't be used with --server");
+            }
+            auth::handle(cmd, cli.profile.as_deref(), cli.token.as_deref()).await
+        }
+
+        Commands::Token(cmd) => {
+            let config = load_config(cli.server.as_deref(), cli.profile.as_deref())?;
+            token::handle(cmd, &config, cli.profile.as_deref(), cli.json).await
+        }
+
+        Commands::Admin(cmd) => {
+            let (_, profile, client) = target.client_for(true).await?;
+            admin::handle(cmd, &client, &profile, cli.json).await
+        }
+
+        Commands::Key(cmd) => {
+            let (_, _, client) = target.client_for(false).await?;
+            key::handle(cmd, &client, cli.json).await
+        }
+
+        Commands::Branch(cmd) => {
+            let (config, _, client) = target.client_for(false).await?;
+            branch::handle(cmd, &client, &config, cli.json).await
+        }
+
+        Commands::Quarantine(cmd) => {
+            let (config, profile) = target.profile()?;
+
+            let quarantine = config.quarantine(&profile);
+            let format = OutputFormat::from_json_flag(cli.json);
+            match cmd {
+                QuarantineCommand::List => print!("{}", format_quarantine(&quarantine.entries(), &format)),
+                QuarantineCommand::Clear { repo } => {
+                    let removed = quarantine.clear(repo.as_deref());
+                    print!("{}", format_quarantine_cleared(repo.as_deref(), removed, &format));
+                }
+            }
+            Ok(())
+        }
+
+        Commands::File(cmd) => {
+            let (_, profile, client) = target.client_for(false).await?;
+            file::handle(cmd, &client, &profile, cli.json).await
+        }
+
+        Commands::Release(cmd) => {
+            let (config, _, client) = target.client_for(false).await?;
+            release::handle(cmd, &client, &config, cli.json).await
+        }
 
-            repo::handle(cmd, &client, cli.json).await
+        Commands::Label(cmd) => {
+            let (config, _, client) = target.client_for(true).await?;
+            label::handle(cmd, &client, &config, cli.json).await
         }
+
+        Commands::Report(cmd) => {
+            let (config, _, client) = target.client_for(false).await?;
+            report::handle(cmd, &client, &config, cli.json).await
+        }
+
+        Commands::OpenItems {
+            repo,
+            watched,
+            include_orgs,
+            limit,
+            jobs,
+        } => {
+            let (config, profile, client) = target.client_for(true).await?;
+
+            let scope = if watched {
+                RepoScope::Watched
+            } else {
+                RepoScope::accessible(include_orgs || config.include_org_repos())
+            };
+            open_items::handle(repo, scope, limit, jobs, &client, &config, &profile, cli.json).await
+        }
+
+        Commands::Capture {
+            repo,
+            title,
+            label,
+            lines,
+            fingerprint,
+            command,
+        } => {
+            let (config, profile, client) = target.client_for(false).await?;
+
+            let options = capture::Options {
+                title,
+                labels: label,
+                lines,
+                fingerprint,
+                exact_labels: config.exact_labels(),
+            };
+            capture::handle(command, repo, options, &client, &config, &profile, cli.json).await
+        }
+
+        Commands::Foreach {
+            repos,
+            jobs,
+            dry_run,
+            command,
+        } => {
+            let (_, _, client) = target.client_for(false).await?;
+
+            // Every run gets the same global options; a token travels in the
+            // environment rather than on command lines others can see
+            let mut global_args = Vec::new();
+            if let Some(profile) = &cli.profile {
+                global_args.extend(["--profile".to_string(), profile.clone()]);
+            }
+            if let Some(tz) = &cli.tz {
+                global_args.extend(["--tz".to_string(), tz.clone()]);
+            }
+            if let Some(path) = &cli.labels_file {
+                global_args.extend(["--labels-file".to_string(), path.display().to_string()]);
+            }
+            // Runs write to a pipe, so `auto` would always mean no color there
+            let color = if output::color_enabled() { "always" } else { "never" };
+            global_args.extend(["--color".to_string(), color.to_string()]);
+            for (set, flag) in [(cli.json, "--json"), (cli.absolute, "--absolute"), (cli.canonical, "--canonical")] {
+                if set {
+                    global_args.push(flag.to_string());
+                }
+            }
+            let mut env = Vec::new();
+            if let Some(server) = &cli.server {
+                env.push(("GOGS_SERVER".to_string(), server.clone()));
+            }
+            if let Some(token) = &cli.token {
+                env.push(("GOGS_TOKEN".to_string(), token.clone()));
+            }
+
+            let options = foreach::Options {
+                patterns: repos,
+                jobs,
+                dry_run,
+                command,
+                global_args,
+                env,
+            };
+            foreach::handle(&client, options, &OutputFormat::from_json_flag(cli.json)).await
+        }
+
+        Commands::Bench {
+            requests,
+            concurrency,
+            endpoint,
+            repo,
+        } => {
+            let (config, _, client) = target.client_for(false).await?;
+            // Cached responses would flatter the server
+            let client = client.without_daemon();
+
+            bench::handle(endpoint, requests, concurrency, repo, &client, &config, cli.json).await
+        }
+
+        Commands::Daemon {
+            stop,
+            status,
+            cache_ttl,
+        } => daemon::handle(stop, status, cache_ttl, cli.json).await,
+
+        Commands::Version { verbose } => {
+            let build = BuildInfo::current();
+            let format = OutputFormat::from_json_flag(cli.json);
+            if !verbose {
+                print!("{}", format_version(&build, false, None, &format));
+                return Ok(());
+            }
+
+            // Build details are useful on their own; the server part needs a working config
+            let name = cli.profile.as_deref();
+            let server = match load_config(cli.server.as_deref(), cli.profile.as_deref()) {
+                Ok(config) => match authenticated_profile(&config, name, cli.server.as_deref(), cli.token.as_deref()) {
+                    Ok(profile) => Some(version::server_report(&config.client(&profile), &config).await),
+                    Err(_) => None,
+                },
+                Err(_) => None,
+            };
+            print!("{}", format_version(&build, true, server.as_ref(), &format));
+            Ok(())
+        }
+
+        Commands::SelfUpdate { channel, check } => {
+            // Not subject to allowed_commands: keeping agents current is the point
+            let config = load_config(cli.server.as_deref(), cli.profile.as_deref())?;
+            let name = cli.profile.as_deref();
+            let profile = authenticated_profile(&config, name, cli.server.as_deref(), cli.token.as_deref())?;
+            let client = config.client(&profile);
+
+            update::handle(channel, check, &client, &config, cli.json).await
+        }
+
+        Commands::Doctor(cmd) => {
+            let config = load_config(cli.server.as_deref(), cli.profile.as_deref())?;
+            // Connectivity can be checked without credentials; the API step just says less
+            let name = cli.profile.as_deref();
+            let profile = authenticated_profile(&config, name, cli.server.as_deref(), cli.token.as_deref()).ok();
+
+            doctor::handle(cmd, &config, profile.as_ref(), cli.json).await
+        }
+
+        Commands::Dev(DevCommand::Coverage { tag, all }) => {
+            let report = coverage::report(tag.as_deref())?;
+            print!("{}", format_coverage(&report, all, &OutputFormat::from_json_flag(cli.json)));
+            Ok(())
+        }
+    }
+}
+
+/// The global options that say which server and profile a command runs as.
+struct Target<'a> {
+    server: Option<&'a str>,
+    profile: Option<&'a str>,
+    token: Option<&'a str>,
+    command_path: &'a str,
+}
+
+impl Target<'_> {
+    /// The config and selected profile, once the profile is known to be allowed
+    /// the command. The profile's token isn't fetched yet.
+    fn profile(&self) -> Result<(Config, Profile)> {
+        let config = load_config(self.server, self.profile)?;
+        let profile = select_profile(&config, self.profile, self.server, self.token)?;
+        profile.check_allowed(config.profile_name(self.profile), self.command_path)?;
+        Ok((config, profile))
+    }
+
+    /// `profile`, with its token. With `find_user`, a bare profile's username
+    /// is filled in too.
+    async fn open(&self, find_user: bool) -> Result<(Config, Profile)> {
+        let (config, mut profile) = self.profile()?;
+        profile.load_token(config.profile_name(self.profile))?;
+        let profile = if find_user { complete_profile(&config, profile).await? } else { profile };
+        Ok((config, profile))
+    }
+
+    /// A client for `profile`, retrying as configured for the command.
+    fn client(&self, config: &Config, profile: &Profile) -> GogsClient {
+        config.client(profile).with_retry_mode(config.retry_mode(self.command_path))
+    }
+
+    /// `open`, and a client for the profile.
+    async fn client_for(&self, find_user: bool) -> Result<(Config, Profile, GogsClient)> {
+        let (config, profile) = self.open(find_user).await?;
+        let client = self.client(&config, &profile);
+        Ok((config, profile, client))
     }
 }
+
+/// Load the config with the checkout's `.gog.toml` over it, pointed at the
+/// profile's server or at `--server`: a `[servers]` name, or a URL for an
+/// ad-hoc server that doesn't need a config file at all.
+fn load_config(server: Option<&str>, profile: Option<&str>) -> Result<Config> {
+    let mut config = match server {
+        Some(_) if !Config::config_path()?.exists() => Config::default(),
+        _ => Config::load()?,
+    };
+    let dir = std::env::current_dir().ok();
+    if let Some(dir) = &dir {
+        config.apply_project(dir)?;
+    }
+
+    match server {
+        Some(name) if config.servers.contains_key(name) => config.select_server(name, profile)?,
+        Some(name) if !name.contains("://") => {
+            let mut known: Vec<&str> = config.servers.keys().map(String::as_str).collect();
+            known.sort();
+            anyhow::bail!(
+                "--server '{}' is neither a URL nor a server in [servers] (configured: {})",
+                name,
+                if known.is_empty() { "none".to_string() } else { known.join(", ") }
+            );
+        }
+        Some(url) => config.server.url = url.trim_end_matches('/').to_string(),
+        None => {
+            let name = config.profile_name(profile).to_string();
+            if let Some(server) = config.profiles.get(&name).and_then(|p| p.server.clone()) {
+                config.select_server(&server, Some(&name))?;
+            }
+        }
+    }
+    // Inside a checkout of a repository on this server, that's the one meant
+    if let Some(dir) = &dir {
+        config.apply_remote(dir);
+    }
+    Ok(config)
+}
+
+/// The selected profile with `--token` applied, or a bare one when only a
+/// token is given and no profile is configured. A token kept in the keyring
+/// or fetched by `token_cmd` is left for `Profile::load_token`, so commands
+/// that make no requests, or may not run at all, don't go looking for it.
+fn select_profile(
+    config: &Config,
+    name: Option<&str>,
+    server: Option<&str>,
+    token: Option<&str>,
+) -> Result<Profile> {
+    if server.is_some_and(|s| !config.servers.contains_key(s)) && token.is_none() {
+        // A profile's token was issued by a configured server; don't hand it to another
+        anyhow::bail!("--server (or GOGS_SERVER/GOGS_URL) needs --token (or GOGS_TOKEN)");
+    }
+
+    let mut profile = match config.get_profile(name) {
+        Ok(profile) => profile.clone(),
+        Err(_) if token.is_some() && name.is_none() => Profile {
+            role: "Ad hoc".to_string(),
+            ..Default::default()
+        },
+        Err(e) => return Err(e),
+    };
+    if let Some(token) = token {
+        profile.token = token.to_string();
+    }
+    for label in config.project_labels() {
+        if !profile.default_labels.contains(label) {
+            profile.default_labels.push(label.clone());
+        }
+    }
+    Ok(profile)
+}
+
+/// `select_profile`, with the profile's token fetched.
+fn authenticated_profile(
+    config: &Config,
+    name: Option<&str>,
+    server: Option<&str>,
+    token: Option<&str>,
+) -> Result<Profile> {
+    let mut profile = select_profile(config, name, server, token)?;
+    profile.load_token(config.profile_name(name))?;
+    Ok(profile)
+}
+
+/// Fill in a bare profile's username from the server, and sign as that user.
+async fn complete_profile(config: &Config, mut profile: Profile) -> Result<Profile> {
+    if profile.gogs_user.is_empty() {
+        profile.gogs_user = config.client(&profile).get_current_user().await?.username;
+        profile.signature = format!("[{}]", profile.gogs_user);
+    }
+    Ok(profile)
+}
+
+/// Drop the profile's comment limit for `--override`. Only a person at a
+/// terminal may do that; the limit exists to stop agents stuck in a loop.
+fn lift_comment_limit(mut profile: Profile, override_limit: bool) -> Result<Profile> {
+    if override_limit {
+        if !editor::is_interactive() {
+            anyhow::bail!("--override only works from a terminal; wait for the comment limit to reset");
+        }
+        profile.comment_limit = None;
+    }
+    Ok(profile)
+}
+
+/// Ask a yes/no question on the terminal, defaulting to no.
+pub fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N]: ", question);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Print `question` and read one trimmed line.
+pub fn prompt(question: &str) -> Result<String> {
+    print!("{}", question);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
+
+/// Like `prompt`, but what is typed isn't echoed, e.g. for a password.
+/// Scripts can set GOGS_PASSWORD instead.
+pub fn prompt_password(question: &str) -> Result<String> {
+    if let Ok(password) = std::env::var("GOGS_PASSWORD") {
+        return Ok(password);
+    }
+    if !editor::is_interactive() {
+        anyhow::bail!("No terminal to ask for a password on; set GOGS_PASSWORD");
+    }
+    prompt_hidden(question)
+}
+
+/// Ask `question` and read the answer without echoing it.
+pub fn prompt_hidden(question: &str) -> Result<String> {
+    print!("{}", question);
+    io::stdout().flush()?;
+    let hidden = set_echo(false);
+    let mut input = String::new();
+    let read = io::stdin().read_line(&mut input);
+    if hidden {
+        set_echo(true);
+        // The Enter that ended the password wasn't echoed either
+        println!();
+    }
+    read?;
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Turn terminal echo on or off; returns whether that worked.
+fn set_echo(on: bool) -> bool {
+    #[cfg(unix)]
+    {
+        std::process::Command::new("stty")
+            .arg(if on { "echo" } else { "-echo" })
+            .stdin(std::process::Stdio::inherit())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = on;
+        false
+    }
+}
+
+/// Say so when what was shown came from the response cache rather than the
+/// server, and how old it is.
+fn note_stale(client: &GogsClient) {
+    if let Some(since) = client.stale_since() {
+        eprintln!(
+            "Note: Showing cached data from {}; it may be out of date",
+            time::format_timestamp(&since)
+        );
+    }
+}
+
+/// Open `url` in the browser, or just print it in JSON mode.
+pub fn open_web(url: &str, format: &OutputFormat) -> Result<()> {
+    print!("{}", output::format_web_url(url, format));
+    if matches!(format, OutputFormat::Human) {
+        browser::open(url)?;
+    }
+    Ok(())
+}
+
+/// Repositories queried, runs started or bulk operations in flight at once,
+/// unless `--jobs` says otherwise.
+pub const DEFAULT_JOBS: usize = 8;
+
+/// Parse a `--jobs` value: how many requests or runs may be in flight, at least 1.
+pub fn parse_jobs(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(jobs) => Ok(jobs),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Which repositories a command covering "all" of them fans out to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoScope {
+    /// Every repository the server lists for the user
+    Accessible,
+    /// Those plus every repository of the user's organizations
+    WithOrgs,
+    /// Only the repositories the user watches
+    Watched,
+}
+
+impl RepoScope {
+    /// Accessible repositories, with the organizations' when `include_orgs`.
+    pub fn accessible(include_orgs: bool) -> Self {
+        if include_orgs {
+            Self::WithOrgs
+        } else {
+            Self::Accessible
+        }
+    }
+
+    async fn repos(self, client: &GogsClient) -> Result<Vec<Repository>> {
+        match self {
+            Self::Accessible => client.list_accessible_repos(false).await,
+            Self::WithOrgs => client.list_accessible_repos(true).await,
+            Self::Watched => client.list_watched_repos().await,
+        }
+    }
+}
+
+/// Run `task` against every repository in `scope` in parallel, at most
+/// `jobs` at a time.
+///
+/// Failures are reported as warnings and the repository is skipped. A
+/// repository that keeps failing with "not found" or "access denied" is
+/// quarantined: skipped without a warning for a while (see `gog quarantine`).
+/// Results are sorted by repository full name for consistent output.
+pub async fn for_each_repo<T, F, Fut>(
+    client: &GogsClient,
+    scope: RepoScope,
+    jobs: usize,
+    task: F,
+) -> Result<Vec<(String, T)>>
+where
+    F: Fn(GogsClient, Repository) -> Fut,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    if jobs == 0 {
+        anyhow::bail!("--jobs must be at least 1");
+    }
+    let mut repos = scope.repos(client).await?;
+
+    let quarantine = client.quarantine();
+    if let Some(quarantine) = quarantine {
+        let skipped = quarantine.active();
+        let before = repos.len();
+        repos.retain(|repo| !skipped.contains(&repo.full_name.to_lowercase()));
+        if repos.len() < before {
+            eprintln!(
+                "Note: Skipping {} quarantined repo(s); see 'gog quarantine list'",
+                before - repos.len()
+            );
+        }
+    }
+
+    // Keep at most `jobs` tasks alive, starting the next repo as one finishes
+    let mut pending = repos.into_iter();
+    let mut tasks = JoinSet::new();
+    let mut results = Vec::new();
+    let mut persistent = Vec::new();
+    loop {
+        while tasks.len() < jobs {
+            let Some(repo) = pending.next() else { break };
+            let full_name = repo.full_name.clone();
+            let future = task(client.clone(), repo);
+            tasks.spawn(async move { (full_name, future.await) });
+        }
+        let Some(joined) = tasks.join_next().await else { break };
+        match joined {
+            Ok((repo_name, Ok(value))) => results.push((repo_name, value)),
+            Ok((repo_name, Err(e))) => {
+                eprintln!("Warning: Failed to query {}: {}", repo_name, e);
+                if quarantine::is_persistent(&e) {
+                    persistent.push((repo_name, e.to_string()));
+                }
+            }
+            Err(e) => {
+                eprintln!("Warning: Task failed: {}", e);
+            }
+        }
+    }
+
+    if let Some(quarantine) = quarantine {
+        let succeeded: Vec<String> = results.iter().map(|(name, _)| name.clone()).collect();
+        for entry in quarantine.record(&succeeded, &persistent) {
+            if let Some(until) = entry.until {
+                eprintln!(
+                    "Warning: {} failed {} times in a row; quarantined, next try {}",
+                    entry.repo,
+                    entry.failures,
+                    time::format_timestamp(&until)
+                );
+            }
+        }
+    }
+
+    // Sort by repo name for consistent output
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(results)
+}
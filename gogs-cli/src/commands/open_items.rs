@@ -0,0 +1,106 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::api::{Comment, GogsClient, Issue, ListIssuesParams};
+use crate::commands::{for_each_repo, RepoScope};
+use crate::config::{Config, Profile};
+use crate::open_items::{self, OpenItem};
+use crate::output::{format_open_items, OutputFormat};
+
+#[allow(clippy::too_many_arguments)]
+pub async fn handle(
+    repo: Option<String>,
+    scope: RepoScope,
+    limit: Option<usize>,
+    jobs: usize,
+    client: &GogsClient,
+    config: &Config,
+    profile: &Profile,
+    json: bool,
+) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+    let params = ListIssuesParams::default();
+
+    let issues: Vec<(String, Issue)> = match repo {
+        Some(repo) => {
+            let (owner, name) = config.get_repo(Some(&repo))?;
+            let full_name = format!("{}/{}", owner, name);
+            client
+                .list_issues(&owner, &name, &params)
+                .await?
+                .into_iter()
+                .map(|issue| (full_name.clone(), issue))
+                .collect()
+        }
+        None => for_each_repo(client, scope, jobs, move |client, repo| {
+            let params = params.clone();
+            async move {
+                client
+                    .list_issues(&repo.owner.username, &repo.name, &params)
+                    .await
+            }
+        })
+        .await?
+        .into_iter()
+        .flat_map(|(repo, issues)| issues.into_iter().map(move |issue| (repo.clone(), issue)))
+        .collect(),
+    };
+
+    let mut items = collect_items(client, issues, &profile.gogs_user, jobs).await;
+    open_items::prioritize(&mut items);
+    if let Some(limit) = limit {
+        items.truncate(limit);
+    }
+
+    print!("{}", format_open_items(&profile.gogs_user, &items, &format));
+    Ok(())
+}
+
+/// Keep the issues that concern `username`. Comments are read only where they
+/// could add a reason: an issue already assigned needs no mention to qualify.
+async fn collect_items(client: &GogsClient, issues: Vec<(String, Issue)>, username: &str, jobs: usize) -> Vec<OpenItem> {
+    let permits = Arc::new(Semaphore::new(jobs));
+    let mut tasks = JoinSet::new();
+
+    for (index, (repo, issue)) in issues.iter().enumerate() {
+        let assigned = issue
+            .assignee
+            .as_ref()
+            .is_some_and(|a| a.username.eq_ignore_ascii_case(username));
+        if issue.comments == 0 || assigned {
+            continue;
+        }
+        let client = client.clone();
+        let permits = permits.clone();
+        let repo = repo.clone();
+        let number = issue.number;
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let (owner, name) = repo.split_once('/').unwrap_or((&repo, ""));
+            (index, client.list_comments(owner, name, number).await)
+        });
+    }
+
+    let mut comments: Vec<Vec<Comment>> = issues.iter().map(|_| Vec::new()).collect();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, Ok(listed))) => comments[index] = listed,
+            Ok((index, Err(e))) => {
+                let (repo, issue) = &issues[index];
+                eprintln!("Warning: Failed to read comments on {}#{}: {}", repo, issue.number, e);
+            }
+            Err(e) => eprintln!("Warning: Task failed: {}", e),
+        }
+    }
+
+    issues
+        .into_iter()
+        .zip(comments)
+        .filter_map(|((repo, issue), comments)| {
+            let reasons = open_items::reasons(&issue, &comments, username);
+            (!reasons.is_empty()).then(|| open_items::item(&repo, issue, reasons))
+        })
+        .collect()
+}
@@ -0,0 +1,29 @@
+use anyhow::Result;
+
+use crate::api::GogsClient;
+use crate::cli::OrgCommand;
+use crate::output::{format_org, format_org_list, format_repo_list, format_user_list, OutputFormat};
+
+pub async fn handle(cmd: OrgCommand, client: &GogsClient, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        OrgCommand::List => {
+            let orgs = client.list_user_orgs().await?;
+            print!("{}", format_org_list(&orgs, &format));
+        }
+        OrgCommand::Show { org } => {
+            let org = client.get_org(&org).await?;
+            print!("{}", format_org(&org, &format));
+        }
+        OrgCommand::Repos { org, limit } => {
+            let repos = client.list_org_repos(&org, limit).await?;
+            print!("{}", format_repo_list(&repos, &format));
+        }
+        OrgCommand::Members { org } => {
+            let members = client.list_org_members(&org).await?;
+            print!("{}", format_user_list(&members, &format));
+        }
+    }
+    Ok(())
+}
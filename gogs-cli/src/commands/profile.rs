@@ -0,0 +1,108 @@
+use anyhow::{Context, Result};
+
+use crate::cli::ProfileCommand;
+use crate::commands::init::{print_preset_summary, test_connection};
+use crate::commands::prompt;
+use crate::config::Config;
+use crate::editor;
+use crate::output::{format_presets, OutputFormat};
+use crate::presets;
+use crate::provenance;
+
+pub async fn handle(cmd: ProfileCommand, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        ProfileCommand::Add {
+            name,
+            preset,
+            user,
+            token,
+            signature,
+            default,
+            skip_test,
+        } => {
+            let mut config = Config::load()?;
+            if config.profiles.contains_key(&name) {
+                anyhow::bail!("Profile '{}' already exists", name);
+            }
+            let preset = presets::find(&config.presets, &preset).with_context(|| {
+                format!("Unknown preset '{}'. Run 'gog profile presets' to list them", preset)
+            })?;
+
+            let user = match user {
+                Some(user) => user,
+                None => ask("Gogs username: ", "--user")?,
+            };
+            let token = match token {
+                Some(token) => token,
+                None => ask("API token (from Gogs settings): ", "--token")?,
+            };
+
+            let mut profile = preset.profile(user, token);
+            if let Some(signature) = signature {
+                profile.signature = signature;
+            }
+
+            if !skip_test {
+                println!("Testing connection to {}...", config.server.url);
+                let client = config.client(&profile);
+                if !test_connection(&client, &profile.gogs_user).await? {
+                    println!("Aborted.");
+                    return Ok(());
+                }
+            }
+
+            println!("Profile '{}': {} {}", name, profile.role, profile.signature);
+            print_preset_summary(&profile);
+            config.profiles.insert(name.clone(), profile);
+            if default {
+                config.defaults.profile = Some(name.clone());
+            }
+            config.save().context("Failed to save configuration")?;
+
+            println!("Profile '{}' added to {:?}", name, Config::config_path()?);
+            Ok(())
+        }
+
+        ProfileCommand::Presets => {
+            let config = Config::load().unwrap_or_default();
+            let output = format_presets(&presets::all(&config.presets), &format);
+            print!("{}", output);
+            Ok(())
+        }
+
+        ProfileCommand::Keygen { name, replace } => {
+            let mut config = Config::load()?;
+            let profile = config
+                .profiles
+                .get_mut(&name)
+                .with_context(|| format!("Profile '{}' not found", name))?;
+            if profile.signing_key.is_some() && !replace {
+                anyhow::bail!("Profile '{}' already has a signing key; pass --replace for a new one", name);
+            }
+
+            let key = provenance::generate_key()?;
+            profile.signing_key = Some(key.clone());
+            config.save().context("Failed to save configuration")?;
+
+            println!("Comments from profile '{}' are now signed.", name);
+            println!("To verify them elsewhere, add to that config:\n");
+            println!("[trusted_keys]\n{} = \"{}\"", name, key);
+            Ok(())
+        }
+    }
+}
+
+/// Prompt for a required value, or explain which flag supplies it when there
+/// is no terminal to ask on.
+fn ask(question: &str, flag: &str) -> Result<String> {
+    if !editor::is_interactive() {
+        anyhow::bail!("No terminal to prompt on; pass {}", flag);
+    }
+    let value = prompt(question)?;
+    if value.is_empty() {
+        anyhow::bail!("A value is required");
+    }
+    Ok(value)
+}
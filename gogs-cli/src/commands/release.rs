@@ -0,0 +1,84 @@
+use anyhow::{Context, Result};
+
+use crate::api::GogsClient;
+use crate::cli::ReleaseCommand;
+use crate::config::Config;
+use crate::glob;
+use crate::output::{format_release_download, format_release_upload, OutputFormat};
+
+pub async fn handle(cmd: ReleaseCommand, client: &GogsClient, config: &Config, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        ReleaseCommand::Upload { tag, files, repo } => {
+            let (owner, name) = config.get_repo(repo.as_deref())?;
+            let release = client.get_release_by_tag(&owner, &name, &tag).await?;
+            let existing = client.list_release_assets(&owner, &name, release.id).await?;
+
+            let mut uploaded = Vec::new();
+            let mut failed = Vec::new();
+            for file in &files {
+                let file_name = file.file_name().map(|n| n.to_string_lossy().into_owned());
+                if let Some(asset) = existing.iter().find(|a| Some(&a.name) == file_name.as_ref()) {
+                    let error = format!("{} already has an asset named {}", tag, asset.name);
+                    failed.push((file.display().to_string(), error));
+                    continue;
+                }
+                match client.upload_release_asset(&owner, &name, release.id, file).await {
+                    Ok(asset) => uploaded.push(asset),
+                    Err(e) => failed.push((file.display().to_string(), format!("{:#}", e))),
+                }
+            }
+
+            print!("{}", format_release_upload(&tag, &uploaded, &failed, &format));
+            if !failed.is_empty() {
+                anyhow::bail!("{} of {} file(s) were not uploaded", failed.len(), files.len());
+            }
+        }
+        ReleaseCommand::Download {
+            tag,
+            pattern,
+            dir,
+            repo,
+        } => {
+            let (owner, name) = config.get_repo(repo.as_deref())?;
+            let release = client.get_release_by_tag(&owner, &name, &tag).await?;
+            let assets = client.list_release_assets(&owner, &name, release.id).await?;
+
+            let wanted: Vec<_> = assets
+                .iter()
+                .filter(|a| pattern.is_empty() || pattern.iter().any(|p| glob::matches(p, &a.name)))
+                .collect();
+            if wanted.is_empty() {
+                let names: Vec<&str> = assets.iter().map(|a| a.name.as_str()).collect();
+                match names.is_empty() {
+                    true => anyhow::bail!("{} has no assets", tag),
+                    false => anyhow::bail!("No asset of {} matches (assets: {})", tag, names.join(", ")),
+                }
+            }
+
+            tokio::fs::create_dir_all(&dir)
+                .await
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            let mut downloaded = Vec::new();
+            for asset in wanted {
+                // Names come from the server; keep them inside the directory
+                let file_name = std::path::Path::new(&asset.name)
+                    .file_name()
+                    .with_context(|| format!("Asset name {:?} isn't a file name", asset.name))?;
+                let dest = dir.join(file_name);
+                if !json {
+                    eprintln!("Downloading {}...", asset.name);
+                }
+                let size = client
+                    .download_to(&asset.browser_download_url, &dest, |_, _| {})
+                    .await
+                    .with_context(|| format!("Failed to download {}", asset.name))?;
+                downloaded.push((asset.name.clone(), dest.display().to_string(), size));
+            }
+
+            print!("{}", format_release_download(&tag, &downloaded, &format));
+        }
+    }
+    Ok(())
+}
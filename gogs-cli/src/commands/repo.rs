@@ -1,20 +1,322 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use crate::api::GogsClient;
+use crate::api::{CreateRepoParams, EditRepoParams, GogsClient, MigrateRepoParams};
 use crate::cli::RepoCommand;
-use crate::output::{format_repo_list, OutputFormat};
+use crate::commands::{collaborator, open_web, prompt, prompt_hidden};
+use crate::config::{parse_repo, Config};
+use crate::editor;
+use crate::output::{
+    format_archive, format_created_repo, format_deleted_repo, format_edited_repo, format_forked_repo, format_migrated_repo, format_repo_detail, format_repo_list,
+    format_download_progress, format_forks, format_parent, format_star_change, format_watch_change, OutputFormat,
+};
 
-pub async fn handle(cmd: RepoCommand, client: &GogsClient, json: bool) -> Result<()> {
+/// Fork chains followed by `repo parent --root` before giving up.
+const MAX_FORK_DEPTH: usize = 20;
+
+/// Where `repo migrate` reads the source's password, so it stays off the command line.
+const MIGRATE_PASSWORD_VAR: &str = "GOGS_MIGRATE_PASSWORD";
+
+/// How often the download progress line is redrawn.
+const PROGRESS_EVERY: Duration = Duration::from_millis(200);
+
+pub async fn handle(cmd: RepoCommand, client: &GogsClient, config: &Config, json: bool) -> Result<()> {
     let format = OutputFormat::from_json_flag(json);
 
     match cmd {
-        RepoCommand::List => handle_list(client, &format).await,
+        RepoCommand::List { limit, starred, watched } => handle_list(client, limit, starred, watched, &format).await,
+
+        RepoCommand::Search { query, limit } => {
+            let repos = client.search_repos(&query, limit).await?;
+            print!("{}", format_repo_list(&repos, &format));
+            Ok(())
+        }
+
+        RepoCommand::Show { repo, web } => {
+            let (owner, name) = config.get_repo(repo.as_deref())?;
+            let repo = client.get_repo(&owner, &name).await?;
+            if web {
+                open_web(&repo.html_url, &format)
+            } else {
+                print!("{}", format_repo_detail(&repo, &format));
+                Ok(())
+            }
+        }
+
+        RepoCommand::Create {
+            name,
+            org,
+            description,
+            private,
+            init,
+            gitignore,
+            license,
+        } => {
+            let params = CreateRepoParams {
+                name,
+                description,
+                private,
+                auto_init: init,
+                gitignores: gitignore,
+                license,
+                readme: init.then(|| "Default".to_string()),
+            };
+            let repo = client.create_repo(org.as_deref(), &params).await?;
+            print!("{}", format_created_repo(&repo, &format));
+            Ok(())
+        }
+
+        RepoCommand::Edit {
+            repo,
+            description,
+            website,
+            default_branch,
+            private,
+            public,
+        } => {
+            let (owner, name) = parse_repo(&repo)?;
+            let params = EditRepoParams {
+                description,
+                website,
+                default_branch,
+                private: match (private, public) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                },
+            };
+            let repo = client.edit_repo(&owner, &name, &params).await?;
+            print!("{}", format_edited_repo(&repo, &format));
+            Ok(())
+        }
+
+        RepoCommand::Fork { repo, org } => {
+            let (owner, name) = parse_repo(&repo)?;
+            let fork = client.fork_repo(&owner, &name, org.as_deref()).await?;
+            print!("{}", format_forked_repo(&fork, &format!("{}/{}", owner, name), &format));
+            Ok(())
+        }
+
+        RepoCommand::Migrate {
+            url,
+            name,
+            org,
+            mirror,
+            private,
+            description,
+            auth_user,
+        } => {
+            let repo_name = match name {
+                Some(name) => name,
+                None => name_from_url(&url)
+                    .with_context(|| format!("Can't tell a repository name from '{}'; pass --name", url))?,
+            };
+            let uid = match &org {
+                Some(org) => client.get_org(org).await?.id,
+                None => client.get_current_user().await?.id,
+            };
+            let auth_password = match &auth_user {
+                Some(user) => Some(migrate_password(user)?),
+                None => None,
+            };
+            let params = MigrateRepoParams {
+                clone_addr: url.clone(),
+                auth_password,
+                auth_username: auth_user,
+                uid,
+                repo_name,
+                mirror,
+                private,
+                description,
+            };
+
+            // The server clones the whole repository before it answers
+            let client = client.clone().with_timeout(Duration::from_secs(600));
+            let repo = client.migrate_repo(&params).await?;
+            print!("{}", format_migrated_repo(&repo, &url, mirror, &format));
+            Ok(())
+        }
+
+        RepoCommand::Forks { repo, tree } => {
+            let (owner, name) = parse_repo(&repo)?;
+            let forks = client.list_fork_tree(&owner, &name, tree).await?;
+            print!("{}", format_forks(&repo, &forks, &format));
+            Ok(())
+        }
+
+        RepoCommand::Parent { repo, root } => {
+            let (owner, name) = config.get_repo(repo.as_deref())?;
+            let full_name = format!("{}/{}", owner, name);
+            let repository = client.get_repo(&owner, &name).await?;
+            let mut parent = match repository.parent {
+                Some(parent) if repository.fork => *parent,
+                _ => anyhow::bail!("{} is not a fork", full_name),
+            };
+
+            let mut via = Vec::new();
+            if root {
+                // A parent embedded in a repository doesn't say whether it is a fork itself
+                loop {
+                    let (parent_owner, parent_name) = parse_repo(&parent.full_name)?;
+                    let mut next = client.get_repo(&parent_owner, &parent_name).await?;
+                    match next.parent.take() {
+                        Some(_) if next.fork && via.len() >= MAX_FORK_DEPTH => {
+                            anyhow::bail!("Fork chain of {} is deeper than {} repositories", full_name, MAX_FORK_DEPTH)
+                        }
+                        Some(grandparent) if next.fork => {
+                            via.push(next.full_name);
+                            parent = *grandparent;
+                        }
+                        _ => {
+                            parent = next;
+                            break;
+                        }
+                    }
+                }
+            }
+
+            print!("{}", format_parent(&full_name, &parent, &via, &format));
+            Ok(())
+        }
+
+        RepoCommand::Watch { repo } => {
+            let (owner, name) = parse_repo(&repo)?;
+            client.watch_repo(&owner, &name).await?;
+            print!("{}", format_watch_change(&repo, true, &format));
+            Ok(())
+        }
+
+        RepoCommand::Unwatch { repo } => {
+            let (owner, name) = parse_repo(&repo)?;
+            client.unwatch_repo(&owner, &name).await?;
+            print!("{}", format_watch_change(&repo, false, &format));
+            Ok(())
+        }
+
+        RepoCommand::Archive {
+            repo,
+            git_ref,
+            format: archive_format,
+            output,
+        } => {
+            let (owner, name) = parse_repo(&repo)?;
+            let git_ref = match git_ref {
+                Some(git_ref) => git_ref,
+                None => client.get_repo(&owner, &name).await?.default_branch,
+            };
+            let dest = output.unwrap_or_else(|| {
+                PathBuf::from(format!("{}-{}.{}", name, git_ref.replace('/', "-"), archive_format.extension()))
+            });
+
+            let label = format!("{}@{}", repo, git_ref);
+            let show_progress = matches!(format, OutputFormat::Human) && std::io::stderr().is_terminal();
+            let mut last_shown: Option<Instant> = None;
+            let size = client
+                .download_archive(&owner, &name, &git_ref, archive_format, &dest, |written, total| {
+                    if show_progress && last_shown.is_none_or(|at| at.elapsed() >= PROGRESS_EVERY) {
+                        eprint!("\r{}", format_download_progress(&label, written, total));
+                        last_shown = Some(Instant::now());
+                    }
+                })
+                .await;
+            if last_shown.is_some() {
+                // Clear the progress line
+                eprint!("\r\x1b[2K");
+            }
+            let size = size.with_context(|| format!("Failed to download {}", label))?;
+
+            print!("{}", format_archive(&repo, &git_ref, &dest.display().to_string(), size, &format));
+            Ok(())
+        }
+
+        RepoCommand::Star { repo } => {
+            let (owner, name) = parse_repo(&repo)?;
+            client.star_repo(&owner, &name).await?;
+            print!("{}", format_star_change(&repo, true, &format));
+            Ok(())
+        }
+
+        RepoCommand::Unstar { repo } => {
+            let (owner, name) = parse_repo(&repo)?;
+            client.unstar_repo(&owner, &name).await?;
+            print!("{}", format_star_change(&repo, false, &format));
+            Ok(())
+        }
+
+        RepoCommand::Starred { limit } => {
+            let repos = client.list_starred_repos(limit).await?;
+            print!("{}", format_repo_list(&repos, &format));
+            Ok(())
+        }
+
+        RepoCommand::Collaborator(cmd) => collaborator::handle(cmd, client, &format).await,
+
+        RepoCommand::Delete { repo, yes } => {
+            let (owner, name) = parse_repo(&repo)?;
+            let full_name = format!("{}/{}", owner, name);
+
+            if !yes {
+                if !editor::is_interactive() {
+                    anyhow::bail!("Refusing to delete {} without confirmation. Pass --yes", full_name);
+                }
+                println!(
+                    "This permanently deletes {} with all its issues, wiki and releases.",
+                    full_name
+                );
+                let typed = prompt(&format!("Type '{}' to confirm: ", full_name))?;
+                if typed != full_name {
+                    println!("Aborted.");
+                    return Ok(());
+                }
+            }
+
+            client.delete_repo(&owner, &name).await?;
+            print!("{}", format_deleted_repo(&full_name, &format));
+            Ok(())
+        }
     }
 }
 
-async fn handle_list(client: &GogsClient, format: &OutputFormat) -> Result<()> {
-    let repos = client.list_user_repos().await?;
+async fn handle_list(
+    client: &GogsClient,
+    limit: Option<usize>,
+    starred: bool,
+    watched: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    let repos = if starred {
+        client.list_starred_repos(limit).await?
+    } else if watched {
+        let mut repos = client.list_watched_repos().await?;
+        if let Some(limit) = limit {
+            repos.truncate(limit);
+        }
+        repos
+    } else {
+        client.list_user_repos_limited(limit).await?
+    };
     let output = format_repo_list(&repos, format);
     print!("{}", output);
     Ok(())
 }
+
+/// "https://host/foo/bar.git" -> "bar"
+fn name_from_url(url: &str) -> Option<String> {
+    let last = url.trim_end_matches('/').rsplit(['/', ':']).next()?;
+    let name = last.strip_suffix(".git").unwrap_or(last);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// The password or token for `user` on a migration source, from
+/// GOGS_MIGRATE_PASSWORD or else asked for without echo.
+fn migrate_password(user: &str) -> Result<String> {
+    if let Ok(password) = std::env::var(MIGRATE_PASSWORD_VAR) {
+        return Ok(password);
+    }
+    if !editor::is_interactive() {
+        anyhow::bail!("No terminal to ask for {}'s password on; set {}", user, MIGRATE_PASSWORD_VAR);
+    }
+    prompt_hidden(&format!("Password or token for {} on the source: ", user))
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::api::{GogsClient, Issue, ListIssuesParams};
+use crate::cli::ReportCommand;
+use crate::commands::{for_each_repo, RepoScope};
+use crate::config::Config;
+use crate::output::{format_top, OutputFormat};
+use crate::votes;
+
+pub async fn handle(cmd: ReportCommand, client: &GogsClient, config: &Config, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        ReportCommand::Top {
+            all,
+            repo,
+            limit,
+            half_life,
+            jobs,
+        } => {
+            if half_life <= 0.0 {
+                anyhow::bail!("--half-life must be more than 0 days");
+            }
+
+            let params = ListIssuesParams::default();
+            let issues: Vec<(String, Issue)> = if all {
+                let scope = RepoScope::accessible(config.include_org_repos());
+                for_each_repo(client, scope, jobs, move |client, repo| {
+                    let params = params.clone();
+                    async move {
+                        client
+                            .list_issues(&repo.owner.username, &repo.name, &params)
+                            .await
+                    }
+                })
+                .await?
+                .into_iter()
+                .flat_map(|(repo, issues)| issues.into_iter().map(move |issue| (repo.clone(), issue)))
+                .collect()
+            } else {
+                let (owner, repo_name) = config.get_repo(repo.as_deref())?;
+                let full_name = format!("{}/{}", owner, repo_name);
+                client
+                    .list_issues(&owner, &repo_name, &params)
+                    .await?
+                    .into_iter()
+                    .map(|issue| (full_name.clone(), issue))
+                    .collect()
+            };
+
+            let counted = count_votes(client, issues, jobs).await;
+            let mut ranked = votes::rank(counted, Utc::now(), half_life);
+            let total = ranked.len();
+            ranked.truncate(limit);
+
+            print!("{}", format_top(&ranked, total, half_life, &format));
+            Ok(())
+        }
+    }
+}
+
+/// Attach each issue's vote count. An issue whose comments can't be read is
+/// counted as having no votes, with a warning. At most `jobs` listings are
+/// fetched at once.
+async fn count_votes(client: &GogsClient, issues: Vec<(String, Issue)>, jobs: usize) -> Vec<(String, Issue, usize)> {
+    let permits = Arc::new(Semaphore::new(jobs));
+    let mut tasks = JoinSet::new();
+
+    for (index, (repo, issue)) in issues.iter().enumerate() {
+        // No comments, no votes: skip the request
+        if issue.comments == 0 {
+            continue;
+        }
+        let client = client.clone();
+        let permits = permits.clone();
+        let repo = repo.clone();
+        let number = issue.number;
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let (owner, name) = repo.split_once('/').unwrap_or((&repo, ""));
+            let result = client.list_comments(owner, name, number).await;
+            (index, result.map(|comments| votes::count(&comments)))
+        });
+    }
+
+    let mut counts = vec![0; issues.len()];
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, Ok(count))) => counts[index] = count,
+            Ok((index, Err(e))) => {
+                let (repo, issue) = &issues[index];
+                eprintln!("Warning: Failed to read comments on {}#{}: {}", repo, issue.number, e);
+            }
+            Err(e) => eprintln!("Warning: Task failed: {}", e),
+        }
+    }
+
+    issues
+        .into_iter()
+        .zip(counts)
+        .map(|((repo, issue), count)| (repo, issue, count))
+        .collect()
+}
@@ -0,0 +1,207 @@
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::io::IsTerminal;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::time::MissedTickBehavior;
+
+use crate::api::{Comment, GogsClient};
+use crate::capture::fenced;
+use crate::config::Profile;
+use crate::output::{format_stream_result, OutputFormat};
+use crate::shutdown;
+
+/// Seconds between edits of the progress comment unless told otherwise.
+pub const DEFAULT_INTERVAL: u64 = 10;
+
+/// Bytes of log kept in the comment unless told otherwise; well inside what
+/// servers accept in a comment body.
+pub const DEFAULT_MAX_BYTES: usize = 60_000;
+
+/// Edits closer together than this would only load the server.
+const MIN_INTERVAL: u64 = 2;
+
+/// The lines a progress comment shows: the latest ones, within a byte budget.
+pub struct Log {
+    title: String,
+    lines: VecDeque<String>,
+    bytes: usize,
+    max_bytes: usize,
+    /// Lines read in all, including dropped ones
+    total: usize,
+}
+
+impl Log {
+    pub fn new(title: Option<String>, max_bytes: usize) -> Self {
+        Self {
+            title: title.unwrap_or_else(|| "Live log".to_string()),
+            lines: VecDeque::new(),
+            bytes: 0,
+            max_bytes: max_bytes.max(1),
+            total: 0,
+        }
+    }
+
+    fn push(&mut self, mut line: String) {
+        if line.len() > self.max_bytes {
+            let mut end = self.max_bytes;
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            line.truncate(end);
+        }
+        self.bytes += line.len() + 1;
+        self.lines.push_back(line);
+        self.total += 1;
+        while self.bytes > self.max_bytes && self.lines.len() > 1 {
+            if let Some(dropped) = self.lines.pop_front() {
+                self.bytes -= dropped.len() + 1;
+            }
+        }
+    }
+
+    fn dropped(&self) -> usize {
+        self.total - self.lines.len()
+    }
+
+    /// The comment body; `state` is "running", "finished" or "interrupted".
+    fn render(&self, profile: &Profile, state: &str) -> String {
+        let mut body = format!("**{}** ({}, {} lines)\n\n", self.title, state, self.total);
+        if self.dropped() > 0 {
+            body.push_str(&format!("{} earlier lines dropped.\n\n", self.dropped()));
+        }
+        let text: Vec<&str> = self.lines.iter().map(String::as_str).collect();
+        body.push_str(&fenced(&text.join("\n")));
+        profile.signed(&body)
+    }
+}
+
+/// Pass stdin through to stdout while keeping a progress comment on
+/// owner/repo#number up to date with it.
+#[allow(clippy::too_many_arguments)]
+pub async fn handle(
+    client: &GogsClient,
+    owner: &str,
+    repo: &str,
+    number: i64,
+    mut log: Log,
+    interval: u64,
+    profile: &Profile,
+    format: &OutputFormat,
+) -> Result<()> {
+    if std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "Pipe a job's output in on stdin, e.g. make 2>&1 | gog issue stream {} --repo {}/{}",
+            number,
+            owner,
+            repo
+        );
+    }
+    // Before reading anything, so a wrong number fails while the job can still be rerun
+    client.get_issue(owner, repo, number).await?;
+
+    let mut progress = Progress {
+        client,
+        owner,
+        repo,
+        number,
+        profile,
+        comment: None,
+    };
+    shutdown::graceful();
+
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval.max(MIN_INTERVAL)));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    ticker.tick().await;
+
+    let mut stdin = BufReader::new(tokio::io::stdin());
+    let mut stdout = tokio::io::stdout();
+    let mut line = Vec::new();
+    let mut pending = false;
+    let state = loop {
+        tokio::select! {
+            read = stdin.read_until(b'\n', &mut line) => {
+                match read {
+                    Ok(0) | Err(_) => break "finished",
+                    Ok(_) => {}
+                }
+                // A closed stdout shouldn't stop the log reaching the tracker
+                let _ = stdout.write_all(&line).await;
+                let _ = stdout.flush().await;
+                log.push(String::from_utf8_lossy(&line).trim_end_matches(['\n', '\r']).to_string());
+                line.clear();
+                pending = true;
+
+                // The first lines go up at once, so the comment appears as the job starts
+                if progress.comment.is_none() && progress.update(&log, "running").await {
+                    pending = false;
+                    ticker.reset();
+                }
+            }
+            _ = ticker.tick(), if pending => {
+                if progress.update(&log, "running").await {
+                    pending = false;
+                }
+            }
+            _ = shutdown::wait() => break "interrupted",
+        }
+    };
+
+    if log.total == 0 {
+        eprintln!("Nothing was read from stdin; no comment posted");
+        return Ok(());
+    }
+    if !progress.update(&log, state).await {
+        anyhow::bail!("Failed to post the log to {}/{}#{}", owner, repo, number);
+    }
+    let repo_name = format!("{}/{}", owner, repo);
+    let comment = progress.comment.as_ref().expect("posted by the update above");
+    // On stderr, since stdout carries the job's own output
+    let output = format_stream_result(&repo_name, number, comment, log.total, format);
+    eprint!("{}", output);
+    if !output.ends_with('\n') {
+        eprintln!();
+    }
+
+    if state == "interrupted" {
+        return Err(shutdown::interrupted(format!("{} lines posted", log.total)));
+    }
+    Ok(())
+}
+
+/// The progress comment: posted on the first update, edited on the rest.
+struct Progress<'a> {
+    client: &'a GogsClient,
+    owner: &'a str,
+    repo: &'a str,
+    number: i64,
+    profile: &'a Profile,
+    comment: Option<Comment>,
+}
+
+impl Progress<'_> {
+    /// Bring the comment up to date with `log`. A failure is only a warning,
+    /// since stopping would break the pipe the job is writing to; the next
+    /// update sends everything again.
+    async fn update(&mut self, log: &Log, state: &str) -> bool {
+        let (client, owner, repo, number) = (self.client, self.owner, self.repo, self.number);
+        let body = log.render(self.profile, state);
+        let result = match self.profile.sign_comment(owner, repo, number, body) {
+            Ok(body) => match &self.comment {
+                Some(comment) => client.edit_comment(owner, repo, comment.id, &body).await,
+                None => client.create_comment(owner, repo, number, &body).await,
+            },
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(comment) => {
+                self.comment = Some(comment);
+                true
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to update the log on {}/{}#{}: {:#}", owner, repo, number, e);
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use anyhow::Result;
+
+use crate::api::{AccessToken, GogsClient};
+use crate::cli::TokenCommand;
+use crate::commands::{prompt, prompt_password};
+use crate::config::Config;
+use crate::output::{format_token_created, format_token_list, OutputFormat};
+
+pub async fn handle(cmd: TokenCommand, config: &Config, profile: Option<&str>, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+    // Signing in with a password needs the server, not a working token
+    let mut client = GogsClient::new(config.server.url.clone(), String::new());
+    if let Some(prefix) = &config.server.api_prefix {
+        client = client.with_api_prefix(prefix);
+    }
+    let configured_user = config.get_profile(profile).ok().map(|p| p.gogs_user.clone());
+
+    match cmd {
+        TokenCommand::Create { name, user } => {
+            let user = username(user, configured_user)?;
+            let name = name.unwrap_or_else(|| format!("gog-{}", config.profile_name(profile)));
+            let token = create_with_password(&client, &user, &name).await?;
+            print!("{}", format_token_created(&user, &token, &format));
+        }
+        TokenCommand::List { user } => {
+            let user = username(user, configured_user)?;
+            let password = prompt_password(&format!("Password for {}: ", user))?;
+            let tokens = client.list_tokens(&user, &password).await?;
+            print!("{}", format_token_list(&user, &tokens, &format));
+        }
+    }
+    Ok(())
+}
+
+/// Ask for `username`'s password and create an API token called `name`.
+pub async fn create_with_password(client: &GogsClient, username: &str, name: &str) -> Result<AccessToken> {
+    let password = prompt_password(&format!("Password for {}: ", username))?;
+    client.create_token(username, &password, name).await
+}
+
+/// The account to sign in as: --user, else the profile's, else asked for.
+fn username(user: Option<String>, configured: Option<String>) -> Result<String> {
+    let user = match user.or(configured.filter(|u| !u.is_empty())) {
+        Some(user) => user,
+        None => prompt("Gogs username: ")?,
+    };
+    if user.is_empty() {
+        anyhow::bail!("Username cannot be empty");
+    }
+    Ok(user)
+}
@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+
+use crate::api::GogsClient;
+use crate::config::{parse_repo, Config};
+use crate::error::GogsError;
+use crate::output::{format_update, OutputFormat};
+use crate::update::{self, Channel, CURRENT_VERSION, DEFAULT_ASSET_URL};
+
+pub async fn handle(
+    channel: Option<Channel>,
+    check: bool,
+    client: &GogsClient,
+    config: &Config,
+    json: bool,
+) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+    let settings = config.update.clone().unwrap_or_default();
+    let source = settings
+        .repo
+        .context("No release source configured. Set repo under [update] in the config")?;
+    let (owner, repo) = parse_repo(&source)?;
+    let channel = channel.or(settings.channel).unwrap_or_default();
+
+    let releases = client.list_releases(&owner, &repo).await?;
+    let release = update::pick_release(&releases, channel)
+        .with_context(|| format!("{} has no {} releases", source, channel.name()))?;
+    let tag = release.tag_name.clone();
+
+    if update::compare_versions(&tag, CURRENT_VERSION) != Ordering::Greater {
+        print!("{}", format_update(CURRENT_VERSION, &tag, "current", &format));
+        return Ok(());
+    }
+    if check {
+        print!("{}", format_update(CURRENT_VERSION, &tag, "available", &format));
+        return Ok(());
+    }
+
+    let asset = update::asset_name();
+    let template = settings.asset_url.as_deref().unwrap_or(DEFAULT_ASSET_URL);
+    let url = update::asset_url(template, &config.server.url, &source, &tag, &asset);
+
+    // Without a checksum there is nothing to tell a good download from a bad one
+    let checksum_url = format!("{}.sha256", url);
+    let expected = match client.download(&checksum_url).await {
+        Ok(text) => update::parse_checksum(&String::from_utf8_lossy(&text), &asset)
+            .with_context(|| format!("No SHA-256 digest for {} in {}", asset, checksum_url))?,
+        Err(e) if matches!(e.downcast_ref(), Some(GogsError::NotFound(_))) => {
+            anyhow::bail!("{} has no checksum at {}; refusing to install", tag, checksum_url)
+        }
+        Err(e) => return Err(e),
+    };
+
+    if !json {
+        eprintln!("Downloading {} {}...", asset, tag);
+    }
+    let binary = client
+        .download(&url)
+        .await
+        .with_context(|| format!("No {} build of {} at {}", asset, tag, url))?;
+    let actual = format!("{:x}", Sha256::digest(&binary));
+    if actual != expected {
+        anyhow::bail!(
+            "Checksum mismatch for {}: expected {}, got {}. Nothing was installed",
+            url,
+            expected,
+            actual
+        );
+    }
+
+    let path = update::install(&binary)?;
+    if !json {
+        eprintln!("Checksum verified; installed to {}", path.display());
+    }
+    print!("{}", format_update(CURRENT_VERSION, &tag, "installed", &format));
+    Ok(())
+}
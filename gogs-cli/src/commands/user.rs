@@ -0,0 +1,76 @@
+use anyhow::Result;
+
+use crate::api::{GogsClient, User};
+use crate::cli::UserCommand;
+use crate::config::Profile;
+use crate::error::GogsError;
+use crate::output::{format_user, format_user_list, OutputFormat};
+
+/// Most "did you mean" suggestions offered for an unknown username.
+const SUGGESTIONS: usize = 3;
+
+pub async fn handle(cmd: UserCommand, client: &GogsClient, profile: &Profile, json: bool) -> Result<()> {
+    let format = OutputFormat::from_json_flag(json);
+
+    match cmd {
+        UserCommand::Show { name } => {
+            let name = match name.as_str() {
+                "@me" => profile.gogs_user.clone(),
+                name => name.trim_start_matches('@').to_string(),
+            };
+            let user = require_user(client, &name, None).await?;
+            print!("{}", format_user(&user, &format));
+        }
+        UserCommand::Search { query, limit } => {
+            let users = client.search_users(&query, limit).await?;
+            print!("{}", format_user_list(&users, &format));
+        }
+    }
+    Ok(())
+}
+
+/// The user called `name`, or a not-found error suggesting similar names.
+/// Assignment commands check with this first: Gogs rejects an unknown assignee
+/// with a bare error, and some versions silently leave the issue unassigned.
+/// Collaborators of `repo` are the likeliest intended names, so they're
+/// among the candidates when given.
+pub async fn require_user(client: &GogsClient, name: &str, repo: Option<(&str, &str)>) -> Result<User> {
+    if let Some(user) = client.get_user(name).await? {
+        return Ok(user);
+    }
+
+    let suggestions = suggestions(client, name, repo).await;
+    let hint = if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(". Did you mean {}?", suggestions.join(", "))
+    };
+    Err(GogsError::NotFound(format!("user '{}'{}", name, hint)).into())
+}
+
+/// Usernames close to `name`, best first. Lookups that fail only mean fewer
+/// suggestions; the error being reported is that `name` doesn't exist.
+async fn suggestions(client: &GogsClient, name: &str, repo: Option<(&str, &str)>) -> Vec<String> {
+    let mut candidates = Vec::new();
+    if let Some((owner, repo)) = repo {
+        if let Ok(collaborators) = client.list_collaborators(owner, repo).await {
+            candidates.extend(collaborators.into_iter().map(|c| c.user.username));
+        }
+    }
+    // Search matches anywhere in the name, so a short prefix still finds
+    // names with a typo further along
+    let prefix: String = name.chars().take(2).collect();
+    if let Ok(users) = client.search_users(&prefix, 50).await {
+        candidates.extend(users.into_iter().map(|u| u.username));
+    }
+    candidates.sort();
+    candidates.dedup();
+
+    // Usernames are case-insensitive in Gogs
+    let lowered: Vec<String> = candidates.iter().map(|c| c.to_lowercase()).collect();
+    difflib::get_close_matches(&name.to_lowercase(), lowered.iter().map(String::as_str).collect(), SUGGESTIONS, 0.6)
+        .into_iter()
+        .filter_map(|matched| lowered.iter().position(|c| c == matched))
+        .map(|i| candidates[i].clone())
+        .collect()
+}
@@ -1,35 +1,286 @@
 use anyhow::{Context, Result};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::cache::{LabelCache, ResponseCache};
+use crate::api::flood::{CommentGuard, CommentLimit};
+use crate::api::quarantine::Quarantine;
+use crate::api::retry::{self, RetryMode};
+use crate::api::client::DEFAULT_API_PREFIX;
+use crate::api::mirror::MirrorConfig;
+use crate::api::GogsClient;
+use crate::encryption::{self, EncryptionConfig};
+use crate::lint::LintConfig;
+use crate::presets::RolePreset;
+use crate::journal::Journal;
+use crate::keyring;
+use crate::provenance::Signer;
+use crate::remote;
+use crate::signature::{self, Position};
+use crate::storage::{self, FileStorage, MemoryStorage, SqliteStorage, Storage};
+use crate::update::UpdateConfig;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Config {
+    /// The server profiles use unless they name one of `servers`
     pub server: ServerConfig,
+    /// Further servers by name, for profiles with `server = "NAME"`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub servers: HashMap<String, ServerConfig>,
     #[serde(default)]
     pub defaults: Defaults,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub profiles: HashMap<String, Profile>,
+    /// Keeps `profiles` encrypted on disk when set, see `gog config encrypt`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<EncryptionConfig>,
+    pub lint: Option<LintConfig>,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// Local issue templates by name, checked before the repository's own
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub templates: HashMap<String, PathBuf>,
+    /// Role presets in addition to, or replacing, the built-in ones
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub presets: HashMap<String, RolePreset>,
+    /// Where `self-update` gets releases from
+    pub update: Option<UpdateConfig>,
+    /// Signing keys of agents without a profile here, by name, for `issue verify`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub trusted_keys: HashMap<String, String>,
+    /// The `.gog.toml` layered over this config, if one was found
+    #[serde(skip)]
+    pub project: Option<(PathBuf, ProjectConfig)>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Name of the per-checkout settings file, looked for from the current
+/// directory upwards.
+pub const PROJECT_FILE: &str = ".gog.toml";
+
+/// Settings for one checkout. Only defaults can be set here: the file comes
+/// with the code, so it mustn't be able to send tokens to another server.
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Repository for commands given no --repo
+    pub repo: Option<String>,
+    /// Profile to use when --profile isn't given
+    pub profile: Option<String>,
+    /// Labels added to every issue created here, after the profile's own
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+impl ProjectConfig {
+    /// The nearest `.gog.toml` from `dir` upwards, if any.
+    pub fn find(dir: &Path) -> Result<Option<(PathBuf, ProjectConfig)>> {
+        let Some(path) = dir.ancestors().map(|d| d.join(PROJECT_FILE)).find(|p| p.is_file()) else {
+            return Ok(None);
+        };
+        let contents = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let project: ProjectConfig =
+            toml::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))?;
+        if let Some(repo) = &project.repo {
+            parse_repo(repo).with_context(|| format!("Bad repo in {}", path.display()))?;
+        }
+        Ok(Some((path, project)))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ServerConfig {
     pub url: String,
+    /// Maximum pages to follow when listing (default 100)
+    pub max_pages: Option<usize>,
+    /// Path of the API under `url` (default "/api/v1")
+    pub api_prefix: Option<String>,
+    /// Most requests per second to send, shared by all of a command's requests
+    pub rate_limit: Option<f64>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct Defaults {
     pub repo: Option<String>,
     pub profile: Option<String>,
+    /// Label applied by `issue create --claim` (default "in-progress")
+    pub claim_label: Option<String>,
+    /// Match label names case-sensitively everywhere (default false)
+    pub exact_labels: Option<bool>,
+    /// Label added by `issue handoff` in place of the claim label (default "handoff")
+    pub handoff_label: Option<String>,
+    /// Also cover organization repositories wherever `--all` is used (default false)
+    pub include_org_repos: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct CacheConfig {
+    /// Keep repository label listings on disk for this many seconds
+    pub label_ttl: Option<u64>,
+    /// Keep GET responses on disk, revalidating those with an ETag or
+    /// Last-Modified, and fall back to them when the server is unreachable
+    /// (default false)
+    pub responses: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct StorageConfig {
+    /// Directory for local state in place of `~/.cache/gogs-cli`; each server
+    /// still gets its own directory under it
+    pub path: Option<PathBuf>,
+    /// "sqlite" (default), "file" or "memory", which keeps nothing between runs
+    #[serde(default, skip_serializing_if = "storage::Backend::is_default")]
+    pub backend: storage::Backend,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct RetryConfig {
+    /// Total attempts for requests that fail transiently (default 3, 1 disables)
+    pub attempts: Option<usize>,
+    /// Milliseconds before the first retry, doubling after each (default 500)
+    pub base_delay_ms: Option<u64>,
+    /// Most seconds to wait between attempts, even if the server asks for longer (default 30)
+    pub max_delay: Option<u64>,
+    /// Per-command overrides keyed by subcommand, e.g. `"issue comment" = "never"`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub commands: HashMap<String, RetryMode>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct Profile {
     pub gogs_user: String,
+    /// Empty when the token is kept in the OS keyring instead
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub token: String,
+    /// Keyring entry holding the token, set by `gog auth login`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyring: Option<String>,
+    /// Command that prints the token, e.g. `pass show gogs/agent1`; run only
+    /// when a command needs the token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_cmd: Option<String>,
+    /// Name of the `[servers]` entry the token is for; `[server]` if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
     pub role: String,
     pub signature: String,
+    /// Where the signature goes: before what is posted, after it, or nowhere
+    #[serde(default, skip_serializing_if = "Position::is_default")]
+    pub signature_position: Position,
+    /// Labels added to every issue this profile creates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_labels: Vec<String>,
+    /// Commands this profile may run, e.g. "issue comment" or "label"; empty allows all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_commands: Vec<String>,
+    /// Mention the issue author when closing with a comment
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub notify_author: bool,
+    /// Second server that issue and label changes are also applied to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<MirrorConfig>,
+    /// Base64 key that comments from this profile are signed with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+    /// Most comments this profile may post on one issue in a time window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_limit: Option<CommentLimit>,
+    /// Never edit or delete other users' comments, even with --force-other-author
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub forbid_other_authors: bool,
+}
+
+impl Profile {
+    /// Fetch the token from the OS keyring or by running `token_cmd`, if
+    /// that's where it comes from rather than the config file.
+    pub fn load_token(&mut self, name: &str) -> Result<()> {
+        if !self.token.is_empty() {
+            return Ok(());
+        }
+        if let Some(entry) = &self.keyring {
+            self.token = keyring::get(entry).with_context(|| {
+                format!(
+                    "Profile '{}' keeps its token in the OS keyring. Run 'gog --profile {} auth login' to store it again",
+                    name, name
+                )
+            })?;
+        } else if let Some(command) = &self.token_cmd {
+            self.token = token_from_command(command)
+                .with_context(|| format!("Profile '{}' gets its token from token_cmd", name))?;
+        }
+        Ok(())
+    }
+
+    /// The signature to post now, with its variables filled in (see
+    /// `signature::expand`).
+    pub fn current_signature(&self) -> String {
+        signature::expand(&self.signature, &self.gogs_user, &self.role, Utc::now())
+    }
+
+    /// `text` ready to post, with the current signature where the profile
+    /// puts it.
+    pub fn signed(&self, text: &str) -> String {
+        signature::apply(&self.current_signature(), self.signature_position, text)
+    }
+
+    /// `text` without the signature this profile would have put on it.
+    pub fn unsigned<'a>(&self, text: &'a str) -> &'a str {
+        signature::remove(&self.signature_pattern(), self.signature_position, text).unwrap_or(text)
+    }
+
+    /// The signature with only the variables that don't change between posts
+    /// filled in, for recognising this profile's earlier posts with
+    /// `signature::matches` and `signature::strip`.
+    pub fn signature_pattern(&self) -> String {
+        signature::pattern(&self.signature, &self.gogs_user, &self.role)
+    }
+
+    /// The signer for this profile's comments, if it has a signing key.
+    pub fn signer(&self) -> Result<Option<Signer>> {
+        self.signing_key
+            .as_deref()
+            .map(|key| Signer::new(key, &self.gogs_user))
+            .transpose()
+    }
+
+    /// `body` ready to post on owner/repo#number: signed when the profile has a key.
+    pub fn sign_comment(&self, owner: &str, repo: &str, number: i64, body: String) -> Result<String> {
+        Ok(match self.signer()? {
+            Some(signer) => signer.sign(&format!("{}/{}", owner, repo), number, &body),
+            None => body,
+        })
+    }
+
+    /// Fail unless `command` (e.g. "issue close") falls under one of the
+    /// profile's allowed commands.
+    pub fn check_allowed(&self, name: &str, command: &str) -> Result<()> {
+        if self.allowed_commands.is_empty() {
+            return Ok(());
+        }
+
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let allowed = self.allowed_commands.iter().any(|entry| {
+            let prefix: Vec<&str> = entry.split_whitespace().collect();
+            words.starts_with(&prefix)
+        });
+        if !allowed {
+            anyhow::bail!(
+                "Profile '{}' is not allowed to run '{}' (allowed: {})",
+                name,
+                command,
+                self.allowed_commands.join(", ")
+            );
+        }
+        Ok(())
+    }
 }
 
 impl Config {
@@ -38,13 +289,63 @@ impl Config {
         let contents = fs::read_to_string(&path)
             .context(format!("Failed to read config from {:?}. Run 'gog init' to create configuration.", path))?;
 
-        let config: Config = toml::from_str(&contents)
+        Self::from_toml(&contents)
+    }
+
+    /// Parse a config file's contents, decrypting its profiles if they are
+    /// encrypted.
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        let mut config: Config = toml::from_str(contents)
             .context("Failed to parse config file")?;
+        config.decrypt_profiles()?;
 
         Ok(config)
     }
 
+    /// Replace `profiles` with the encrypted ones, if there are any, and
+    /// return them as written.
+    pub fn decrypt_profiles(&mut self) -> Result<Option<toml::Value>> {
+        let Some(encryption) = self.encryption.as_mut().filter(|e| !e.profiles.is_empty()) else {
+            return Ok(None);
+        };
+        let plaintext = encryption::decrypt(&encryption.profiles, encryption)?;
+        encryption.profiles.clear();
+
+        let table: toml::Value = plaintext.parse().context("The decrypted profiles are not valid TOML")?;
+        let profiles: HashMap<String, Profile> =
+            table.clone().try_into().context("Failed to parse the decrypted profiles")?;
+        if let Some(name) = profiles.keys().find(|name| self.profiles.contains_key(*name)) {
+            anyhow::bail!(
+                "Profile '{}' is both in the encrypted profiles and in plain [profiles]; remove one",
+                name
+            );
+        }
+        self.profiles.extend(profiles);
+        Ok(Some(table))
+    }
+
+    /// The file contents for this config, with the profiles encrypted if
+    /// `encryption` is set.
+    pub fn to_toml(&self) -> Result<String> {
+        let Some(encryption) = &self.encryption else {
+            return Ok(toml::to_string_pretty(self)?);
+        };
+        let plaintext = toml::to_string_pretty(&self.profiles)?;
+        let mut stored: Config = toml::Value::try_from(self)?.try_into()?;
+        stored.profiles.clear();
+        stored.encryption = Some(EncryptionConfig {
+            profiles: encryption::encrypt(&plaintext, encryption)?,
+            ..encryption.clone()
+        });
+        Ok(toml::to_string_pretty(&stored)?)
+    }
+
     pub fn save(&self) -> Result<()> {
+        Self::write(&self.to_toml()?)
+    }
+
+    /// Replace the config file with `contents`, readable only by the user.
+    pub fn write(contents: &str) -> Result<()> {
         let path = Self::config_path()?;
 
         // Create parent directory if needed
@@ -52,8 +353,7 @@ impl Config {
             fs::create_dir_all(parent)?;
         }
 
-        let contents = toml::to_string_pretty(self)?;
-        fs::write(&path, &contents)?;
+        fs::write(&path, contents)?;
 
         // Set restrictive permissions (Unix only)
         #[cfg(unix)]
@@ -78,24 +378,289 @@ impl Config {
         Ok(config_dir.join("gogs-cli").join("config.toml"))
     }
 
+    /// Name of the profile to use: the one asked for, else the default.
+    pub fn profile_name<'a>(&'a self, name: Option<&'a str>) -> &'a str {
+        name.or(self.defaults.profile.as_deref()).unwrap_or("default")
+    }
+
     pub fn get_profile(&self, name: Option<&str>) -> Result<&Profile> {
-        let profile_name = name
-            .or(self.defaults.profile.as_deref())
-            .unwrap_or("default");
+        let profile_name = self.profile_name(name);
 
         self.profiles.get(profile_name)
             .context(format!("Profile '{}' not found in config", profile_name))
     }
 
+    /// Layer the nearest `.gog.toml` over the defaults. Only call this on a
+    /// config that won't be saved, or the file's settings would be written
+    /// into it.
+    pub fn apply_project(&mut self, dir: &Path) -> Result<()> {
+        let Some((path, project)) = ProjectConfig::find(dir)? else {
+            return Ok(());
+        };
+        if let Some(repo) = &project.repo {
+            self.defaults.repo = Some(repo.clone());
+        }
+        if let Some(profile) = &project.profile {
+            if !self.profiles.contains_key(profile) {
+                anyhow::bail!("{} names profile '{}', which isn't in the config", path.display(), profile);
+            }
+            self.defaults.profile = Some(profile.clone());
+        }
+        self.project = Some((path, project));
+        Ok(())
+    }
+
+    /// Take `defaults.repo` from the `origin` remote of the checkout holding
+    /// `dir`, when that remote is on this config's server and `.gog.toml`
+    /// doesn't name a repository. Call once the server is settled.
+    pub fn apply_remote(&mut self, dir: &Path) {
+        if self.project.as_ref().is_some_and(|(_, project)| project.repo.is_some()) {
+            return;
+        }
+        if let Some(remote) = remote::origin(dir).filter(|remote| remote.is_on(&self.server.url)) {
+            self.defaults.repo = Some(remote.full_name());
+        }
+    }
+
+    /// Labels the project file adds to new issues.
+    pub fn project_labels(&self) -> &[String] {
+        self.project.as_ref().map_or(&[], |(_, project)| project.labels.as_slice())
+    }
+
+    /// Point this config at `[servers.NAME]` and make the profile to use
+    /// there the default: `profile` if given, else the default profile or the
+    /// only one for that server. The profile must be one of the server's, so
+    /// a token never goes to a server it wasn't issued by.
+    pub fn select_server(&mut self, name: &str, profile: Option<&str>) -> Result<()> {
+        let server = self
+            .servers
+            .get(name)
+            .with_context(|| format!("Server '{}' not found in [servers]", name))?
+            .clone();
+        let is_on_server = |profile: &Profile| profile.server.as_deref() == Some(name);
+
+        let chosen = match profile {
+            Some(profile) => {
+                if self.profiles.get(profile).is_some_and(|p| !is_on_server(p)) {
+                    anyhow::bail!(
+                        "Profile '{}' is not for server '{}'; set server = \"{}\" in the profile or pick another",
+                        profile,
+                        name,
+                        name
+                    );
+                }
+                profile.to_string()
+            }
+            None => {
+                let default = self.profile_name(None).to_string();
+                let mut candidates: Vec<&String> = self
+                    .profiles
+                    .iter()
+                    .filter(|(_, p)| is_on_server(p))
+                    .map(|(n, _)| n)
+                    .collect();
+                candidates.sort();
+                match candidates.as_slice() {
+                    _ if candidates.contains(&&default) => default,
+                    [only] => only.to_string(),
+                    [] => anyhow::bail!("No profile has server = \"{}\"", name),
+                    _ => anyhow::bail!(
+                        "Several profiles are for server '{}' ({}); pick one with --profile",
+                        name,
+                        candidates.iter().map(|n| n.as_str()).collect::<Vec<_>>().join(", ")
+                    ),
+                }
+            }
+        };
+
+        self.server = server;
+        self.defaults.profile = Some(chosen);
+        Ok(())
+    }
+
+    /// The server `profile`'s token is for.
+    pub fn server_for(&self, profile: &Profile) -> Result<&ServerConfig> {
+        match &profile.server {
+            Some(name) => self
+                .servers
+                .get(name)
+                .with_context(|| format!("Server '{}' not found in [servers]", name)),
+            None => Ok(&self.server),
+        }
+    }
+
+    /// Build an API client for the server using the given profile's token.
+    pub fn client(&self, profile: &Profile) -> GogsClient {
+        let mut client = GogsClient::new(self.server.url.clone(), profile.token.clone()).with_daemon();
+        if let Some(prefix) = &self.server.api_prefix {
+            client = client.with_api_prefix(prefix);
+        }
+        if let Some(max_pages) = self.server.max_pages {
+            client = client.with_max_pages(max_pages);
+        }
+        if let Some(rate) = self.server.rate_limit {
+            client = client.with_rate_limit(rate);
+        }
+        if let Some(attempts) = self.retry.attempts {
+            client = client.with_retry_attempts(attempts);
+        }
+        let base = self.retry.base_delay_ms.map_or(retry::DEFAULT_BASE_DELAY, Duration::from_millis);
+        let max = self.retry.max_delay.map_or(retry::DEFAULT_MAX_DELAY, Duration::from_secs);
+        client = client.with_retry_delays(base, max);
+        if let (Some(ttl), Some(storage)) = (self.cache.label_ttl, self.storage()) {
+            let cache = LabelCache::with_storage(storage, Duration::from_secs(ttl));
+            client = client.with_label_cache(cache);
+        }
+        if let (Some(true), Some(storage)) = (self.cache.responses, self.storage()) {
+            client = client.with_response_cache(ResponseCache::new(storage));
+        }
+        if let Some(mirror) = &profile.mirror {
+            client = client.with_mirror(mirror);
+        }
+        if let Some(limit) = &profile.comment_limit {
+            let identity = format!("{} {}", profile.gogs_user, profile.signature_pattern());
+            let guard = CommentGuard::new(limit.clone(), identity, self.storage_or_memory());
+            client = client.with_comment_guard(guard);
+        }
+        client = client.with_quarantine(self.quarantine(profile));
+        client
+    }
+
+    /// Where edits and deletions of existing comments are recorded.
+    pub fn journal(&self) -> Journal {
+        Journal::new(self.storage())
+    }
+
+    /// The repositories fan-out commands skip for `profile`'s user.
+    pub fn quarantine(&self, profile: &Profile) -> Quarantine {
+        Quarantine::new(profile.gogs_user.clone(), self.storage_or_memory())
+    }
+
+    /// Where local state is kept, per `[storage]`; None when it should be
+    /// on disk but there is no directory for it. A database that can't be
+    /// opened falls back to files in the same directory.
+    pub fn storage(&self) -> Option<Arc<dyn Storage>> {
+        match self.storage.backend {
+            storage::Backend::Memory => Some(MemoryStorage::shared()),
+            storage::Backend::File => Some(Arc::new(FileStorage::new(self.cache_dir()?))),
+            storage::Backend::Sqlite => {
+                let dir = self.cache_dir()?;
+                match SqliteStorage::open(dir.clone()) {
+                    Ok(db) => Some(Arc::new(db)),
+                    Err(e) => {
+                        eprintln!(
+                            "Warning: Can't open {}: {}; keeping local state in files",
+                            dir.join(storage::DATABASE_FILE).display(),
+                            e
+                        );
+                        Some(Arc::new(FileStorage::new(dir)))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Local storage, falling back to memory so state still holds within this run.
+    fn storage_or_memory(&self) -> Arc<dyn Storage> {
+        self.storage().unwrap_or_else(|| MemoryStorage::shared())
+    }
+
+    pub fn api_prefix(&self) -> String {
+        let prefix = self.server.api_prefix.as_deref().unwrap_or(DEFAULT_API_PREFIX);
+        format!("/{}", prefix.trim_matches('/'))
+    }
+
+    /// Retry behaviour for `command` (e.g. "issue comment"), from `[retry.commands]`.
+    pub fn retry_mode(&self, command: &str) -> RetryMode {
+        self.retry.commands.get(command).copied().unwrap_or_default()
+    }
+
+    /// Per-server cache directory, e.g. `~/.cache/gogs-cli/gogs.example.com`,
+    /// or under `storage.path` if set.
+    pub fn cache_dir(&self) -> Option<PathBuf> {
+        let server: String = self
+            .server
+            .url
+            .split("://")
+            .last()
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+            .collect();
+        let root = match &self.storage.path {
+            Some(path) => path.clone(),
+            None => dirs::cache_dir()?.join("gogs-cli"),
+        };
+        Some(root.join(server))
+    }
+
+    pub fn claim_label(&self) -> &str {
+        self.defaults.claim_label.as_deref().unwrap_or("in-progress")
+    }
+
+    pub fn handoff_label(&self) -> &str {
+        self.defaults.handoff_label.as_deref().unwrap_or("handoff")
+    }
+
+    pub fn exact_labels(&self) -> bool {
+        self.defaults.exact_labels.unwrap_or(false)
+    }
+
+    pub fn include_org_repos(&self) -> bool {
+        self.defaults.include_org_repos.unwrap_or(false)
+    }
+
+    /// Every key a comment may be signed with: the profiles' own, then trusted ones.
+    pub fn verification_keys(&self) -> Vec<(String, String)> {
+        let mut keys: Vec<(String, String)> = self
+            .profiles
+            .iter()
+            .filter_map(|(name, p)| p.signing_key.clone().map(|key| (name.clone(), key)))
+            .collect();
+        keys.sort();
+        let mut trusted: Vec<(String, String)> = self.trusted_keys.clone().into_iter().collect();
+        trusted.sort();
+        keys.extend(trusted);
+        keys
+    }
+
+    /// The repository to use: GOGS_REPO, else `--repo`, else `defaults.repo`
+    /// (which `.gog.toml` or the checkout's git remote may have set).
     pub fn get_repo(&self, repo: Option<&str>) -> Result<(String, String)> {
-        let repo_str = repo
+        let env_repo = std::env::var("GOGS_REPO").ok().filter(|r| !r.is_empty());
+        let repo_str = env_repo
+            .as_deref()
+            .or(repo)
             .or(self.defaults.repo.as_deref())
-            .context("Repository not specified. Use --repo owner/name or set defaults.repo in config")?;
+            .context("Repository not specified. Use --repo owner/name, set GOGS_REPO, or set defaults.repo in config")?;
 
         parse_repo(repo_str)
     }
 }
 
+/// Run `command` through the shell and take the first line it prints as the
+/// token. Its stdin and stderr stay on the terminal, so a secrets manager can
+/// ask for a passphrase.
+fn token_from_command(command: &str) -> Result<String> {
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let output = std::process::Command::new(shell)
+        .args([flag, command])
+        .stdin(std::process::Stdio::inherit())
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run '{}'", command))?;
+    if !output.status.success() {
+        anyhow::bail!("'{}' failed ({})", command, output.status);
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let token = stdout.lines().next().unwrap_or_default().trim();
+    if token.is_empty() {
+        anyhow::bail!("'{}' printed no token", command);
+    }
+    Ok(token.to_string())
+}
+
 pub fn parse_repo(repo: &str) -> Result<(String, String)> {
     let parts: Vec<&str> = repo.split('/').collect();
     if parts.len() != 2 {
@@ -109,9 +674,23 @@ impl Default for Config {
         Self {
             server: ServerConfig {
                 url: "https://gogs.example.com".to_string(),
+                max_pages: None,
+                api_prefix: None,
+                rate_limit: None,
             },
+            servers: HashMap::new(),
             defaults: Defaults::default(),
             profiles: HashMap::new(),
+            encryption: None,
+            lint: None,
+            cache: CacheConfig::default(),
+            retry: RetryConfig::default(),
+            storage: StorageConfig::default(),
+            templates: HashMap::new(),
+            presets: HashMap::new(),
+            update: None,
+            trusted_keys: HashMap::new(),
+            project: None,
         }
     }
 }
@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::api::ENDPOINTS;
+
+/// OpenAPI description of the server API that coverage is measured against.
+const SPEC: &str = include_str!("../spec/gogs-api-v1.json");
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "patch", "head", "options"];
+
+#[derive(Debug, Deserialize)]
+struct Spec {
+    paths: BTreeMap<String, BTreeMap<String, Operation>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Operation {
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    summary: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Route {
+    pub method: String,
+    pub path: String,
+    pub tag: String,
+    pub summary: String,
+    pub implemented: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CoverageReport {
+    pub total: usize,
+    pub implemented: usize,
+    pub routes: Vec<Route>,
+    /// Endpoints in `api::ENDPOINTS` the spec doesn't list, as "METHOD path"
+    pub unlisted: Vec<String>,
+}
+
+/// Compare `api::ENDPOINTS` against the bundled spec, optionally only for
+/// routes tagged `tag`.
+pub fn report(tag: Option<&str>) -> Result<CoverageReport> {
+    let spec: Spec = serde_json::from_str(SPEC).context("Bundled OpenAPI spec is invalid")?;
+    let implemented: Vec<(String, String)> = ENDPOINTS
+        .iter()
+        .map(|(method, path)| (method.to_uppercase(), normalize(path)))
+        .collect();
+
+    let mut routes = Vec::new();
+    for (path, operations) in &spec.paths {
+        for (method, operation) in operations {
+            // Path items can carry shared fields such as "parameters"
+            if !METHODS.contains(&method.as_str()) {
+                continue;
+            }
+            let route_tag = operation.tags.first().cloned().unwrap_or_else(|| "other".to_string());
+            if tag.is_some_and(|tag| !route_tag.eq_ignore_ascii_case(tag)) {
+                continue;
+            }
+            let method = method.to_uppercase();
+            let key = (method.clone(), normalize(path));
+            routes.push(Route {
+                implemented: implemented.contains(&key),
+                method,
+                path: path.clone(),
+                tag: route_tag,
+                summary: operation.summary.clone(),
+            });
+        }
+    }
+    routes.sort_by(|a, b| (&a.tag, &a.path, &a.method).cmp(&(&b.tag, &b.path, &b.method)));
+
+    if routes.is_empty() {
+        if let Some(tag) = tag {
+            let mut tags: Vec<String> = spec
+                .paths
+                .values()
+                .flat_map(|ops| ops.values())
+                .filter_map(|op| op.tags.first().cloned())
+                .collect();
+            tags.sort();
+            tags.dedup();
+            anyhow::bail!("No routes tagged '{}' (tags: {})", tag, tags.join(", "));
+        }
+    }
+
+    let in_spec: Vec<(String, String)> = spec
+        .paths
+        .iter()
+        .flat_map(|(path, ops)| ops.keys().map(move |m| (m.to_uppercase(), normalize(path))))
+        .collect();
+    let unlisted = ENDPOINTS
+        .iter()
+        .filter(|(method, path)| !in_spec.contains(&(method.to_uppercase(), normalize(path))))
+        .map(|(method, path)| format!("{} {}", method, path))
+        .collect();
+
+    Ok(CoverageReport {
+        total: routes.len(),
+        implemented: routes.iter().filter(|r| r.implemented).count(),
+        routes,
+        unlisted,
+    })
+}
+
+/// Blank out parameter names, so "/labels/{id}" matches "/labels/{label_id}".
+fn normalize(path: &str) -> String {
+    path.split('/')
+        .map(|segment| if segment.starts_with('{') && segment.ends_with('}') { "{}" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_endpoint_is_in_the_spec() {
+        let report = report(None).unwrap();
+        assert!(report.total > 0);
+        assert!(report.unlisted.is_empty(), "not in the spec: {:?}", report.unlisted);
+    }
+}
@@ -0,0 +1,283 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+use url::Url;
+
+use crate::config::{Config, Profile};
+
+/// How long each network step may take before it counts as failed.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Certificates expiring sooner than this are flagged.
+const EXPIRY_WARNING_DAYS: i64 = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+/// The outcome of one diagnostic step, with what to do about it if it failed.
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Check {
+    pub(crate) fn ok(name: &'static str, detail: String) -> Self {
+        Self { name, status: Status::Ok, detail, hint: None }
+    }
+
+    pub(crate) fn warn(name: &'static str, detail: String, hint: &str) -> Self {
+        Self { name, status: Status::Warn, detail, hint: Some(hint.to_string()) }
+    }
+
+    pub(crate) fn fail(name: &'static str, detail: String, hint: &str) -> Self {
+        Self { name, status: Status::Fail, detail, hint: Some(hint.to_string()) }
+    }
+}
+
+/// Walk from URL to authenticated API call, stopping at the first step that
+/// fails since everything after it would fail for the same reason.
+pub async fn network_checks(config: &Config, profile: Option<&Profile>) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let url = match check_url(&config.server.url) {
+        Ok((url, check)) => {
+            checks.push(check);
+            url
+        }
+        Err(check) => {
+            checks.push(check);
+            return checks;
+        }
+    };
+    let host = url.host_str().unwrap_or_default().to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let addrs = match check_dns(&host, port).await {
+        Ok((addrs, check)) => {
+            checks.push(check);
+            addrs
+        }
+        Err(check) => {
+            checks.push(check);
+            return checks;
+        }
+    };
+
+    let tcp = tokio::task::spawn_blocking(move || check_tcp(&addrs)).await;
+    let tcp = tcp.unwrap_or_else(|e| Err(Check::fail("tcp", e.to_string(), "Internal error")));
+    let stream = match tcp {
+        Ok((stream, check)) => {
+            checks.push(check);
+            stream
+        }
+        Err(check) => {
+            checks.push(check);
+            return checks;
+        }
+    };
+
+    if url.scheme() == "https" {
+        let tls_host = host.clone();
+        let tls = tokio::task::spawn_blocking(move || check_tls(&tls_host, stream)).await;
+        let check = tls.unwrap_or_else(|e| Check::fail("tls", e.to_string(), "Internal error"));
+        let failed = check.status == Status::Fail;
+        checks.push(check);
+        if failed {
+            return checks;
+        }
+    } else {
+        drop(stream);
+        checks.push(Check::warn(
+            "tls",
+            "Server URL uses plain HTTP".to_string(),
+            "Your API token is sent unencrypted; use https:// if the server supports it",
+        ));
+    }
+
+    checks.push(check_api(config, profile).await);
+    checks
+}
+
+fn check_url(raw: &str) -> Result<(Url, Check), Check> {
+    let hint = "Set server.url to the address you open in a browser, e.g. https://gogs.example.com";
+    let url = Url::parse(raw).map_err(|e| Check::fail("url", format!("'{}' is not a valid URL: {}", raw, e), hint))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(Check::fail("url", format!("Unsupported scheme '{}'", url.scheme()), hint));
+    }
+    if url.host_str().is_none() {
+        return Err(Check::fail("url", format!("'{}' has no host name", raw), hint));
+    }
+    if url.path().trim_end_matches('/').ends_with("/api/v1") {
+        return Ok((
+            url.clone(),
+            Check::warn(
+                "url",
+                format!("{} includes the API path", raw),
+                "Remove /api/v1 from server.url; gog adds the API prefix itself",
+            ),
+        ));
+    }
+    Ok((url.clone(), Check::ok("url", url.to_string())))
+}
+
+async fn check_dns(host: &str, port: u16) -> Result<(Vec<SocketAddr>, Check), Check> {
+    let started = Instant::now();
+    let lookup = tokio::time::timeout(TIMEOUT, tokio::net::lookup_host((host, port))).await;
+    let elapsed = started.elapsed();
+
+    let hint = "Check the host name for typos, and that your DNS or VPN can see the server";
+    match lookup {
+        Ok(Ok(addrs)) => {
+            let addrs: Vec<SocketAddr> = addrs.collect();
+            if addrs.is_empty() {
+                return Err(Check::fail("dns", format!("{} resolved to no addresses", host), hint));
+            }
+            let shown: Vec<String> = addrs.iter().map(|a| a.ip().to_string()).collect();
+            let detail = format!("{} -> {} ({}ms)", host, shown.join(", "), elapsed.as_millis());
+            Ok((addrs, Check::ok("dns", detail)))
+        }
+        Ok(Err(e)) => Err(Check::fail("dns", format!("Cannot resolve {}: {}", host, e), hint)),
+        Err(_) => Err(Check::fail("dns", format!("Resolving {} timed out", host), hint)),
+    }
+}
+
+fn check_tcp(addrs: &[SocketAddr]) -> Result<(TcpStream, Check), Check> {
+    let mut last_error = None;
+    for addr in addrs {
+        let started = Instant::now();
+        match TcpStream::connect_timeout(addr, TIMEOUT) {
+            Ok(stream) => {
+                let _ = stream.set_read_timeout(Some(TIMEOUT));
+                let detail = format!("Connected to {} ({}ms)", addr, started.elapsed().as_millis());
+                return Ok((stream, Check::ok("tcp", detail)));
+            }
+            Err(e) => last_error = Some(format!("{}: {}", addr, e)),
+        }
+    }
+
+    Err(Check::fail(
+        "tcp",
+        format!("Cannot connect to {}", last_error.unwrap_or_default()),
+        "Check the port in server.url, that the server is running, and that no firewall or proxy blocks it",
+    ))
+}
+
+fn check_tls(host: &str, stream: TcpStream) -> Check {
+    let connector = match native_tls::TlsConnector::new() {
+        Ok(connector) => connector,
+        Err(e) => return Check::fail("tls", e.to_string(), "The system TLS library could not be loaded"),
+    };
+
+    let tls = match connector.connect(host, stream) {
+        Ok(tls) => tls,
+        Err(e) => {
+            return Check::fail(
+                "tls",
+                format!("Handshake failed: {}", e),
+                "The certificate may be self-signed, expired, or issued for another name; \
+                 install the issuing CA in your system trust store",
+            )
+        }
+    };
+
+    let der = match tls.peer_certificate() {
+        Ok(Some(cert)) => cert.to_der().unwrap_or_default(),
+        _ => return Check::ok("tls", "Certificate verified".to_string()),
+    };
+    let not_after = x509_parser::parse_x509_certificate(&der)
+        .ok()
+        .and_then(|(_, cert)| DateTime::<Utc>::from_timestamp(cert.validity().not_after.timestamp(), 0));
+    let Some(not_after) = not_after else {
+        return Check::ok("tls", "Certificate verified".to_string());
+    };
+
+    let days_left = (not_after - Utc::now()).num_days();
+    let detail = format!(
+        "Certificate valid until {} ({} days)",
+        not_after.format("%Y-%m-%d"),
+        days_left
+    );
+    if days_left < EXPIRY_WARNING_DAYS {
+        Check::warn("tls", detail, "Renew the server certificate soon")
+    } else {
+        Check::ok("tls", detail)
+    }
+}
+
+async fn check_api(config: &Config, profile: Option<&Profile>) -> Check {
+    let prefix = config.api_prefix();
+    let base = config.server.url.trim_end_matches('/');
+    let url = format!("{}{}/user", base, prefix);
+
+    let client = match reqwest::Client::builder().timeout(TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => return Check::fail("api", e.to_string(), "Internal error"),
+    };
+    let mut req = client.get(&url);
+    if let Some(profile) = profile {
+        req = req.header("Authorization", format!("token {}", profile.token));
+    }
+
+    let started = Instant::now();
+    let resp = match req.send().await {
+        Ok(resp) => resp,
+        Err(e) => {
+            return Check::fail(
+                "api",
+                format!("Request to {} failed: {}", url, e),
+                "The server is reachable but HTTP requests fail; check for a proxy in between",
+            )
+        }
+    };
+    let elapsed = started.elapsed().as_millis();
+    let status = resp.status();
+    let is_json = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("json"));
+
+    if status.as_u16() == 404 || (status.is_success() && !is_json) {
+        return Check::fail(
+            "api",
+            format!("No Gogs API at {}{} (HTTP {})", base, prefix, status.as_u16()),
+            "Check server.url points at the Gogs root, or set server.api_prefix if the API is served elsewhere",
+        );
+    }
+    if status.as_u16() == 401 {
+        let detail = format!("API found at {} ({}ms), but the token was rejected", prefix, elapsed);
+        return match profile {
+            Some(_) => Check::warn("api", detail, "Create a new token in Gogs user settings and update the profile"),
+            None => Check::warn("api", detail, "No profile configured; run 'gog init'"),
+        };
+    }
+    if !status.is_success() {
+        return Check::fail(
+            "api",
+            format!("{} returned HTTP {}", url, status),
+            "The server reported an error; check its logs",
+        );
+    }
+
+    let user = resp
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|v| v.get("username").and_then(|u| u.as_str()).map(String::from));
+    let detail = match user {
+        Some(user) => format!("API at {} ({}ms), authenticated as {}", prefix, elapsed, user),
+        None => format!("API at {} ({}ms)", prefix, elapsed),
+    };
+    Check::ok("api", detail)
+}
@@ -0,0 +1,84 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::IsTerminal;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Lines starting with this marker, and everything after them, are dropped.
+const MARKER: &str = "<!-- gog:";
+
+/// Whether an editor can be used, i.e. we are attached to a terminal.
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+/// Open `$VISUAL`/`$EDITOR` on a temporary file holding `initial` followed by
+/// `hint` as instructions, and return what the user left above them. An empty
+/// result aborts.
+pub fn compose(initial: &str, hint: &str) -> Result<String> {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let path = std::env::temp_dir().join(format!("gog-{}-{}.md", std::process::id(), stamp));
+
+    let initial = initial.trim_end();
+    let separator = if initial.is_empty() { "" } else { "\n" };
+    let template = format!(
+        "{}\n{}{} {} -->\n{} Everything from the first such line down is ignored. Save an empty file to abort. -->\n",
+        initial,
+        separator,
+        MARKER,
+        hint,
+        MARKER
+    );
+    fs::write(&path, template).context("Failed to create temporary file for editor")?;
+
+    let result = run_editor(&path).and_then(|()| {
+        fs::read_to_string(&path).context("Failed to read back editor contents")
+    });
+    let _ = fs::remove_file(&path);
+
+    let text = strip_instructions(&result?);
+    if text.is_empty() {
+        anyhow::bail!("Aborted: empty text");
+    }
+    Ok(text)
+}
+
+fn run_editor(path: &std::path::Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| default_editor().to_string());
+
+    // Allow editors with arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().context("Editor command is empty")?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .context(format!("Failed to launch editor '{}'", editor))?;
+
+    if !status.success() {
+        anyhow::bail!("Editor '{}' exited with {}", editor, status);
+    }
+    Ok(())
+}
+
+fn default_editor() -> &'static str {
+    if cfg!(windows) {
+        "notepad"
+    } else {
+        "vi"
+    }
+}
+
+fn strip_instructions(text: &str) -> String {
+    let kept: Vec<&str> = text
+        .lines()
+        .take_while(|line| !line.trim_start().starts_with(MARKER))
+        .collect();
+    kept.join("\n").trim().to_string()
+}
@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// How the `[profiles]` section is encrypted. The work is done by the age
+/// tool (https://age-encryption.org), to public keys or with a passphrase.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct EncryptionConfig {
+    /// age public keys (age1...) to encrypt to; without any, a passphrase is asked for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipients: Vec<String>,
+    /// age identity file holding a private key for one of the recipients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<PathBuf>,
+    /// The profiles as encrypted on disk; filled in on save, emptied on load
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub profiles: String,
+}
+
+impl EncryptionConfig {
+    /// What `encrypt` protects the text with, for messages.
+    pub fn describe(&self) -> String {
+        match self.recipients.len() {
+            0 => "a passphrase".to_string(),
+            1 => "1 age recipient".to_string(),
+            n => format!("{} age recipients", n),
+        }
+    }
+}
+
+/// `plaintext` encrypted and ASCII-armored. With a passphrase, age asks for
+/// it on the terminal.
+pub fn encrypt(plaintext: &str, config: &EncryptionConfig) -> Result<String> {
+    let mut args = vec!["--encrypt", "--armor"];
+    if config.recipients.is_empty() {
+        args.push("--passphrase");
+    }
+    for recipient in &config.recipients {
+        args.extend(["--recipient", recipient.as_str()]);
+    }
+    run(&args, plaintext).context("Failed to encrypt the profiles")
+}
+
+/// The plaintext of what `encrypt` produced.
+pub fn decrypt(armored: &str, config: &EncryptionConfig) -> Result<String> {
+    let identity = config.identity.as_ref().map(|path| path.display().to_string());
+    let mut args = vec!["--decrypt"];
+    if let Some(identity) = &identity {
+        args.extend(["--identity", identity.as_str()]);
+    }
+    run(&args, armored).context("Failed to decrypt the profiles in the config")
+}
+
+/// Run age with `input` on stdin. Prompts and errors go to the terminal.
+fn run(args: &[&str], input: &str) -> Result<String> {
+    let mut child = Command::new("age")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .context("Failed to run 'age'; is it installed? See https://age-encryption.org")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!("age failed ({})", output.status);
+    }
+    String::from_utf8(output.stdout).context("age printed something other than text")
+}
@@ -11,12 +11,22 @@ pub enum GogsError {
     #[error("Resource not found: {0}")]
     NotFound(String),
 
+    #[error("Access denied. Check permissions for this resource.")]
+    Forbidden,
+
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    #[error("Interrupted: {0}")]
+    Interrupted(String),
+
+    /// A command run by `gog capture` failed; gog exits with its status
+    #[error("Command exited with status {0}")]
+    CommandFailed(i32),
+
     #[error("Network error: {0}")]
     NetworkError(#[from] reqwest::Error),
 
@@ -28,6 +38,8 @@ impl GogsError {
     pub fn exit_code(&self) -> i32 {
         match self {
             Self::NotFound(_) => 2,
+            Self::Interrupted(_) => crate::shutdown::INTERRUPTED_EXIT,
+            Self::CommandFailed(status) => (*status).clamp(1, 255),
             _ => 1,
         }
     }
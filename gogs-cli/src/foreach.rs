@@ -0,0 +1,53 @@
+use crate::api::Repository;
+use crate::glob;
+
+/// Placeholders a `gog foreach` command template can use.
+pub const PLACEHOLDERS: &[&str] = &["{repo}", "{owner}", "{name}"];
+
+/// Whether any argument of `template` names the repository.
+pub fn has_placeholder(template: &[String]) -> bool {
+    template
+        .iter()
+        .any(|arg| PLACEHOLDERS.iter().any(|p| arg.contains(p)))
+}
+
+/// `template` for `repo`: {repo} becomes owner/name, {owner} and {name} their parts.
+pub fn expand(template: &[String], repo: &Repository) -> Vec<String> {
+    template
+        .iter()
+        .map(|arg| {
+            arg.replace("{repo}", &repo.full_name)
+                .replace("{owner}", &repo.owner.username)
+                .replace("{name}", &repo.name)
+        })
+        .collect()
+}
+
+/// Whether `full_name` matches any of `patterns`, globs like `org/*` or
+/// `*/api-*`. Repository names are case-insensitive in Gogs.
+pub fn matches(patterns: &[String], full_name: &str) -> bool {
+    let full_name = full_name.to_lowercase();
+    patterns
+        .iter()
+        .any(|pattern| glob::matches(&pattern.to_lowercase(), &full_name))
+}
+
+/// One repository's run of the command.
+#[derive(Debug)]
+pub struct Run {
+    pub repo: String,
+    /// The gog arguments run, after the global options
+    pub args: Vec<String>,
+    /// Exit status; None if the run was never started, or was killed by a signal
+    pub status: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// An interrupt stopped the run before it began
+    pub skipped: bool,
+}
+
+impl Run {
+    pub fn ok(&self) -> bool {
+        self.status == Some(0)
+    }
+}
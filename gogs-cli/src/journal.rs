@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::storage::Storage;
+
+/// Key of the journal in local storage.
+const JOURNAL_KEY: &str = "journal.jsonl";
+
+/// One change to existing content on the server: a comment edited or deleted,
+/// or an issue's body replaced.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub at: DateTime<Utc>,
+    /// Account that made the change, and the signature it used
+    pub user: String,
+    pub signature: String,
+    /// "edit comment", "delete comment" or "edit issue"
+    pub action: String,
+    pub repo: String,
+    pub issue: i64,
+    /// The comment changed; None when the issue's own body was
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<i64>,
+    /// Who wrote the comment or issue
+    pub author: String,
+    /// The comment was someone else's, changed with --force-other-author
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub other_author: bool,
+    /// The text before the change, so it can be put back
+    pub before: String,
+    /// SHA-256 of `before`, to tell whether the text is still as it was left
+    #[serde(default)]
+    pub before_sha256: String,
+    /// The text after an edit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+/// Append-only record of changes to existing content, one JSON object per
+/// line, so the prior text of anything overwritten can be recovered.
+pub struct Journal {
+    storage: Option<Arc<dyn Storage>>,
+}
+
+impl Journal {
+    /// Keep the journal in `storage`; with none, nothing can be recorded.
+    pub fn new(storage: Option<Arc<dyn Storage>>) -> Self {
+        Self { storage }
+    }
+
+    /// Where the journal is kept, for messages.
+    pub fn location(&self) -> Option<String> {
+        self.storage.as_ref().map(|storage| storage.describe(JOURNAL_KEY))
+    }
+
+    /// Add `entry`. Callers record before making the change, so a change is
+    /// never made without its record.
+    pub fn record(&self, entry: &JournalEntry) -> Result<()> {
+        let storage = self
+            .storage
+            .as_deref()
+            .context("No cache directory to keep the journal in")?;
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        storage
+            .append(JOURNAL_KEY, line.as_bytes())
+            .with_context(|| format!("Failed to write the journal at {}", storage.describe(JOURNAL_KEY)))
+    }
+}
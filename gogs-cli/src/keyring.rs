@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Service name every entry is stored under.
+const SERVICE: &str = "gogs-cli";
+
+/// Keyring entry for a profile's token, e.g. `builder@gogs.example.com`.
+pub fn entry_name(profile: &str, server_url: &str) -> String {
+    let host = server_url
+        .split("://")
+        .last()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    format!("{}@{}", profile, host)
+}
+
+/// The token stored under `entry`.
+pub fn get(entry: &str) -> Result<String> {
+    let output = lookup(entry)?;
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || token.is_empty() {
+        anyhow::bail!("No token stored in the OS keyring under '{}'", entry);
+    }
+    Ok(token)
+}
+
+/// Store `token` under `entry`, replacing any token already there. The token
+/// goes to the keyring tool on stdin, never on its command line.
+pub fn set(entry: &str, token: &str) -> Result<()> {
+    let output = store(entry, token)?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "The OS keyring refused the token: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Remove `entry`; returns whether there was one.
+pub fn delete(entry: &str) -> Result<bool> {
+    // Not every tool says whether it found anything to clear
+    let existed = lookup(entry)?.status.success();
+    let output = clear(entry)?;
+    if existed && !output.status.success() {
+        anyhow::bail!(
+            "The OS keyring kept the token: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(existed)
+}
+
+/// Run `program` with `args`, writing `input` to its stdin.
+fn run(program: &str, args: &[&str], input: &str) -> Result<Output> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run '{}' for the OS keyring; is it installed?", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    Ok(child.wait_with_output()?)
+}
+
+#[cfg(target_os = "macos")]
+fn lookup(entry: &str) -> Result<Output> {
+    run("security", &["find-generic-password", "-s", SERVICE, "-a", entry, "-w"], "")
+}
+
+#[cfg(target_os = "macos")]
+fn store(entry: &str, token: &str) -> Result<Output> {
+    // `security -i` reads commands from stdin, which keeps the token out of ps
+    let command = format!(
+        "add-generic-password -U -s {} -a {} -w {}\n",
+        quoted(SERVICE)?,
+        quoted(entry)?,
+        quoted(token)?
+    );
+    run("security", &["-i"], &command)
+}
+
+/// `value` as one double-quoted word on a `security -i` command line. A line
+/// break would end the command, so values with one are refused.
+#[cfg(any(target_os = "macos", test))]
+fn quoted(value: &str) -> Result<String> {
+    if value.contains(['\n', '\r']) {
+        anyhow::bail!("Keyring entries and tokens can't contain line breaks");
+    }
+    Ok(format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")))
+}
+
+#[cfg(target_os = "macos")]
+fn clear(entry: &str) -> Result<Output> {
+    run("security", &["delete-generic-password", "-s", SERVICE, "-a", entry], "")
+}
+
+/// The Secret Service (GNOME Keyring, KWallet) through libsecret's tool.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn lookup(entry: &str) -> Result<Output> {
+    run("secret-tool", &["lookup", "service", SERVICE, "account", entry], "")
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn store(entry: &str, token: &str) -> Result<Output> {
+    let label = format!("Gogs API token ({})", entry);
+    run(
+        "secret-tool",
+        &["store", "--label", &label, "service", SERVICE, "account", entry],
+        token,
+    )
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn clear(entry: &str) -> Result<Output> {
+    run("secret-tool", &["clear", "service", SERVICE, "account", entry], "")
+}
+
+// Windows has no stock command that reads a credential back out
+#[cfg(target_os = "windows")]
+fn lookup(_entry: &str) -> Result<Output> {
+    anyhow::bail!("The OS keyring isn't supported on Windows yet; keep the token in the config")
+}
+
+#[cfg(target_os = "windows")]
+fn store(entry: &str, _token: &str) -> Result<Output> {
+    lookup(entry)
+}
+
+#[cfg(target_os = "windows")]
+fn clear(entry: &str) -> Result<Output> {
+    lookup(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoting_keeps_values_whole() {
+        assert_eq!(quoted(r#"a"b\c"#).unwrap(), r#""a\"b\\c""#);
+        assert!(quoted("a\n-w x").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::api::{default_label_color, label_name_matches, parse_color, Label};
+
+/// A declarative set of labels every repository should carry, read from a
+/// `labels.toml` file.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct LabelManifest {
+    #[serde(default, rename = "label")]
+    pub labels: Vec<LabelSpec>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LabelSpec {
+    pub name: String,
+
+    /// Hex color; derived from the name when omitted
+    pub color: Option<String>,
+
+    /// Old names this label should be renamed from
+    #[serde(default)]
+    pub renamed_from: Vec<String>,
+}
+
+/// One edit needed to bring a repository's labels in line with a manifest.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum LabelChange {
+    Create {
+        name: String,
+        color: String,
+    },
+    Update {
+        #[serde(skip)]
+        id: i64,
+        from_name: String,
+        from_color: String,
+        /// New name, if it changes
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        /// New color, if it changes
+        #[serde(skip_serializing_if = "Option::is_none")]
+        color: Option<String>,
+    },
+}
+
+impl LabelManifest {
+    /// Read and validate a manifest, normalizing every color to `#rrggbb`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read label manifest {}", path.display()))?;
+        let mut manifest: LabelManifest = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse label manifest {}", path.display()))?;
+
+        if manifest.labels.is_empty() {
+            anyhow::bail!("{} defines no [[label]] entries", path.display());
+        }
+
+        let mut seen: Vec<String> = Vec::new();
+        for spec in &mut manifest.labels {
+            if spec.name.trim().is_empty() {
+                anyhow::bail!("{}: label name cannot be empty", path.display());
+            }
+            let color = match &spec.color {
+                Some(color) => parse_color(color)
+                    .map_err(|e| anyhow::anyhow!("{}: label '{}': {}", path.display(), spec.name, e))?,
+                None => default_label_color(&spec.name),
+            };
+            spec.color = Some(color);
+
+            for name in std::iter::once(&spec.name).chain(&spec.renamed_from) {
+                let key = name.to_lowercase();
+                if seen.contains(&key) {
+                    anyhow::bail!("{}: '{}' appears more than once", path.display(), name);
+                }
+                seen.push(key);
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Work out the changes that make `existing` match this manifest. Labels
+    /// the manifest doesn't mention are left alone.
+    pub fn plan(&self, existing: &[Label], exact: bool) -> Vec<LabelChange> {
+        let mut claimed: Vec<i64> = Vec::new();
+        let mut changes = Vec::new();
+
+        for spec in &self.labels {
+            let color = spec.color.clone().unwrap_or_else(|| default_label_color(&spec.name));
+            let unclaimed = |name: &str| {
+                existing
+                    .iter()
+                    .find(|l| !claimed.contains(&l.id) && label_name_matches(&l.name, name, exact))
+            };
+
+            // Prefer a label already carrying the name over one to be renamed
+            let found = unclaimed(&spec.name)
+                .or_else(|| spec.renamed_from.iter().find_map(|old| unclaimed(old)))
+                .cloned();
+
+            let Some(label) = found else {
+                changes.push(LabelChange::Create {
+                    name: spec.name.clone(),
+                    color,
+                });
+                continue;
+            };
+            claimed.push(label.id);
+
+            let current_color = parse_color(&label.color).unwrap_or_else(|_| label.color.clone());
+            let name = (label.name != spec.name).then(|| spec.name.clone());
+            let color = (current_color != color).then_some(color);
+            if name.is_some() || color.is_some() {
+                changes.push(LabelChange::Update {
+                    id: label.id,
+                    from_name: label.name,
+                    from_color: current_color,
+                    name,
+                    color,
+                });
+            }
+        }
+
+        changes
+    }
+}
@@ -1,7 +1,32 @@
 // Library root for testing
 pub mod api;
+pub mod bench;
+pub mod browser;
+pub mod capture;
 pub mod cli;
 pub mod commands;
 pub mod config;
+pub mod coverage;
+pub mod doctor;
+pub mod editor;
+pub mod encryption;
 pub mod error;
+pub mod foreach;
+pub mod glob;
+pub mod journal;
+pub mod keyring;
+pub mod labels;
+pub mod lint;
+pub mod open_items;
 pub mod output;
+pub mod presets;
+pub mod provenance;
+pub mod remote;
+pub mod scrub;
+pub mod shutdown;
+pub mod signature;
+pub mod storage;
+pub mod time;
+pub mod update;
+pub mod version;
+pub mod votes;
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct LintConfig {
+    /// Section headings that must appear in the body (e.g. "Acceptance Criteria")
+    #[serde(default)]
+    pub required_sections: Vec<String>,
+
+    /// Maximum body length in characters
+    pub max_length: Option<usize>,
+
+    /// Phrases that must not appear in the body (case-insensitive)
+    #[serde(default)]
+    pub forbidden_phrases: Vec<String>,
+
+    /// Require a `---` delimited metadata block at the top of the body
+    #[serde(default)]
+    pub require_front_matter: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Violation {
+    pub rule: String,
+    pub message: String,
+}
+
+impl Violation {
+    fn new(rule: &str, message: String) -> Self {
+        Self {
+            rule: rule.to_string(),
+            message,
+        }
+    }
+}
+
+pub fn lint_body(body: &str, rules: &LintConfig) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    if rules.require_front_matter && !has_front_matter(body) {
+        violations.push(Violation::new(
+            "front-matter",
+            "Body must start with a '---' delimited metadata block".to_string(),
+        ));
+    }
+
+    for section in &rules.required_sections {
+        if !has_section(body, section) {
+            violations.push(Violation::new(
+                "required-section",
+                format!("Missing required section '{}'", section),
+            ));
+        }
+    }
+
+    if let Some(max) = rules.max_length {
+        let len = body.chars().count();
+        if len > max {
+            violations.push(Violation::new(
+                "max-length",
+                format!("Body is {} characters, maximum is {}", len, max),
+            ));
+        }
+    }
+
+    let lower = body.to_lowercase();
+    for phrase in &rules.forbidden_phrases {
+        if lower.contains(&phrase.to_lowercase()) {
+            violations.push(Violation::new(
+                "forbidden-phrase",
+                format!("Body contains forbidden phrase '{}'", phrase),
+            ));
+        }
+    }
+
+    violations
+}
+
+fn has_front_matter(body: &str) -> bool {
+    let mut lines = body.trim_start().lines();
+    if lines.next().map(str::trim_end) != Some("---") {
+        return false;
+    }
+    lines.any(|line| line.trim_end() == "---")
+}
+
+fn has_section(body: &str, section: &str) -> bool {
+    body.lines().any(|line| {
+        let line = line.trim();
+        // Markdown heading ("## Acceptance Criteria") or label line ("Acceptance Criteria:")
+        let heading = line.trim_start_matches('#');
+        let name = if heading.len() < line.len() {
+            heading.trim()
+        } else if let Some(label) = line.strip_suffix(':') {
+            label.trim()
+        } else {
+            return false;
+        };
+        name.eq_ignore_ascii_case(section)
+    })
+}
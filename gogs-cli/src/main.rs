@@ -1,25 +1,22 @@
 use anyhow::Result;
-use clap::Parser;
 use std::process::ExitCode;
 
-mod api;
-mod cli;
-mod commands;
-mod config;
-mod error;
-mod output;
-
-use cli::Cli;
+use gogs_cli::cli::Cli;
+use gogs_cli::commands;
+use gogs_cli::error::GogsError;
+use gogs_cli::shutdown;
 
 #[tokio::main]
 async fn main() -> ExitCode {
-    let cli = Cli::parse();
+    let cli = Cli::parse_args();
+    shutdown::install();
 
     match run(cli).await {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
             eprintln!("Error: {:#}", e);
-            ExitCode::FAILURE
+            let code = e.downcast_ref::<GogsError>().map_or(1, GogsError::exit_code);
+            ExitCode::from(code as u8)
         }
     }
 }
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::api::{Comment, Issue};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Issue,
+    Pull,
+}
+
+impl ItemKind {
+    pub fn marker(self) -> &'static str {
+        match self {
+            ItemKind::Issue => "issue",
+            ItemKind::Pull => "PR",
+        }
+    }
+}
+
+/// Why an item is on the current user's list, most pressing first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Reason {
+    Assigned,
+    Mentioned,
+}
+
+impl Reason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Reason::Assigned => "assigned",
+            Reason::Mentioned => "mentioned",
+        }
+    }
+}
+
+/// An open issue or pull request that concerns the current user.
+#[derive(Debug, Serialize)]
+pub struct OpenItem {
+    pub kind: ItemKind,
+    pub repo: String,
+    pub number: i64,
+    pub title: String,
+    pub reasons: Vec<Reason>,
+    pub updated_at: DateTime<Utc>,
+    pub html_url: String,
+}
+
+/// Whether `text` mentions `@username`, ignoring case. An '@' inside a word,
+/// as in an email address, is not a mention.
+pub fn mentions(text: &str, username: &str) -> bool {
+    let text = text.to_lowercase();
+    let needle = format!("@{}", username.to_lowercase());
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.');
+
+    text.match_indices(&needle).any(|(at, _)| {
+        let starts_word = !text[..at].chars().next_back().is_some_and(|c| c.is_alphanumeric());
+        let mut rest = text[at + needle.len()..].chars();
+        let continues = match rest.next() {
+            // "@bob." ends a sentence; "@bob.smith" is someone else
+            Some('.') => rest.next().is_some_and(is_name_char),
+            Some(c) => is_name_char(c),
+            None => false,
+        };
+        starts_word && !continues
+    })
+}
+
+/// Why `issue` concerns `username`, given its comments (empty when not fetched).
+/// The user's own words are not mentions.
+pub fn reasons(issue: &Issue, comments: &[Comment], username: &str) -> Vec<Reason> {
+    let mut reasons = Vec::new();
+    let mine = |name: &str| name.eq_ignore_ascii_case(username);
+
+    if issue.assignee.as_ref().is_some_and(|a| mine(&a.username)) {
+        reasons.push(Reason::Assigned);
+    }
+
+    let in_body = !mine(&issue.user.username) && issue.body.as_deref().is_some_and(|b| mentions(b, username));
+    let in_comments = comments
+        .iter()
+        .any(|c| !mine(&c.user.username) && mentions(&c.body, username));
+    if in_body || in_comments {
+        reasons.push(Reason::Mentioned);
+    }
+    reasons
+}
+
+pub fn item(repo: &str, issue: Issue, reasons: Vec<Reason>) -> OpenItem {
+    OpenItem {
+        kind: if issue.pull_request.is_some() { ItemKind::Pull } else { ItemKind::Issue },
+        repo: repo.to_string(),
+        number: issue.number,
+        title: issue.title,
+        reasons,
+        updated_at: issue.updated_at,
+        html_url: issue.html_url,
+    }
+}
+
+/// Most pressing reason first, then most recently active.
+pub fn prioritize(items: &mut [OpenItem]) {
+    items.sort_by(|a, b| {
+        a.reasons
+            .first()
+            .cmp(&b.reasons.first())
+            .then_with(|| b.updated_at.cmp(&a.updated_at))
+            .then_with(|| a.repo.cmp(&b.repo))
+            .then_with(|| a.number.cmp(&b.number))
+    });
+}
@@ -1,4 +1,31 @@
-use crate::api::types::{Comment, Issue, Repository};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::api::types::{
+    Attachment, Branch, Collaborator, CollaboratorPermission, Comment, FileCommit, FileContents, Fork, Issue, Label, Organization,
+    AccessToken, PublicKey, Repository, User,
+};
+#[cfg(unix)]
+use crate::api::daemon::DaemonStatus;
+use crate::api::quarantine::Quarantined;
+use crate::bench::BenchReport;
+use crate::coverage::{CoverageReport, Route};
+use crate::doctor::{Check, Status};
+use crate::error::GogsError;
+use crate::foreach::Run;
+use crate::labels::LabelChange;
+use crate::lint::Violation;
+use crate::open_items::OpenItem;
+use crate::presets::RolePreset;
+use crate::provenance::{self, Provenance};
+use crate::time::format_timestamp;
+use crate::version::{BuildInfo, ServerReport, Support};
+use crate::votes::RankedIssue;
 
 pub enum OutputFormat {
     Human,
@@ -15,7 +42,227 @@ impl OutputFormat {
     }
 }
 
-pub fn format_issue_list(issues: Vec<(String, Vec<Issue>)>, format: &OutputFormat) -> String {
+/// When to color human output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Only when writing to a terminal and NO_COLOR is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+static COLOR: OnceLock<bool> = OnceLock::new();
+
+/// Decide whether human output uses color. Only the first call takes effect.
+pub fn configure_color(choice: ColorChoice) {
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        // https://no-color.org: any non-empty value disables color
+        ColorChoice::Auto => {
+            std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                && std::env::var("TERM").map_or(true, |t| t != "dumb")
+                && std::io::stdout().is_terminal()
+        }
+    };
+    let _ = COLOR.set(enabled);
+}
+
+/// Whether human output is colored, as decided by `configure_color`.
+pub fn color_enabled() -> bool {
+    *COLOR.get_or_init(|| false)
+}
+
+static CANONICAL: OnceLock<bool> = OnceLock::new();
+
+/// Make output repeatable for the same server state: JSON object keys sorted,
+/// issues ordered by repository then number, labels by name. Only the first
+/// call takes effect.
+pub fn configure_canonical(enabled: bool) {
+    let _ = CANONICAL.set(enabled);
+}
+
+fn canonical() -> bool {
+    *CANONICAL.get_or_init(|| false)
+}
+
+/// Pretty JSON for `value`. In canonical mode every object's keys are sorted,
+/// not just written in field order.
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> serde_json::Result<String> {
+    if canonical() {
+        // serde_json's Map is ordered by key, so a round trip through Value sorts
+        serde_json::to_string_pretty(&serde_json::to_value(value)?)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+}
+
+fn sort_labels(labels: &mut [Label]) {
+    labels.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+}
+
+/// `issue` with its labels in canonical order, copied only in canonical mode.
+fn canonical_issue(issue: &Issue) -> Cow<'_, Issue> {
+    if !canonical() {
+        return Cow::Borrowed(issue);
+    }
+    let mut issue = issue.clone();
+    sort_labels(&mut issue.labels);
+    Cow::Owned(issue)
+}
+
+/// Field captions in human output that `--labels-file` can replace.
+pub const CAPTIONS: &[&str] = &[
+    "State",
+    "Author",
+    "Assignee",
+    "Milestone",
+    "Created",
+    "Updated",
+    "Labels",
+    "URL",
+    "Description",
+    "Website",
+    "Default branch",
+    "Visibility",
+    "Size",
+    "Fork of",
+    "Fork",
+    "Mirror",
+    "Stars",
+    "Watchers",
+    "Forks",
+    "Open issues",
+    "Your permissions",
+    "Clone (HTTPS)",
+    "Clone (SSH)",
+    "Commit",
+    "Committer",
+    "Date",
+];
+
+static CAPTION_OVERRIDES: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Read replacement captions from a TOML file of `"Caption" = "Replacement"`
+/// lines. Unknown captions are warned about, not rejected, so one file can
+/// serve several gog versions. Only the first call takes effect.
+pub fn configure_captions(path: &Path) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read labels file {}", path.display()))?;
+    let overrides: HashMap<String, String> = toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse labels file {}", path.display()))?;
+
+    let mut unknown: Vec<&str> = overrides
+        .keys()
+        .map(String::as_str)
+        .filter(|key| !CAPTIONS.contains(key))
+        .collect();
+    if !unknown.is_empty() {
+        unknown.sort();
+        eprintln!(
+            "Warning: {} has unknown captions: {} (known: {})",
+            path.display(),
+            unknown.join(", "),
+            CAPTIONS.join(", ")
+        );
+    }
+
+    let _ = CAPTION_OVERRIDES.set(overrides);
+    Ok(())
+}
+
+/// The caption shown for `key`, one of `CAPTIONS`.
+fn caption(key: &'static str) -> &'static str {
+    CAPTION_OVERRIDES
+        .get()
+        .and_then(|overrides| overrides.get(key))
+        .map(String::as_str)
+        .unwrap_or(key)
+}
+
+/// One "Caption: value" line.
+fn field(key: &'static str, value: impl std::fmt::Display) -> String {
+    format!("{}: {}\n", caption(key), value)
+}
+
+/// Render a label as a badge in its own color, or as `[name]` without color.
+fn label_badge(label: &Label) -> String {
+    let rgb = color_enabled()
+        .then(|| crate::api::parse_color(&label.color).ok())
+        .flatten()
+        .and_then(|hex| u32::from_str_radix(&hex[1..], 16).ok());
+    let Some(rgb) = rgb else {
+        return format!("[{}]", label.name);
+    };
+
+    let (r, g, b) = ((rgb >> 16) & 0xff, (rgb >> 8) & 0xff, rgb & 0xff);
+    // Dark text on light backgrounds, light text on dark ones
+    let luma = (299 * r + 587 * g + 114 * b) / 1000;
+    let fg = if luma > 140 { "30" } else { "97" };
+    format!("\x1b[{};48;2;{};{};{}m {} \x1b[0m", fg, r, g, b, label.name)
+}
+
+/// Unix timestamps emitted alongside the RFC 3339 fields in JSON output.
+#[derive(serde::Serialize)]
+struct Epochs {
+    created_at_epoch: i64,
+    updated_at_epoch: i64,
+}
+
+impl Epochs {
+    fn new(created_at: &DateTime<Utc>, updated_at: &DateTime<Utc>) -> Self {
+        Self {
+            created_at_epoch: created_at.timestamp(),
+            updated_at_epoch: updated_at.timestamp(),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct IssueJson<'a> {
+    #[serde(flatten)]
+    issue: &'a Issue,
+    #[serde(flatten)]
+    epochs: Epochs,
+}
+
+impl<'a> IssueJson<'a> {
+    fn new(issue: &'a Issue) -> Self {
+        Self {
+            issue,
+            epochs: Epochs::new(&issue.created_at, &issue.updated_at),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct CommentJson<'a> {
+    #[serde(flatten)]
+    comment: &'a Comment,
+    #[serde(flatten)]
+    epochs: Epochs,
+}
+
+impl<'a> CommentJson<'a> {
+    fn new(comment: &'a Comment) -> Self {
+        Self {
+            comment,
+            epochs: Epochs::new(&comment.created_at, &comment.updated_at),
+        }
+    }
+}
+
+pub fn format_issue_list(mut issues: Vec<(String, Vec<Issue>)>, format: &OutputFormat) -> String {
+    if canonical() {
+        issues.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, repo_issues) in &mut issues {
+            repo_issues.sort_by_key(|issue| issue.number);
+            for issue in repo_issues.iter_mut() {
+                sort_labels(&mut issue.labels);
+            }
+        }
+    }
     match format {
         OutputFormat::Human => format_issues_human(issues),
         OutputFormat::Json => format_issues_json(issues),
@@ -33,20 +280,12 @@ fn format_issues_human(issues: Vec<(String, Vec<Issue>)>) -> String {
             output.push_str(&format!("\n{}\n", repo));
 
             for issue in repo_issues {
-                let labels: Vec<String> = issue
-                    .labels
-                    .iter()
-                    .map(|l| format!("[{}]", l.name))
-                    .collect();
-                let labels_str = if labels.is_empty() {
-                    String::new()
-                } else {
-                    format!(" {}", labels.join(" "))
-                };
-
                 output.push_str(&format!(
                     "  #{:<4} [{}]{} {}\n",
-                    issue.number, issue.state, labels_str, issue.title
+                    issue.number,
+                    issue.state,
+                    format_labels_inline(issue),
+                    issue.title
                 ));
                 total += 1;
             }
@@ -65,28 +304,132 @@ fn format_issues_human(issues: Vec<(String, Vec<Issue>)>) -> String {
     output
 }
 
-fn format_issues_json(issues: Vec<(String, Vec<Issue>)>) -> String {
-    #[derive(serde::Serialize)]
-    struct IssueWithRepo {
-        repo: String,
-        #[serde(flatten)]
-        issue: Issue,
+/// Format issues from several repositories as one list, keeping the given order.
+pub fn format_sorted_issue_list(mut issues: Vec<(String, Issue)>, format: &OutputFormat) -> String {
+    if canonical() {
+        for (_, issue) in &mut issues {
+            sort_labels(&mut issue.labels);
+        }
+    }
+    match format {
+        OutputFormat::Human => {
+            let mut output = String::new();
+            let mut repos: Vec<&str> = issues.iter().map(|(repo, _)| repo.as_str()).collect();
+            repos.sort();
+            repos.dedup();
+
+            for (repo, issue) in &issues {
+                output.push_str(&format!(
+                    "  {}#{:<4} [{}]{} {}\n",
+                    repo,
+                    issue.number,
+                    issue.state,
+                    format_labels_inline(issue),
+                    issue.title
+                ));
+            }
+
+            if issues.is_empty() {
+                output.push_str("\nNo issues found.\n");
+            } else {
+                output.push_str(&format!(
+                    "\nTotal: {} issue(s) across {} repo(s)\n",
+                    issues.len(),
+                    repos.len()
+                ));
+            }
+            output
+        }
+        OutputFormat::Json => {
+            let flattened: Vec<IssueWithRepo> = issues
+                .iter()
+                .map(|(repo, issue)| IssueWithRepo {
+                    repo,
+                    issue: IssueJson::new(issue),
+                })
+                .collect();
+            to_json(&flattened).unwrap_or_else(|_| "[]".to_string())
+        }
+    }
+}
+
+fn format_labels_inline(issue: &Issue) -> String {
+    let labels: Vec<String> = issue
+        .labels
+        .iter()
+        .map(label_badge)
+        .collect();
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", labels.join(" "))
     }
+}
+
+#[derive(serde::Serialize)]
+struct IssueWithRepo<'a> {
+    repo: &'a str,
+    #[serde(flatten)]
+    issue: IssueJson<'a>,
+}
 
+fn format_issues_json(issues: Vec<(String, Vec<Issue>)>) -> String {
     let flattened: Vec<IssueWithRepo> = issues
-        .into_iter()
+        .iter()
         .flat_map(|(repo, repo_issues)| {
-            repo_issues.into_iter().map(move |issue| IssueWithRepo {
-                repo: repo.clone(),
-                issue,
+            repo_issues.iter().map(move |issue| IssueWithRepo {
+                repo,
+                issue: IssueJson::new(issue),
             })
         })
         .collect();
 
-    serde_json::to_string_pretty(&flattened).unwrap_or_else(|_| "[]".to_string())
+    to_json(&flattened).unwrap_or_else(|_| "[]".to_string())
+}
+
+pub fn format_issue_counts(counts: &[(String, usize)], quiet: bool, format: &OutputFormat) -> String {
+    let total: usize = counts.iter().map(|(_, n)| n).sum();
+
+    if quiet {
+        return format!("{}\n", total);
+    }
+
+    match format {
+        OutputFormat::Human => {
+            let mut output = String::new();
+            for (repo, count) in counts {
+                output.push_str(&format!("  {:<5} {}\n", count, repo));
+            }
+            output.push_str(&format!("\nTotal: {} issue(s) across {} repo(s)\n", total, counts.len()));
+            output
+        }
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct RepoCount<'a> {
+                repo: &'a str,
+                count: usize,
+            }
+
+            #[derive(serde::Serialize)]
+            struct Counts<'a> {
+                repos: Vec<RepoCount<'a>>,
+                total: usize,
+            }
+
+            let counts = Counts {
+                repos: counts
+                    .iter()
+                    .map(|(repo, count)| RepoCount { repo, count: *count })
+                    .collect(),
+                total,
+            };
+            to_json(&counts).unwrap_or_else(|_| "{}".to_string())
+        }
+    }
 }
 
 pub fn format_issue_detail(issue: &Issue, comments: &[Comment], format: &OutputFormat) -> String {
+    let issue = &*canonical_issue(issue);
     match format {
         OutputFormat::Human => format_issue_detail_human(issue, comments),
         OutputFormat::Json => format_issue_detail_json(issue, comments),
@@ -97,17 +440,28 @@ fn format_issue_detail_human(issue: &Issue, comments: &[Comment]) -> String {
     let mut output = String::new();
 
     output.push_str(&format!("#{} {}\n", issue.number, issue.title));
-    output.push_str(&format!("State: {}\n", issue.state));
-    output.push_str(&format!("Author: {}\n", issue.user.username));
-    output.push_str(&format!("Created: {}\n", issue.created_at));
-    output.push_str(&format!("Updated: {}\n", issue.updated_at));
+    output.push_str(&field("State", issue.state));
+    output.push_str(&field("Author", &issue.user.username));
+    if let Some(assignee) = &issue.assignee {
+        output.push_str(&field("Assignee", &assignee.username));
+    }
+    if let Some(milestone) = &issue.milestone {
+        output.push_str(&field("Milestone", &milestone.title));
+    }
+    output.push_str(&field("Created", format_timestamp(&issue.created_at)));
+    output.push_str(&field("Updated", format_timestamp(&issue.updated_at)));
 
     if !issue.labels.is_empty() {
-        let labels: Vec<&str> = issue.labels.iter().map(|l| l.name.as_str()).collect();
-        output.push_str(&format!("Labels: {}\n", labels.join(", ")));
+        let labels: Vec<String> = if color_enabled() {
+            issue.labels.iter().map(label_badge).collect()
+        } else {
+            issue.labels.iter().map(|l| l.name.clone()).collect()
+        };
+        let separator = if color_enabled() { " " } else { ", " };
+        output.push_str(&field("Labels", labels.join(separator)));
     }
 
-    output.push_str(&format!("URL: {}\n", issue.html_url));
+    output.push_str(&field("URL", &issue.html_url));
 
     if let Some(body) = &issue.body {
         if !body.is_empty() {
@@ -120,7 +474,10 @@ fn format_issue_detail_human(issue: &Issue, comments: &[Comment]) -> String {
         for comment in comments {
             output.push_str(&format!(
                 "\n@{} ({})\n{}\n",
-                comment.user.username, comment.created_at, comment.body
+                comment.user.username,
+                format_timestamp(&comment.created_at),
+                // Signature lines are for `issue verify`, not for reading
+                provenance::strip(&comment.body)
             ));
         }
     }
@@ -132,16 +489,60 @@ fn format_issue_detail_json(issue: &Issue, comments: &[Comment]) -> String {
     #[derive(serde::Serialize)]
     struct IssueDetail<'a> {
         #[serde(flatten)]
-        issue: &'a Issue,
-        comment_list: &'a [Comment],
+        issue: IssueJson<'a>,
+        comment_list: Vec<CommentJson<'a>>,
     }
 
     let detail = IssueDetail {
-        issue,
-        comment_list: comments,
+        issue: IssueJson::new(issue),
+        comment_list: comments.iter().map(CommentJson::new).collect(),
+    };
+
+    to_json(&detail).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// A repository's issues and their comments as one JSON document. Always JSON:
+/// an export is for other tools to read.
+pub fn format_export(repo: &str, issues: &[(Issue, Vec<Comment>)], scrubbed: bool) -> String {
+    #[derive(serde::Serialize)]
+    struct ExportedIssue<'a> {
+        #[serde(flatten)]
+        issue: IssueJson<'a>,
+        comment_list: Vec<CommentJson<'a>>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Export<'a> {
+        repo: &'a str,
+        /// Left out under --canonical, so the same issues export identically
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exported_at: Option<DateTime<Utc>>,
+        scrubbed: bool,
+        issues: Vec<ExportedIssue<'a>>,
+    }
+
+    let mut issues: Vec<(Cow<Issue>, &[Comment])> = issues
+        .iter()
+        .map(|(issue, comments)| (canonical_issue(issue), comments.as_slice()))
+        .collect();
+    if canonical() {
+        issues.sort_by_key(|(issue, _)| issue.number);
+    }
+
+    let export = Export {
+        repo,
+        exported_at: (!canonical()).then(Utc::now),
+        scrubbed,
+        issues: issues
+            .iter()
+            .map(|(issue, comments)| ExportedIssue {
+                issue: IssueJson::new(issue),
+                comment_list: comments.iter().map(CommentJson::new).collect(),
+            })
+            .collect(),
     };
 
-    serde_json::to_string_pretty(&detail).unwrap_or_else(|_| "{}".to_string())
+    to_json(&export).unwrap_or_else(|_| "{}".to_string())
 }
 
 pub fn format_repo_list(repos: &[Repository], format: &OutputFormat) -> String {
@@ -175,26 +576,1825 @@ fn format_repos_human(repos: &[Repository]) -> String {
 }
 
 fn format_repos_json(repos: &[Repository]) -> String {
-    serde_json::to_string_pretty(repos).unwrap_or_else(|_| "[]".to_string())
+    to_json(repos).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// First 12 characters of a commit SHA, as shown in lists.
+fn short_sha(sha: &str) -> &str {
+    sha.get(..12).unwrap_or(sha)
+}
+
+pub fn format_branch_list(repo: &str, branches: &[Branch], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            if branches.is_empty() {
+                return format!("{} has no branches.\n", repo);
+            }
+            let width = branches.iter().map(|b| b.name.len()).max().unwrap_or(0);
+            let mut output = format!("{} branch(es) in {}:\n\n", branches.len(), repo);
+            for branch in branches {
+                let subject = branch.commit.message.lines().next().unwrap_or_default();
+                output.push_str(&format!(
+                    "  {:<width$}  {}  {}\n",
+                    branch.name,
+                    short_sha(&branch.commit.id),
+                    subject,
+                    width = width
+                ));
+            }
+            output
+        }
+        OutputFormat::Json => to_json(branches).unwrap_or_else(|_| "[]".to_string()),
+    }
 }
 
-pub fn format_created_issue(issue: &Issue, format: &OutputFormat) -> String {
+pub fn format_quarantine(entries: &[Quarantined], format: &OutputFormat) -> String {
     match format {
-        OutputFormat::Human => format!("Created issue #{}: {}\nURL: {}\n", issue.number, issue.title, issue.html_url),
-        OutputFormat::Json => serde_json::to_string_pretty(issue).unwrap_or_else(|_| "{}".to_string()),
+        OutputFormat::Human => {
+            if entries.is_empty() {
+                return "No repositories are failing or quarantined.\n".to_string();
+            }
+
+            let mut output = String::new();
+            for entry in entries {
+                let state = match entry.until {
+                    Some(until) if entry.is_active() => format!("quarantined, next try {}", format_timestamp(&until)),
+                    Some(_) => "quarantine over, tried on the next run".to_string(),
+                    None => "not quarantined yet".to_string(),
+                };
+                output.push_str(&format!(
+                    "{}  {} failure(s) in a row, {}\n  last failed {}: {}\n",
+                    entry.repo,
+                    entry.failures,
+                    state,
+                    format_timestamp(&entry.last_failed),
+                    entry.last_error
+                ));
+            }
+            output
+        }
+        OutputFormat::Json => to_json(entries).unwrap_or_else(|_| "[]".to_string()),
     }
 }
 
-pub fn format_created_comment(comment: &Comment, format: &OutputFormat) -> String {
+pub fn format_quarantine_cleared(repo: Option<&str>, removed: usize, format: &OutputFormat) -> String {
     match format {
-        OutputFormat::Human => format!("Comment added by @{} at {}\n", comment.user.username, comment.created_at),
-        OutputFormat::Json => serde_json::to_string_pretty(comment).unwrap_or_else(|_| "{}".to_string()),
+        OutputFormat::Human => match (repo, removed) {
+            (Some(repo), 0) => format!("{} was not quarantined.\n", repo),
+            (Some(repo), _) => format!("Released {}; the next run tries it again.\n", repo),
+            (None, n) => format!("Released {} repo(s).\n", n),
+        },
+        OutputFormat::Json => to_json(&serde_json::json!({ "repo": repo, "released": removed }))
+            .unwrap_or_else(|_| "{}".to_string()),
     }
 }
 
-pub fn format_issue_updated(issue: &Issue, action: &str, format: &OutputFormat) -> String {
+/// A file's entry from the contents API; the human form leaves out the content.
+pub fn format_file_contents(file: &FileContents, format: &OutputFormat) -> String {
     match format {
-        OutputFormat::Human => format!("Issue #{} {}: {}\n", issue.number, action, issue.title),
-        OutputFormat::Json => serde_json::to_string_pretty(issue).unwrap_or_else(|_| "{}".to_string()),
+        OutputFormat::Human => format!("{}  {} bytes  {}\n", file.path, file.size, file.sha),
+        OutputFormat::Json => to_json(file).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+/// `action` is "created", "updated" or "unchanged" (with no commit).
+pub fn format_file_put(path: &str, action: &str, commit: Option<&FileCommit>, format: &OutputFormat) -> String {
+    let sha = commit.and_then(|c| c.commit.as_ref()).map(|c| c.sha.as_str());
+    match format {
+        OutputFormat::Human => match (action, sha) {
+            ("unchanged", _) => format!("{} already has these contents; nothing committed\n", path),
+            (action, Some(sha)) => format!("{} {} in commit {}\n", path, action, short_sha(sha)),
+            (action, None) => format!("{} {}\n", path, action),
+        },
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "path": path,
+            "action": action,
+            "commit": commit.and_then(|c| c.commit.as_ref()),
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+/// Report an edited or deleted comment; `action` is "edited" or "deleted".
+pub fn format_comment_change(repo: &str, number: i64, comment: &Comment, action: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format!(
+            "Comment {} by @{} on {}#{} {}\n",
+            comment.id, comment.user.username, repo, number, action
+        ),
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "repo": repo,
+            "number": number,
+            "comment_id": comment.id,
+            "author": comment.user.username,
+            "action": action,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_stream_result(repo: &str, number: i64, comment: &Comment, lines: usize, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = format!("Streamed {} line(s) to {}#{}\n", lines, repo, number);
+            if let Some(url) = comment.html_url.as_deref().filter(|url| !url.is_empty()) {
+                output.push_str(&field("URL", url));
+            }
+            output
+        }
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "repo": repo,
+            "number": number,
+            "comment_id": comment.id,
+            "html_url": comment.html_url,
+            "lines": lines,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_capture(repo: &str, issue: &Issue, action: &str, status: i32, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => match action {
+            "updated" => format!("Failure (exit status {}) added to {}#{}: {}\n", status, repo, issue.number, issue.html_url),
+            _ => format!("Filed {}#{} for the failure (exit status {}): {}\n", repo, issue.number, status, issue.html_url),
+        },
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "repo": repo,
+            "number": issue.number,
+            "html_url": issue.html_url,
+            "action": action,
+            "status": status,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_branch(branch: &Branch, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let commit = &branch.commit;
+            let mut output = format!("{}\n", branch.name);
+            output.push_str(&field("Commit", &commit.id));
+            if let Some(author) = &commit.author {
+                output.push_str(&field("Author", format!("{} <{}>", author.name, author.email)));
+            }
+            if let Some(committer) = &commit.committer {
+                output.push_str(&field("Committer", format!("{} <{}>", committer.name, committer.email)));
+            }
+            if let Some(timestamp) = &commit.timestamp {
+                output.push_str(&field("Date", format_timestamp(timestamp)));
+            }
+            if !commit.message.trim().is_empty() {
+                output.push_str(&format!("\n{}\n", commit.message.trim_end()));
+            }
+            output
+        }
+        OutputFormat::Json => to_json(branch).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_org_list(orgs: &[Organization], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            if orgs.is_empty() {
+                return "You don't belong to any organizations.\n".to_string();
+            }
+            let mut output = format!("Found {} organization(s):\n\n", orgs.len());
+            for org in orgs {
+                output.push_str(&format!("  {}\n", org.username));
+                if let Some(desc) = org.description.as_deref().filter(|d| !d.is_empty()) {
+                    output.push_str(&format!("    {}\n", desc));
+                }
+            }
+            output
+        }
+        OutputFormat::Json => to_json(orgs).unwrap_or_else(|_| "[]".to_string()),
+    }
+}
+
+pub fn format_org(org: &Organization, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = match org.full_name.as_deref().filter(|n| !n.is_empty()) {
+                Some(full_name) => format!("{} ({})\n", org.username, full_name),
+                None => format!("{}\n", org.username),
+            };
+            if let Some(desc) = org.description.as_deref().filter(|d| !d.is_empty()) {
+                output.push_str(&format!("{}\n", desc));
+            }
+            if let Some(website) = org.website.as_deref().filter(|w| !w.is_empty()) {
+                output.push_str(&field("Website", website));
+            }
+            output
+        }
+        OutputFormat::Json => to_json(org).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_created_org(org: &Organization, owner: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = format!("Created organization {} (owner {})\n", org.username, owner);
+            if let Some(desc) = org.description.as_deref().filter(|d| !d.is_empty()) {
+                output.push_str(&field("Description", desc));
+            }
+            output
+        }
+        OutputFormat::Json => to_json(org).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+/// A unified diff of an issue body being replaced, for review before it is.
+pub fn format_body_diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let mut output = String::new();
+    for line in difflib::unified_diff(&old, &new, "current", "new", "", "", 3) {
+        // Header lines carry their own newline, with a tab before the empty date
+        let line = line.trim_end_matches('\n').trim_end_matches('\t');
+        let color = match line.chars().next() {
+            _ if line.starts_with("---") || line.starts_with("+++") => None,
+            Some('-') => Some(31),
+            Some('+') => Some(32),
+            Some('@') => Some(36),
+            _ => None,
+        };
+        match color {
+            Some(code) if color_enabled() => output.push_str(&format!("\x1b[{}m{}\x1b[0m\n", code, line)),
+            _ => output.push_str(&format!("{}\n", line)),
+        }
+    }
+    output
+}
+
+/// The commands `gog foreach --dry-run` would run, per repository.
+pub fn format_foreach_plan(plan: &[(String, Vec<String>)], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = String::new();
+            for (repo, args) in plan {
+                output.push_str(&format!("{}: gog {}\n", repo, args.join(" ")));
+            }
+            output.push_str(&format!("\n{} repo(s) would be covered\n", plan.len()));
+            output
+        }
+        OutputFormat::Json => {
+            let plan: Vec<_> = plan
+                .iter()
+                .map(|(repo, args)| serde_json::json!({ "repo": repo, "args": args }))
+                .collect();
+            to_json(&plan).unwrap_or_else(|_| "[]".to_string())
+        }
+    }
+}
+
+/// Each repository's run of a `gog foreach` command, then a summary.
+pub fn format_foreach_results(runs: &[Run], format: &OutputFormat) -> String {
+    let skipped = runs.iter().filter(|run| run.skipped).count();
+    let succeeded = runs.iter().filter(|run| run.ok()).count();
+    let failed = runs.len() - succeeded - skipped;
+
+    match format {
+        OutputFormat::Human => {
+            let mut output = String::new();
+            for run in runs {
+                let outcome = match run.status {
+                    _ if run.skipped => "skipped (interrupted)".to_string(),
+                    Some(0) => "ok".to_string(),
+                    Some(status) => format!("failed (exit {})", status),
+                    None => "failed".to_string(),
+                };
+                output.push_str(&format!("== {}: {}\n", run.repo, outcome));
+                // A failed run's errors matter more than whatever it printed first
+                let text = if run.ok() { &run.stdout } else { &run.stderr };
+                for line in text.lines() {
+                    if line.is_empty() {
+                        output.push('\n');
+                    } else {
+                        output.push_str(&format!("  {}\n", line));
+                    }
+                }
+            }
+            output.push_str(&format!("\nforeach: {} succeeded, {} failed", succeeded, failed));
+            if skipped > 0 {
+                output.push_str(&format!(", {} not attempted", skipped));
+            }
+            output.push('\n');
+            output
+        }
+        OutputFormat::Json => {
+            let results: Vec<_> = runs
+                .iter()
+                .map(|run| {
+                    // Runs get --json too, so their output is usually JSON itself
+                    let output = serde_json::from_str::<serde_json::Value>(&run.stdout)
+                        .unwrap_or_else(|_| serde_json::Value::String(run.stdout.clone()));
+                    serde_json::json!({
+                        "repo": run.repo,
+                        "args": run.args,
+                        "ok": run.ok(),
+                        "status": run.status,
+                        "skipped": run.skipped,
+                        "output": output,
+                        "stderr": run.stderr,
+                    })
+                })
+                .collect();
+            to_json(&serde_json::json!({
+                "succeeded": succeeded,
+                "failed": failed,
+                "skipped": skipped,
+                "results": results,
+            }))
+            .unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+/// Report who the token belongs to. `profile` is None for an ad hoc profile
+/// made from --token; `configured_user` is the profile's gogs_user, if set.
+/// `token` says where the token came from: "--token", "keyring", "token_cmd" or "profile".
+pub fn format_whoami(
+    user: &User,
+    profile: Option<&str>,
+    configured_user: &str,
+    server: &str,
+    token: &str,
+    format: &OutputFormat,
+) -> String {
+    let mismatch = !configured_user.is_empty() && configured_user != user.username;
+    match format {
+        OutputFormat::Human => {
+            let mut output = match user.full_name.as_deref().filter(|n| !n.is_empty()) {
+                Some(full_name) => format!("{} ({})\n", user.username, full_name),
+                None => format!("{}\n", user.username),
+            };
+            if let Some(email) = user.email.as_deref().filter(|e| !e.is_empty()) {
+                output.push_str(&format!("  Email:   {}\n", email));
+            }
+            output.push_str(&format!("  Profile: {}\n", profile.unwrap_or("(none)")));
+            output.push_str(&format!("  Server:  {}\n", server));
+            output.push_str(&format!("  Token:   from {}\n", token));
+            if mismatch {
+                output.push_str(&format!(
+                    "\nWarning: profile '{}' is configured for {}, but its token belongs to {}\n",
+                    profile.unwrap_or("default"),
+                    configured_user,
+                    user.username
+                ));
+            }
+            output
+        }
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "username": user.username,
+            "full_name": user.full_name,
+            "email": user.email,
+            "id": user.id,
+            "profile": profile,
+            "profile_user": (!configured_user.is_empty()).then_some(configured_user),
+            "user_mismatch": mismatch,
+            "server": server,
+            "token": token,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_token_created(user: &str, token: &AccessToken, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format!(
+            "Created token '{}' for {}:\n  {}\n\nKeep it somewhere safe; it grants API access as {}.\n",
+            token.name, user, token.sha1, user
+        ),
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "user": user,
+            "name": token.name,
+            "token": token.sha1,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+/// A config setting: text as is, sections as TOML, anything else as a TOML value.
+pub fn format_config_value(key: &str, value: &toml::Value, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => match value {
+            toml::Value::String(text) => format!("{}\n", text),
+            toml::Value::Table(_) => toml::to_string_pretty(value).unwrap_or_default(),
+            _ => format!("{}\n", value),
+        },
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "key": key,
+            "value": value,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_config_change(key: &str, value: &toml::Value, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format!("Set {} = {}\n", key, value),
+        OutputFormat::Json => format_config_value(key, value, format),
+    }
+}
+
+/// Report the profiles encrypted with `protection` (e.g. "a passphrase"), or
+/// stored in plain text again when None.
+pub fn format_config_encryption(profiles: usize, protection: Option<&str>, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => match protection {
+            Some(protection) => format!("Encrypted {} profile(s) with {}.\n", profiles, protection),
+            None => format!("Stored {} profile(s) unencrypted.\n", profiles),
+        },
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "profiles": profiles,
+            "encrypted": protection.is_some(),
+            "protection": protection,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+/// Report a new account, with the API token made for it if one was.
+pub fn format_created_user(user: &User, token: Option<&AccessToken>, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = format!("Created user {} (ID {})\n", user.username, user.id);
+            if let Some(email) = user.email.as_deref().filter(|e| !e.is_empty()) {
+                output.push_str(&format!("  Email: {}\n", email));
+            }
+            if let Some(token) = token {
+                output.push_str(&format!(
+                    "\nCreated token '{}' for {}:\n  {}\n\nKeep it somewhere safe; it grants API access as {}.\n",
+                    token.name, user.username, token.sha1, user.username
+                ));
+            }
+            output
+        }
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "user": user,
+            "token": token.map(|token| serde_json::json!({ "name": token.name, "token": token.sha1 })),
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_deleted_user(username: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format!("Deleted user {}\n", username),
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "user": username,
+            "deleted": true,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+/// List tokens by name. Gogs returns the tokens themselves too, but only
+/// enough of each is shown to tell them apart.
+pub fn format_token_list(user: &str, tokens: &[AccessToken], format: &OutputFormat) -> String {
+    let prefix = |token: &AccessToken| format!("{}...", token.sha1.chars().take(6).collect::<String>());
+    match format {
+        OutputFormat::Human => {
+            if tokens.is_empty() {
+                return format!("{} has no API tokens.\n", user);
+            }
+            let mut output = String::new();
+            for token in tokens {
+                output.push_str(&format!("  {:<10} {}\n", prefix(token), token.name));
+            }
+            output.push_str(&format!("\nTotal: {} token(s)\n", tokens.len()));
+            output
+        }
+        OutputFormat::Json => {
+            let tokens: Vec<_> = tokens
+                .iter()
+                .map(|token| serde_json::json!({ "name": token.name, "prefix": prefix(token) }))
+                .collect();
+            to_json(&tokens).unwrap_or_else(|_| "[]".to_string())
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct KeyJson<'a> {
+    #[serde(flatten)]
+    key: &'a PublicKey,
+    fingerprint: Option<String>,
+}
+
+impl<'a> KeyJson<'a> {
+    fn new(key: &'a PublicKey) -> Self {
+        Self {
+            key,
+            fingerprint: key.fingerprint(),
+        }
+    }
+}
+
+pub fn format_key_list(keys: &[PublicKey], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            if keys.is_empty() {
+                return "No SSH keys registered.\n".to_string();
+            }
+            let mut output = String::new();
+            for key in keys {
+                output.push_str(&format!(
+                    "  {:<6} {} {}\n",
+                    key.id,
+                    key.fingerprint().unwrap_or_else(|| "(unreadable key)".to_string()),
+                    key.title
+                ));
+            }
+            output.push_str(&format!("\nTotal: {} key(s)\n", keys.len()));
+            output
+        }
+        OutputFormat::Json => {
+            let keys: Vec<KeyJson> = keys.iter().map(KeyJson::new).collect();
+            to_json(&keys).unwrap_or_else(|_| "[]".to_string())
+        }
+    }
+}
+
+/// Report an SSH key that was added, deleted or found already registered.
+pub fn format_key_change(key: &PublicKey, action: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format!(
+            "SSH key {} '{}' {} ({})\n",
+            key.id,
+            key.title,
+            action,
+            key.fingerprint().unwrap_or_default()
+        ),
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "action": action,
+            "key": KeyJson::new(key),
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_user(user: &User, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = match user.full_name.as_deref().filter(|n| !n.is_empty()) {
+                Some(full_name) => format!("{} ({})\n", user.username, full_name),
+                None => format!("{}\n", user.username),
+            };
+            if let Some(email) = user.email.as_deref().filter(|e| !e.is_empty()) {
+                output.push_str(&format!("  Email: {}\n", email));
+            }
+            output.push_str(&format!("  ID:    {}\n", user.id));
+            output
+        }
+        OutputFormat::Json => to_json(user).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_user_list(users: &[User], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            if users.is_empty() {
+                return "No users found.\n".to_string();
+            }
+            let mut output = format!("Found {} user(s):\n\n", users.len());
+            for user in users {
+                match user.full_name.as_deref().filter(|n| !n.is_empty()) {
+                    Some(full_name) => output.push_str(&format!("  {} ({})\n", user.username, full_name)),
+                    None => output.push_str(&format!("  {}\n", user.username)),
+                }
+            }
+            output
+        }
+        OutputFormat::Json => to_json(users).unwrap_or_else(|_| "[]".to_string()),
+    }
+}
+
+pub fn format_watch_change(repo: &str, watching: bool, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human if watching => format!("Watching {}\n", repo),
+        OutputFormat::Human => format!("No longer watching {}\n", repo),
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "repo": repo,
+            "watching": watching,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_star_change(repo: &str, starred: bool, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human if starred => format!("Starred {}\n", repo),
+        OutputFormat::Human => format!("Unstarred {}\n", repo),
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "repo": repo,
+            "starred": starred,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_collaborators(repo: &str, collaborators: &[Collaborator], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            if collaborators.is_empty() {
+                return format!("{} has no collaborators.\n", repo);
+            }
+            let mut output = format!("{} collaborator(s) on {}:\n\n", collaborators.len(), repo);
+            for c in collaborators {
+                let access = if c.permissions.admin {
+                    "admin"
+                } else if c.permissions.push {
+                    "write"
+                } else {
+                    "read"
+                };
+                output.push_str(&format!("  {:<20} {}\n", c.user.username, access));
+            }
+            output
+        }
+        OutputFormat::Json => to_json(collaborators).unwrap_or_else(|_| "[]".to_string()),
+    }
+}
+
+/// `permission` is what was granted, or None when access was removed.
+pub fn format_collaborator_change(
+    repo: &str,
+    user: &str,
+    permission: Option<CollaboratorPermission>,
+    format: &OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Human => match permission {
+            Some(permission) => format!("Gave {} {} access to {}\n", user, permission, repo),
+            None => format!("Removed {} from {}\n", user, repo),
+        },
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "repo": repo,
+            "user": user,
+            "permission": permission,
+            "removed": permission.is_none(),
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_repo_detail(repo: &Repository, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format_repo_detail_human(repo),
+        OutputFormat::Json => to_json(repo).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+fn format_repo_detail_human(repo: &Repository) -> String {
+    let visibility = if repo.private { "[private]" } else { "[public]" };
+    let mut output = format!("{} {}\n", repo.full_name, visibility);
+    if let Some(desc) = repo.description.as_deref().filter(|d| !d.is_empty()) {
+        output.push_str(&format!("{}\n", desc));
+    }
+    output.push('\n');
+
+    if let Some(website) = repo.website.as_deref().filter(|w| !w.is_empty()) {
+        output.push_str(&field("Website", website));
+    }
+    if repo.empty {
+        output.push_str(&field("Default branch", "(empty repository)"));
+    } else if !repo.default_branch.is_empty() {
+        output.push_str(&field("Default branch", &repo.default_branch));
+    }
+    output.push_str(&field("Size", format_size(repo.size)));
+    match &repo.parent {
+        Some(parent) => output.push_str(&field("Fork of", &parent.full_name)),
+        None if repo.fork => output.push_str(&field("Fork", "yes")),
+        None => {}
+    }
+    if repo.mirror {
+        output.push_str(&field("Mirror", "yes"));
+    }
+    output.push_str(&format!(
+        "{}: {}  {}: {}  {}: {}  {}: {}\n",
+        caption("Stars"),
+        repo.stars_count,
+        caption("Watchers"),
+        repo.watchers_count,
+        caption("Forks"),
+        repo.forks_count,
+        caption("Open issues"),
+        repo.open_issues_count
+    ));
+    if let Some(p) = &repo.permissions {
+        let granted: Vec<&str> = [(p.admin, "admin"), (p.push, "push"), (p.pull, "pull")]
+            .into_iter()
+            .filter_map(|(granted, name)| granted.then_some(name))
+            .collect();
+        let granted = if granted.is_empty() { "none".to_string() } else { granted.join(", ") };
+        output.push_str(&field("Your permissions", granted));
+    }
+    if let Some(created) = &repo.created_at {
+        output.push_str(&field("Created", format_timestamp(created)));
+    }
+    if let Some(updated) = &repo.updated_at {
+        output.push_str(&field("Updated", format_timestamp(updated)));
+    }
+
+    output.push('\n');
+    output.push_str(&format_repo_urls(repo));
+    output
+}
+
+/// One line of download progress, e.g. "owner/project@main: 3.2 MiB of 10.0 MiB".
+pub fn format_download_progress(label: &str, written: u64, total: Option<u64>) -> String {
+    let kib = |bytes: u64| bytes.div_ceil(1024) as i64;
+    match total {
+        Some(total) => format!("{}: {} of {}", label, format_size(kib(written)), format_size(kib(total))),
+        None => format!("{}: {}", label, format_size(kib(written))),
+    }
+}
+
+pub fn format_archive(repo: &str, git_ref: &str, path: &str, size: u64, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format!(
+            "Saved {} at {} to {} ({})\n",
+            repo,
+            git_ref,
+            path,
+            format_size(size.div_ceil(1024) as i64)
+        ),
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "repo": repo,
+            "ref": git_ref,
+            "path": path,
+            "size": size,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+/// A size given in KiB, in the largest unit that keeps it above 1.
+fn format_size(kib: i64) -> String {
+    match kib {
+        kib if kib >= 1024 * 1024 => format!("{:.1} GiB", kib as f64 / (1024.0 * 1024.0)),
+        kib if kib >= 1024 => format!("{:.1} MiB", kib as f64 / 1024.0),
+        kib => format!("{} KiB", kib),
+    }
+}
+
+pub fn format_created_repo(repo: &Repository, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let visibility = if repo.private { "[private]" } else { "[public]" };
+            let mut output = format!("Created repository {} {}\n", repo.full_name, visibility);
+            output.push_str(&format_repo_urls(repo));
+            output
+        }
+        OutputFormat::Json => to_json(repo).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_edited_repo(repo: &Repository, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let visibility = if repo.private { "private" } else { "public" };
+            let mut output = format!("Updated repository {}\n", repo.full_name);
+            output.push_str(&field(
+                "Description",
+                repo.description.as_deref().filter(|d| !d.is_empty()).unwrap_or("(none)"),
+            ));
+            output.push_str(&field(
+                "Website",
+                repo.website.as_deref().filter(|w| !w.is_empty()).unwrap_or("(none)"),
+            ));
+            if !repo.default_branch.is_empty() {
+                output.push_str(&field("Default branch", &repo.default_branch));
+            }
+            output.push_str(&field("Visibility", visibility));
+            output
+        }
+        OutputFormat::Json => to_json(repo).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_forked_repo(fork: &Repository, upstream: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = format!("Forked {} to {}\n", upstream, fork.full_name);
+            output.push_str(&format_repo_urls(fork));
+            output
+        }
+        OutputFormat::Json => to_json(fork).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_forks(repo: &str, forks: &[Fork], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            if forks.is_empty() {
+                return format!("{} has no forks.\n", repo);
+            }
+            let mut output = format!("{} fork(s) of {}:\n\n", forks.len(), repo);
+            for fork in forks {
+                let indent = "  ".repeat(fork.depth);
+                match &fork.repo.updated_at {
+                    Some(updated) => output.push_str(&format!(
+                        "{}{} (updated {})\n",
+                        indent,
+                        fork.repo.full_name,
+                        format_timestamp(updated)
+                    )),
+                    None => output.push_str(&format!("{}{}\n", indent, fork.repo.full_name)),
+                }
+            }
+            output
+        }
+        OutputFormat::Json => to_json(forks).unwrap_or_else(|_| "[]".to_string()),
+    }
+}
+
+/// `via` lists the forks between `repo` and `parent`, nearest first, for `--root`.
+pub fn format_parent(repo: &str, parent: &Repository, via: &[String], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = format!("{} is a fork of {}\n", repo, parent.full_name);
+            if !via.is_empty() {
+                output.push_str(&format!("  via {}\n", via.join(" <- ")));
+            }
+            output.push_str(&format_repo_urls(parent));
+            output
+        }
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "repo": repo,
+            "parent": parent,
+            "via": via,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_migrated_repo(repo: &Repository, source: &str, mirror: bool, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let kind = if mirror { "Mirrored" } else { "Imported" };
+            let mut output = format!("{} {} as {}\n", kind, source, repo.full_name);
+            output.push_str(&format_repo_urls(repo));
+            output
+        }
+        OutputFormat::Json => to_json(repo).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+fn format_repo_urls(repo: &Repository) -> String {
+    let mut output = field("URL", &repo.html_url);
+    output.push_str(&field("Clone (HTTPS)", &repo.clone_url));
+    if !repo.ssh_url.is_empty() {
+        output.push_str(&field("Clone (SSH)", &repo.ssh_url));
+    }
+    output
+}
+
+pub fn format_deleted_repo(full_name: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format!("Deleted repository {}\n", full_name),
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "repo": full_name,
+            "deleted": true,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+/// Report a new issue, plus the outcome of each `--attach` upload as
+/// `uploaded` attachments and `(file, error)` failures.
+pub fn format_created_issue(
+    issue: &Issue,
+    uploaded: &[Attachment],
+    failed: &[(String, String)],
+    format: &OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = format!("Created issue #{}: {}\n", issue.number, issue.title);
+            if let Some(assignee) = &issue.assignee {
+                output.push_str(&format!("Assigned to: {}\n", assignee.username));
+            }
+            output.push_str(&format!("URL: {}\n", issue.html_url));
+            for attachment in uploaded {
+                output.push_str(&format!("Attached: {}\n", attachment.name));
+            }
+            for (file, error) in failed {
+                output.push_str(&format!("Failed to attach {}: {}\n", file, error));
+            }
+            if !failed.is_empty() {
+                output.push_str(&format!(
+                    "Attachments: {} uploaded, {} failed\n",
+                    uploaded.len(),
+                    failed.len()
+                ));
+            }
+            output
+        }
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct AttachmentError<'a> {
+                file: &'a str,
+                error: &'a str,
+            }
+
+            #[derive(serde::Serialize)]
+            struct CreatedIssue<'a> {
+                #[serde(flatten)]
+                issue: IssueJson<'a>,
+                #[serde(skip_serializing_if = "<[_]>::is_empty")]
+                attachments: &'a [Attachment],
+                #[serde(skip_serializing_if = "Vec::is_empty")]
+                attachment_errors: Vec<AttachmentError<'a>>,
+            }
+
+            let result = CreatedIssue {
+                issue: IssueJson::new(issue),
+                attachments: uploaded,
+                attachment_errors: failed
+                    .iter()
+                    .map(|(file, error)| AttachmentError { file, error })
+                    .collect(),
+            };
+            to_json(&result).unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+pub fn format_release_upload(
+    tag: &str,
+    uploaded: &[Attachment],
+    failed: &[(String, String)],
+    format: &OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = String::new();
+            for asset in uploaded {
+                output.push_str(&format!(
+                    "Uploaded {} ({}) to {}\n  {}\n",
+                    asset.name,
+                    format_size((asset.size + 1023) / 1024),
+                    tag,
+                    asset.browser_download_url
+                ));
+            }
+            for (file, error) in failed {
+                output.push_str(&format!("Failed to upload {}: {}\n", file, error));
+            }
+            output
+        }
+        OutputFormat::Json => {
+            let failed: Vec<_> = failed
+                .iter()
+                .map(|(file, error)| serde_json::json!({ "file": file, "error": error }))
+                .collect();
+            to_json(&serde_json::json!({
+                "tag": tag,
+                "uploaded": uploaded,
+                "failed": failed,
+            }))
+            .unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+/// `downloaded` holds (asset name, path written, bytes).
+pub fn format_release_download(tag: &str, downloaded: &[(String, String, u64)], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = format!("Downloaded {} asset(s) of {}:\n", downloaded.len(), tag);
+            for (_, path, size) in downloaded {
+                output.push_str(&format!("  {} ({})\n", path, format_size(size.div_ceil(1024) as i64)));
+            }
+            output
+        }
+        OutputFormat::Json => {
+            let assets: Vec<_> = downloaded
+                .iter()
+                .map(|(name, path, size)| serde_json::json!({ "name": name, "path": path, "size": size }))
+                .collect();
+            to_json(&serde_json::json!({ "tag": tag, "downloaded": assets }))
+                .unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+/// Report a new comment along with any follow-up actions taken on its issue.
+pub fn format_vote(repo: &str, number: i64, voted: bool, votes: usize, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human if voted => format!("Voted for {}#{} ({} vote(s))\n", repo, number, votes),
+        OutputFormat::Human => format!("Already voted for {}#{} ({} vote(s))\n", repo, number, votes),
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "repo": repo,
+            "number": number,
+            "voted": voted,
+            "votes": votes,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_verification(
+    repo: &str,
+    number: i64,
+    results: &[(Comment, Provenance)],
+    format: &OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Human => {
+            if results.is_empty() {
+                return format!("No comments on {}#{}.\n", repo, number);
+            }
+
+            let mut output = format!("Comments on {}#{}:\n\n", repo, number);
+            for (comment, provenance) in results {
+                let status = match provenance {
+                    Provenance::Verified(signer) => format!("verified ({})", signer),
+                    Provenance::Unsigned => "unsigned".to_string(),
+                    Provenance::Invalid => "INVALID signature".to_string(),
+                };
+                output.push_str(&format!(
+                    "  {:<10} @{} ({}): {}\n",
+                    comment.id,
+                    comment.user.username,
+                    format_timestamp(&comment.created_at),
+                    status
+                ));
+            }
+
+            let count = |wanted: fn(&Provenance) -> bool| results.iter().filter(|(_, p)| wanted(p)).count();
+            output.push_str(&format!(
+                "\n{} verified, {} unsigned, {} invalid\n",
+                count(|p| matches!(p, Provenance::Verified(_))),
+                count(|p| *p == Provenance::Unsigned),
+                count(|p| *p == Provenance::Invalid)
+            ));
+            output
+        }
+        OutputFormat::Json => {
+            let entries: Vec<_> = results
+                .iter()
+                .map(|(comment, provenance)| {
+                    let mut entry = serde_json::json!({
+                        "id": comment.id,
+                        "user": comment.user.username,
+                        "created_at": comment.created_at,
+                    });
+                    if let (Some(entry), Ok(serde_json::Value::Object(fields))) =
+                        (entry.as_object_mut(), serde_json::to_value(provenance))
+                    {
+                        entry.extend(fields);
+                    }
+                    entry
+                })
+                .collect();
+            to_json(&serde_json::json!({
+                "repo": repo,
+                "number": number,
+                "comments": entries,
+            }))
+            .unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+pub fn format_open_items(user: &str, items: &[OpenItem], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            if items.is_empty() {
+                return format!("Nothing open is assigned to or mentions {}.\n", user);
+            }
+
+            let mut output = format!("{} open item(s) for {}:\n\n", items.len(), user);
+            for item in items {
+                let reasons: Vec<&str> = item.reasons.iter().map(|r| r.as_str()).collect();
+                output.push_str(&format!(
+                    "  [{:<5}] {}#{}  {}\n          {}, updated {}\n",
+                    item.kind.marker(),
+                    item.repo,
+                    item.number,
+                    item.title,
+                    reasons.join(", "),
+                    format_timestamp(&item.updated_at)
+                ));
+            }
+            output
+        }
+        OutputFormat::Json => to_json(items).unwrap_or_else(|_| "[]".to_string()),
+    }
+}
+
+/// `ranked` is already cut to the requested limit; `total` is how many open
+/// issues were ranked.
+pub fn format_top(ranked: &[RankedIssue], total: usize, half_life: f64, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            if ranked.is_empty() {
+                return "No open issues found.\n".to_string();
+            }
+
+            let mut output = format!(
+                "Top {} of {} open issue(s) by votes, then activity (half-life {} days):\n\n",
+                ranked.len(),
+                total,
+                half_life
+            );
+            output.push_str("  Rank  Score  Votes  Updated    Issue\n");
+            for (rank, entry) in ranked.iter().enumerate() {
+                output.push_str(&format!(
+                    "  {:>4}  {:>5.2}  {:>5}  {:<9}  {}#{} {}\n",
+                    rank + 1,
+                    entry.score,
+                    entry.votes,
+                    format_timestamp(&entry.issue.updated_at),
+                    entry.repo,
+                    entry.issue.number,
+                    entry.issue.title
+                ));
+            }
+            output
+        }
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct Ranked<'a> {
+                rank: usize,
+                #[serde(flatten)]
+                entry: &'a RankedIssue,
+            }
+            let ranked: Vec<Ranked> = ranked
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| Ranked { rank: i + 1, entry })
+                .collect();
+            to_json(&ranked).unwrap_or_else(|_| "[]".to_string())
+        }
+    }
+}
+
+pub fn format_created_comment(
+    repo: &str,
+    number: i64,
+    comment: &Comment,
+    closed: Option<&Issue>,
+    labels_added: &[String],
+    format: &OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = format!(
+                "Comment added by @{} ({})\n",
+                comment.user.username,
+                format_timestamp(&comment.created_at)
+            );
+            if let Some(issue) = closed {
+                output.push_str(&format!("Issue #{} closed: {}\n", issue.number, issue.title));
+            }
+            for label in labels_added {
+                output.push_str(&format!("Label '{}' added to issue #{}\n", label, number));
+            }
+            output
+        }
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct CommentResult<'a> {
+                id: i64,
+                repo: &'a str,
+                issue: i64,
+                html_url: Option<&'a str>,
+                comment: CommentJson<'a>,
+                closed: bool,
+                labels_added: &'a [String],
+            }
+
+            let result = CommentResult {
+                id: comment.id,
+                repo,
+                issue: number,
+                html_url: comment.html_url.as_deref(),
+                comment: CommentJson::new(comment),
+                closed: closed.is_some(),
+                labels_added,
+            };
+            to_json(&result).unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+pub fn format_issue_updated(issue: &Issue, action: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format!("Issue #{} {}: {}\n", issue.number, action, issue.title),
+        OutputFormat::Json => to_json(&IssueJson::new(issue)).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+/// Report the outcome of `issue bulk`, one `(repo, number, result)` per issue.
+pub fn format_bulk_results(
+    action: &str,
+    results: &[(String, i64, anyhow::Result<()>)],
+    format: &OutputFormat,
+) -> String {
+    // Operations an interrupt stopped before they began
+    let is_skipped = |result: &anyhow::Result<()>| {
+        result
+            .as_ref()
+            .is_err_and(|e| matches!(e.downcast_ref(), Some(GogsError::Interrupted(_))))
+    };
+    let skipped = results.iter().filter(|(_, _, r)| is_skipped(r)).count();
+    let failed = results.iter().filter(|(_, _, r)| r.is_err()).count() - skipped;
+    let succeeded = results.len() - failed - skipped;
+
+    match format {
+        OutputFormat::Human => {
+            let mut output = String::new();
+            for (repo, number, result) in results {
+                match result {
+                    Ok(()) => output.push_str(&format!("{}#{}: ok\n", repo, number)),
+                    Err(_) if is_skipped(result) => output.push_str(&format!("{}#{}: skipped (interrupted)\n", repo, number)),
+                    Err(e) => output.push_str(&format!("{}#{}: failed: {:#}\n", repo, number, e)),
+                }
+            }
+            output.push_str(&format!("{}: {} succeeded, {} failed", action, succeeded, failed));
+            if skipped > 0 {
+                output.push_str(&format!(", {} not attempted", skipped));
+            }
+            output.push('\n');
+            output
+        }
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct ItemResult<'a> {
+                repo: &'a str,
+                number: i64,
+                ok: bool,
+                #[serde(skip_serializing_if = "std::ops::Not::not")]
+                skipped: bool,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                error: Option<String>,
+            }
+
+            #[derive(serde::Serialize)]
+            struct BulkResult<'a> {
+                action: &'a str,
+                succeeded: usize,
+                failed: usize,
+                #[serde(skip_serializing_if = "is_zero")]
+                skipped: usize,
+                results: Vec<ItemResult<'a>>,
+            }
+
+            fn is_zero(n: &usize) -> bool {
+                *n == 0
+            }
+
+            let result = BulkResult {
+                action,
+                succeeded,
+                failed,
+                skipped,
+                results: results
+                    .iter()
+                    .map(|(repo, number, result)| ItemResult {
+                        repo,
+                        number: *number,
+                        ok: result.is_ok(),
+                        skipped: is_skipped(result),
+                        error: result.as_ref().err().map(|e| format!("{:#}", e)),
+                    })
+                    .collect(),
+            };
+            to_json(&result).unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+/// Report a close or reopen, with the comment posted alongside it if any.
+pub fn format_state_change(
+    issue: &Issue,
+    action: &str,
+    comment: Option<&Comment>,
+    format: &OutputFormat,
+) -> String {
+    match (format, comment) {
+        (OutputFormat::Human, Some(comment)) => format!(
+            "Comment added by @{} ({})\nIssue #{} {}: {}\n",
+            comment.user.username,
+            format_timestamp(&comment.created_at),
+            issue.number,
+            action,
+            issue.title
+        ),
+        (OutputFormat::Json, Some(comment)) => {
+            #[derive(serde::Serialize)]
+            struct StateChange<'a> {
+                #[serde(flatten)]
+                issue: IssueJson<'a>,
+                comment: CommentJson<'a>,
+            }
+
+            let result = StateChange {
+                issue: IssueJson::new(issue),
+                comment: CommentJson::new(comment),
+            };
+            to_json(&result).unwrap_or_else(|_| "{}".to_string())
+        }
+        (_, None) => format_issue_updated(issue, action, format),
+    }
+}
+
+/// Report a change of ownership (`issue handoff`, `issue unassign-all`).
+pub fn format_ownership_change(
+    issue: &Issue,
+    action: &str,
+    comment: Option<&Comment>,
+    labels_removed: &[String],
+    labels_added: &[String],
+    format: &OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = format!("Issue #{} {}: {}\n", issue.number, action, issue.title);
+            for label in labels_removed {
+                output.push_str(&format!("Label '{}' removed\n", label));
+            }
+            for label in labels_added {
+                output.push_str(&format!("Label '{}' added\n", label));
+            }
+            if comment.is_some() {
+                output.push_str("Handoff comment posted\n");
+            }
+            output
+        }
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct OwnershipChange<'a> {
+                #[serde(flatten)]
+                issue: IssueJson<'a>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                comment: Option<CommentJson<'a>>,
+                labels_removed: &'a [String],
+                labels_added: &'a [String],
+            }
+
+            let result = OwnershipChange {
+                issue: IssueJson::new(issue),
+                comment: comment.map(CommentJson::new),
+                labels_removed,
+                labels_added,
+            };
+            to_json(&result).unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+pub fn format_label_list(labels: &[Label], format: &OutputFormat) -> String {
+    let mut labels = Cow::Borrowed(labels);
+    if canonical() {
+        sort_labels(labels.to_mut());
+    }
+    let labels = &*labels;
+    match format {
+        OutputFormat::Human => {
+            if labels.is_empty() {
+                return "No labels found.\n".to_string();
+            }
+            let mut output = String::new();
+            for label in labels {
+                output.push_str(&format!("  {:<8} {}\n", label.color, label.name));
+            }
+            output.push_str(&format!("\nTotal: {} label(s)\n", labels.len()));
+            output
+        }
+        OutputFormat::Json => to_json(labels).unwrap_or_else(|_| "[]".to_string()),
+    }
+}
+
+/// Report a label that was just created, updated or deleted.
+pub fn format_label(label: &Label, action: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format!("Label '{}' {} ({})\n", label.name, action, label.color),
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct LabelResult<'a> {
+                action: &'a str,
+                #[serde(flatten)]
+                label: &'a Label,
+            }
+
+            to_json(&LabelResult { action, label }).unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+/// Report what `label sync` changed (or would change) in each repository.
+pub fn format_label_sync(
+    results: &[(String, anyhow::Result<Vec<LabelChange>>)],
+    dry_run: bool,
+    format: &OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = String::new();
+            for (repo, result) in results {
+                let changes = match result {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        output.push_str(&format!("{}: failed: {:#}\n", repo, e));
+                        continue;
+                    }
+                };
+                if changes.is_empty() {
+                    output.push_str(&format!("{}: up to date\n", repo));
+                    continue;
+                }
+
+                output.push_str(&format!("{}:\n", repo));
+                for change in changes {
+                    let line = match change {
+                        LabelChange::Create { name, color } => {
+                            format!("create   {} ({})", name, color)
+                        }
+                        LabelChange::Update {
+                            from_name,
+                            from_color,
+                            name,
+                            color,
+                            ..
+                        } => {
+                            let mut parts = Vec::new();
+                            if let Some(name) = name {
+                                parts.push(format!("rename   {} -> {}", from_name, name));
+                            }
+                            if let Some(color) = color {
+                                let label = name.as_ref().unwrap_or(from_name);
+                                parts.push(format!("recolor  {} ({} -> {})", label, from_color, color));
+                            }
+                            parts.join("\n  ")
+                        }
+                    };
+                    output.push_str(&format!("  {}\n", line));
+                }
+            }
+
+            let changed: usize = results
+                .iter()
+                .filter_map(|(_, r)| r.as_ref().ok())
+                .map(|c| c.len())
+                .sum();
+            let verb = if dry_run { "would be made" } else { "made" };
+            output.push_str(&format!(
+                "\n{} label change(s) {} across {} repo(s)\n",
+                changed,
+                verb,
+                results.len()
+            ));
+            output
+        }
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct RepoSync<'a> {
+                repo: &'a str,
+                dry_run: bool,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                changes: Option<&'a [LabelChange]>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                error: Option<String>,
+            }
+
+            let items: Vec<RepoSync> = results
+                .iter()
+                .map(|(repo, result)| RepoSync {
+                    repo,
+                    dry_run,
+                    changes: result.as_ref().ok().map(|c| c.as_slice()),
+                    error: result.as_ref().err().map(|e| format!("{:#}", e)),
+                })
+                .collect();
+            to_json(&items).unwrap_or_else(|_| "[]".to_string())
+        }
+    }
+}
+
+pub fn format_presets(presets: &[(String, RolePreset)], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = String::new();
+            for (name, preset) in presets {
+                output.push_str(&format!("{:<10} {} {}\n", name, preset.role, preset.signature));
+                if !preset.default_labels.is_empty() {
+                    output.push_str(&format!("           Labels: {}\n", preset.default_labels.join(", ")));
+                }
+                let commands = if preset.allowed_commands.is_empty() {
+                    "all".to_string()
+                } else {
+                    preset.allowed_commands.join(", ")
+                };
+                output.push_str(&format!("           Commands: {}\n", commands));
+                if preset.notify_author {
+                    output.push_str("           Mentions the issue author when closing\n");
+                }
+                if let Some(limit) = &preset.comment_limit {
+                    output.push_str(&format!(
+                        "           Comments: at most {} per issue every {} minutes\n",
+                        limit.max, limit.window_minutes
+                    ));
+                }
+                if preset.forbid_other_authors {
+                    output.push_str("           Never edits or deletes other users' comments\n");
+                }
+            }
+            output
+        }
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct PresetJson<'a> {
+                name: &'a str,
+                #[serde(flatten)]
+                preset: &'a RolePreset,
+            }
+
+            let items: Vec<PresetJson> = presets
+                .iter()
+                .map(|(name, preset)| PresetJson { name, preset })
+                .collect();
+            to_json(&items).unwrap_or_else(|_| "[]".to_string())
+        }
+    }
+}
+
+pub fn format_bench(report: &BenchReport, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let mut output = format!(
+                "GET {}: {} request(s), {} concurrent\n\n",
+                report.endpoint, report.requests, report.concurrency
+            );
+            let error_rate = 100.0 * report.failed as f64 / report.requests as f64;
+            output.push_str(&format!(
+                "  Succeeded:  {}\n  Failed:     {} ({:.1}%)\n",
+                report.succeeded, report.failed, error_rate
+            ));
+            output.push_str(&format!(
+                "  Duration:   {:.0}ms ({:.1} req/s)\n",
+                report.elapsed_ms, report.requests_per_sec
+            ));
+
+            if let Some(l) = &report.latency_ms {
+                output.push_str("\nLatency (ms, successful requests):\n");
+                output.push_str(&format!(
+                    "  min {:.1}  mean {:.1}  p50 {:.1}  p90 {:.1}  p95 {:.1}  p99 {:.1}  max {:.1}\n",
+                    l.min, l.mean, l.p50, l.p90, l.p95, l.p99, l.max
+                ));
+            }
+
+            if !report.errors.is_empty() {
+                output.push_str("\nErrors:\n");
+                for (error, count) in &report.errors {
+                    output.push_str(&format!("  {:>5}  {}\n", count, error));
+                }
+            }
+            output
+        }
+        OutputFormat::Json => to_json(report).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+/// Coverage per tag, listing the missing routes (and implemented ones too with
+/// `all`).
+pub fn format_coverage(report: &CoverageReport, all: bool, format: &OutputFormat) -> String {
+    let shown: Vec<&Route> = report.routes.iter().filter(|r| all || !r.implemented).collect();
+
+    match format {
+        OutputFormat::Human => {
+            let percent = if report.total == 0 {
+                0.0
+            } else {
+                100.0 * report.implemented as f64 / report.total as f64
+            };
+            let mut output = format!(
+                "API coverage: {} of {} routes ({:.0}%)\n",
+                report.implemented, report.total, percent
+            );
+
+            let mut tags: Vec<&str> = report.routes.iter().map(|r| r.tag.as_str()).collect();
+            tags.dedup();
+            for tag in tags {
+                let in_tag: Vec<&Route> = report.routes.iter().filter(|r| r.tag == tag).collect();
+                let done = in_tag.iter().filter(|r| r.implemented).count();
+                output.push_str(&format!("\n{} ({}/{})\n", tag, done, in_tag.len()));
+                for route in shown.iter().filter(|r| r.tag == tag) {
+                    let mark = if route.implemented { "✓" } else { " " };
+                    output.push_str(&format!(
+                        "  {} {:<6} {:<52} {}\n",
+                        mark, route.method, route.path, route.summary
+                    ));
+                }
+            }
+
+            if !report.unlisted.is_empty() {
+                output.push_str("\nCalled by gog but not in the spec:\n");
+                for endpoint in &report.unlisted {
+                    output.push_str(&format!("  {}\n", endpoint));
+                }
+            }
+            output
+        }
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct Json<'a> {
+                total: usize,
+                implemented: usize,
+                routes: Vec<&'a Route>,
+                unlisted: &'a [String],
+            }
+            to_json(&Json {
+                total: report.total,
+                implemented: report.implemented,
+                routes: shown,
+                unlisted: &report.unlisted,
+            })
+            .unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+pub fn format_version(
+    build: &BuildInfo,
+    verbose: bool,
+    server: Option<&ServerReport>,
+    format: &OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Human if !verbose => format!("gog {}\n", build.version),
+        OutputFormat::Human => {
+            let features = if build.features.is_empty() {
+                "none".to_string()
+            } else {
+                build.features.join(", ")
+            };
+            let mut output = format!("gog {} ({})\n", build.version, build.git_sha);
+            output.push_str(&format!("  Target:   {} ({})\n", build.target, build.profile));
+            output.push_str(&format!("  Compiler: {}\n", build.rustc));
+            output.push_str(&format!("  Features: {}\n", features));
+            output.push_str(&format!("  TLS:      {}\n", build.tls));
+
+            let Some(server) = server else {
+                output.push_str("\nNo server configured; run 'gog init' to see server compatibility.\n");
+                return output;
+            };
+            output.push_str(&format!(
+                "\nServer {} ({})\n",
+                server.url,
+                server.version.as_deref().unwrap_or("version not reported")
+            ));
+            for feature in &server.features {
+                let status = match &feature.support {
+                    Support::Supported => "yes".to_string(),
+                    Support::Unsupported => "no".to_string(),
+                    Support::Unknown(reason) => format!("?    {}", reason),
+                };
+                output.push_str(&format!("  {:<24} {}\n", feature.name, status));
+            }
+            output
+        }
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct VersionJson<'a> {
+                #[serde(flatten)]
+                build: &'a BuildInfo,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                server: Option<&'a ServerReport>,
+            }
+
+            to_json(&VersionJson { build, server }).unwrap_or_else(|_| "{}".to_string())
+        }
+    }
+}
+
+/// Report the outcome of `self-update`: "current", "available" or "installed".
+pub fn format_update(current: &str, release: &str, status: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => match status {
+            "current" => format!("gog {} is up to date (latest release: {})\n", current, release),
+            "available" => format!(
+                "Update available: {} -> {}\nRun 'gog self-update' to install it.\n",
+                current, release
+            ),
+            _ => format!("Updated gog {} -> {}\n", current, release),
+        },
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "current": current,
+            "release": release,
+            "status": status,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+#[cfg(unix)]
+pub fn format_daemon_status(status: Option<&DaemonStatus>, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => match status {
+            None => "No daemon is running.\n".to_string(),
+            Some(status) => {
+                let hit_rate = match status.requests {
+                    0 => 0.0,
+                    n => status.cache_hits as f64 * 100.0 / n as f64,
+                };
+                format!(
+                    "Daemon running (pid {}) on {}\n  up {}s, {} request(s) relayed, {} from cache ({:.0}%)\n  {} response(s) cached for {}s each\n",
+                    status.pid,
+                    status.socket,
+                    status.uptime_secs,
+                    status.requests,
+                    status.cache_hits,
+                    hit_rate,
+                    status.cached,
+                    status.cache_ttl_secs
+                )
+            }
+        },
+        OutputFormat::Json => to_json(&serde_json::json!({
+            "running": status.is_some(),
+            "daemon": status,
+        }))
+        .unwrap_or_else(|_| "{}".to_string()),
+    }
+}
+
+pub fn format_checks(checks: &[Check], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => {
+            let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0).max(4);
+            let mut output = String::new();
+            for check in checks {
+                let status = match check.status {
+                    Status::Ok => "ok",
+                    Status::Warn => "WARN",
+                    Status::Fail => "FAIL",
+                };
+                output.push_str(&format!("{:<4} {:<width$} {}\n", status, check.name, check.detail));
+                if let Some(hint) = &check.hint {
+                    output.push_str(&format!("{:indent$}{}\n", "", hint, indent = width + 6));
+                }
+            }
+            output
+        }
+        OutputFormat::Json => to_json(checks).unwrap_or_else(|_| "[]".to_string()),
+    }
+}
+
+pub fn format_lint_result(issue: Option<i64>, violations: &[Violation], format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format_lint_human(issue, violations),
+        OutputFormat::Json => format_lint_json(issue, violations),
+    }
+}
+
+fn format_lint_human(issue: Option<i64>, violations: &[Violation]) -> String {
+    let subject = match issue {
+        Some(number) => format!("Issue #{}", number),
+        None => "Body".to_string(),
+    };
+
+    if violations.is_empty() {
+        return format!("{} passes all lint rules.\n", subject);
+    }
+
+    let mut output = format!("{} has {} lint violation(s):\n", subject, violations.len());
+    for v in violations {
+        output.push_str(&format!("  [{}] {}\n", v.rule, v.message));
+    }
+    output
+}
+
+fn format_lint_json(issue: Option<i64>, violations: &[Violation]) -> String {
+    #[derive(serde::Serialize)]
+    struct LintResult<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        issue: Option<i64>,
+        passed: bool,
+        violations: &'a [Violation],
+    }
+
+    let result = LintResult {
+        issue,
+        passed: violations.is_empty(),
+        violations,
+    };
+
+    to_json(&result).unwrap_or_else(|_| "{}".to_string())
+}
+
+pub fn format_web_url(url: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Human => format!("Opening {} in your browser.\n", url),
+        OutputFormat::Json => {
+            to_json(&serde_json::json!({ "url": url }))
+                .unwrap_or_else(|_| "{}".to_string())
+        }
     }
 }
@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::api::flood::CommentLimit;
+use crate::config::Profile;
+use crate::signature::Position;
+
+/// Settings a profile picks up in one go from a role, e.g. everything a
+/// builder agent needs. Presets in the config's `[presets.NAME]` tables add to
+/// or replace the built-in ones.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RolePreset {
+    /// Role description stored on the profile
+    pub role: String,
+
+    /// Comment signature; `{user}` is replaced by the profile's Gogs username
+    pub signature: String,
+
+    /// Labels added to every issue the profile creates
+    #[serde(default)]
+    pub default_labels: Vec<String>,
+
+    /// Commands the profile may run, e.g. "issue comment"; empty allows all
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+
+    /// Mention the issue author when closing with a comment
+    #[serde(default)]
+    pub notify_author: bool,
+
+    /// Most comments per issue in a time window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_limit: Option<CommentLimit>,
+
+    /// Never edit or delete other users' comments
+    #[serde(default)]
+    pub forbid_other_authors: bool,
+}
+
+/// Built-in presets as (name, preset), in the order init offers them.
+pub fn builtin() -> Vec<(String, RolePreset)> {
+    let preset = |role: &str, signature: &str, labels: &[&str], commands: &[&str], notify: bool| RolePreset {
+        role: role.to_string(),
+        signature: signature.to_string(),
+        default_labels: labels.iter().map(|s| s.to_string()).collect(),
+        allowed_commands: commands.iter().map(|s| s.to_string()).collect(),
+        notify_author: notify,
+        // Only agents are restricted, and only agents loop
+        comment_limit: (!commands.is_empty()).then(CommentLimit::agent_default),
+        forbid_other_authors: !commands.is_empty(),
+    };
+
+    vec![
+        ("human".to_string(), preset("Human Developer", "[Human]", &[], &[], false)),
+        (
+            "planner".to_string(),
+            preset(
+                "Planning Agent",
+                "[Planner]",
+                &["planned"],
+                &["issue", "label", "repo list", "org", "user", "branch", "report", "open-items", "file get", "repo archive"],
+                false,
+            ),
+        ),
+        (
+            "builder".to_string(),
+            preset(
+                "Builder Agent",
+                "[Builder]",
+                &["follow-up"],
+                &[
+                    "issue list",
+                    "issue search",
+                    "issue show",
+                    "issue create",
+                    "issue comment",
+                    "issue stream",
+                    "issue vote",
+                    "issue close",
+                    "issue reopen",
+                    "issue label",
+                    "issue unlabel",
+                    "issue handoff",
+                    "issue unassign-all",
+                    "issue lint",
+                    "repo list",
+                    "org list",
+                    "org repos",
+                    "user show",
+                    "user search",
+                    "file get",
+                    "repo archive",
+                    "label list",
+                    "branch",
+                    "open-items",
+                    "capture",
+                ],
+                true,
+            ),
+        ),
+        (
+            "reviewer".to_string(),
+            preset(
+                "Reviewer Agent",
+                "[Reviewer]",
+                &["review-finding"],
+                &[
+                    "issue list",
+                    "issue search",
+                    "issue show",
+                    "issue create",
+                    "issue comment",
+                    "issue stream",
+                    "issue vote",
+                    "issue label",
+                    "issue unlabel",
+                    "issue handoff",
+                    "issue lint",
+                    "repo list",
+                    "org list",
+                    "org repos",
+                    "user show",
+                    "user search",
+                    "file get",
+                    "repo archive",
+                    "label list",
+                    "branch",
+                    "open-items",
+                ],
+                true,
+            ),
+        ),
+        (
+            "triage".to_string(),
+            preset(
+                "Triage Agent",
+                "[Triage]",
+                &[],
+                &[
+                    "issue list",
+                    "issue search",
+                    "issue show",
+                    "issue comment",
+                    "issue vote",
+                    "issue close",
+                    "issue label",
+                    "issue unlabel",
+                    "issue bulk",
+                    "repo list",
+                    "org list",
+                    "org repos",
+                    "user show",
+                    "user search",
+                    "file get",
+                    "repo archive",
+                    "label",
+                ],
+                false,
+            ),
+        ),
+    ]
+}
+
+/// Built-in presets overlaid with the config's own, built-ins first.
+pub fn all(custom: &HashMap<String, RolePreset>) -> Vec<(String, RolePreset)> {
+    let mut presets = builtin();
+    for (name, preset) in &mut presets {
+        if let Some(custom) = custom.get(name.as_str()) {
+            *preset = custom.clone();
+        }
+    }
+
+    let mut extra: Vec<_> = custom
+        .iter()
+        .filter(|(name, _)| !presets.iter().any(|(n, _)| n == *name))
+        .map(|(name, preset)| (name.clone(), preset.clone()))
+        .collect();
+    extra.sort_by(|a, b| a.0.cmp(&b.0));
+    presets.extend(extra);
+    presets
+}
+
+/// Look a preset up by name, case-insensitively.
+pub fn find(custom: &HashMap<String, RolePreset>, name: &str) -> Option<RolePreset> {
+    all(custom)
+        .into_iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, preset)| preset)
+}
+
+impl RolePreset {
+    /// Build a profile for `gogs_user` from this preset.
+    pub fn profile(&self, gogs_user: String, token: String) -> Profile {
+        Profile {
+            signature: self.signature.replace("{user}", &gogs_user),
+            signature_position: Position::default(),
+            gogs_user,
+            token,
+            keyring: None,
+            token_cmd: None,
+            server: None,
+            role: self.role.clone(),
+            default_labels: self.default_labels.clone(),
+            allowed_commands: self.allowed_commands.clone(),
+            notify_author: self.notify_author,
+            mirror: None,
+            signing_key: None,
+            comment_limit: self.comment_limit.clone(),
+            forbid_other_authors: self.forbid_other_authors,
+        }
+    }
+}
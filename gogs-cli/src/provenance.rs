@@ -0,0 +1,161 @@
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::api::Comment;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Start of the line a signed comment ends with; an HTML comment, so the web
+/// UI doesn't render it.
+const TRAILER: &str = "<!-- gog-signature v1 ";
+const TRAILER_END: &str = " -->";
+
+/// Bytes in a key made by `gog profile keygen`.
+const KEY_LEN: usize = 32;
+
+/// Signs comments with a profile's key. The signature covers the repository,
+/// issue number and posting account as well as the text, so a signed comment
+/// can't be copied to another issue or reposted from another account.
+#[derive(Clone)]
+pub struct Signer {
+    key: Vec<u8>,
+    user: String,
+}
+
+/// What `gog issue verify` found out about one comment.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(tag = "status", content = "signer", rename_all = "lowercase")]
+pub enum Provenance {
+    /// Signed with the key of the named profile or trusted key
+    Verified(String),
+    /// No signature line
+    Unsigned,
+    /// A signature line that no known key produced
+    Invalid,
+}
+
+/// A fresh random key, base64-encoded as stored in the config.
+pub fn generate_key() -> Result<String> {
+    let mut key = [0u8; KEY_LEN];
+    getrandom::getrandom(&mut key).map_err(|e| anyhow::anyhow!("No randomness available: {}", e))?;
+    Ok(STANDARD.encode(key))
+}
+
+fn decode_key(key: &str) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(key.trim())
+        .context("Signing key is not valid base64")
+}
+
+fn mac(key: &[u8], repo: &str, number: i64, user: &str, text: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length");
+    let message = format!("v1\n{}#{}\n{}\n{}", repo.to_lowercase(), number, user.to_lowercase(), text);
+    mac.update(message.as_bytes());
+    mac
+}
+
+impl Signer {
+    /// A signer for comments posted as `user` with the base64 `key`.
+    pub fn new(key: &str, user: &str) -> Result<Self> {
+        Ok(Self {
+            key: decode_key(key)?,
+            user: user.to_string(),
+        })
+    }
+
+    /// `body` with a signature line appended, for posting on `repo#number`.
+    pub fn sign(&self, repo: &str, number: i64, body: &str) -> String {
+        let tag = mac(&self.key, repo, number, &self.user, body).finalize().into_bytes();
+        format!("{}\n\n{}{}{}", body, TRAILER, URL_SAFE_NO_PAD.encode(tag), TRAILER_END)
+    }
+}
+
+/// Split a signed body into the text that was signed and its signature.
+fn split_signed(body: &str) -> Option<(&str, &str)> {
+    let body = body.trim_end();
+    let start = body.rfind(TRAILER)?;
+    let signature = body[start + TRAILER.len()..].strip_suffix(TRAILER_END)?;
+    let text = body[..start].strip_suffix("\n\n")?;
+    Some((text, signature))
+}
+
+/// The comment's text without its signature line, for display.
+pub fn strip(body: &str) -> &str {
+    split_signed(body).map(|(text, _)| text).unwrap_or(body)
+}
+
+/// Check `comment` on `repo#number` against each named base64 key.
+pub fn verify(comment: &Comment, repo: &str, number: i64, keys: &[(String, String)]) -> Provenance {
+    let Some((text, signature)) = split_signed(&comment.body) else {
+        return Provenance::Unsigned;
+    };
+    let Ok(tag) = URL_SAFE_NO_PAD.decode(signature) else {
+        return Provenance::Invalid;
+    };
+
+    for (name, key) in keys {
+        let Ok(key) = decode_key(key) else { continue };
+        if mac(&key, repo, number, &comment.user.username, text)
+            .verify_slice(&tag)
+            .is_ok()
+        {
+            return Provenance::Verified(name.clone());
+        }
+    }
+    Provenance::Invalid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::User;
+    use chrono::Utc;
+
+    fn comment(user: &str, body: &str) -> Comment {
+        Comment {
+            id: 1,
+            body: body.to_string(),
+            user: User {
+                id: 1,
+                username: user.to_string(),
+                full_name: None,
+                email: None,
+            },
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            html_url: None,
+        }
+    }
+
+    #[test]
+    fn signed_comments_verify_only_where_posted() {
+        let key = generate_key().unwrap();
+        let keys = [("agent".to_string(), key.clone())];
+        let body = Signer::new(&key, "Agent").unwrap().sign("Org/Repo", 7, "Fixed in abc123");
+
+        let verified = Provenance::Verified("agent".to_string());
+        assert_eq!(verify(&comment("agent", &body), "org/repo", 7, &keys), verified);
+        assert_eq!(strip(&body), "Fixed in abc123");
+
+        let edited = body.replace("abc123", "def456");
+        assert_eq!(verify(&comment("agent", &edited), "org/repo", 7, &keys), Provenance::Invalid);
+        assert_eq!(verify(&comment("agent", &body), "org/other", 7, &keys), Provenance::Invalid);
+        assert_eq!(verify(&comment("agent", &body), "org/repo", 8, &keys), Provenance::Invalid);
+        assert_eq!(verify(&comment("mallory", &body), "org/repo", 7, &keys), Provenance::Invalid);
+
+        let other_key = [("other".to_string(), generate_key().unwrap())];
+        assert_eq!(verify(&comment("agent", &body), "org/repo", 7, &other_key), Provenance::Invalid);
+    }
+
+    #[test]
+    fn unsigned_comments_say_so() {
+        let keys = [("agent".to_string(), generate_key().unwrap())];
+        let plain = comment("agent", "Looks good");
+        assert_eq!(verify(&plain, "org/repo", 7, &keys), Provenance::Unsigned);
+        assert_eq!(strip(&plain.body), "Looks good");
+    }
+}
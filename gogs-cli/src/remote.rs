@@ -0,0 +1,103 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Where a git remote points: the Gogs server and the repository on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remote {
+    /// Web address of the server, e.g. `https://gogs.example.com`. For
+    /// SSH remotes this is a guess, as the clone URL doesn't say.
+    pub server_url: String,
+    pub host: String,
+    pub owner: String,
+    pub repo: String,
+}
+
+impl Remote {
+    /// `owner/repo`.
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+
+    /// Whether the remote is on the server at `server_url`. Only host names
+    /// are compared, since SSH and the web interface use different ports.
+    pub fn is_on(&self, server_url: &str) -> bool {
+        Self::split_authority(server_url.split("://").last().unwrap_or_default())
+            .is_some_and(|(host, _)| host.eq_ignore_ascii_case(&self.host))
+    }
+
+    /// Parse a clone URL: `https://host[:port]/[prefix/]owner/repo.git`,
+    /// `ssh://git@host[:port]/owner/repo.git` or `git@host:owner/repo.git`.
+    /// Local paths give None.
+    pub fn parse(url: &str) -> Option<Remote> {
+        let url = url.trim().trim_end_matches('/');
+        let url = url.strip_suffix(".git").unwrap_or(url);
+
+        let (scheme, authority, path) = match url.split_once("://") {
+            Some((scheme, rest)) => {
+                let (authority, path) = rest.split_once('/')?;
+                (Some(scheme), authority, path)
+            }
+            // scp-like syntax; a colon after a slash is part of a local path
+            None => {
+                let (authority, path) = url.split_once(':')?;
+                if authority.contains('/') {
+                    return None;
+                }
+                (None, authority, path)
+            }
+        };
+        let (host, authority) = Self::split_authority(authority)?;
+
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let [prefix @ .., owner, repo] = segments.as_slice() else {
+            return None;
+        };
+
+        let server_url = match scheme {
+            Some(scheme @ ("http" | "https")) => {
+                // Gogs may be served from a sub-path, which the clone URL keeps
+                let mut url = format!("{}://{}", scheme, authority);
+                for segment in prefix {
+                    url.push('/');
+                    url.push_str(segment);
+                }
+                url
+            }
+            Some("ssh" | "git") | None => format!("https://{}", host),
+            Some(_) => return None,
+        };
+
+        Some(Remote {
+            server_url,
+            host: host.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+
+    /// The host name and the authority without credentials, from
+    /// `[user@]host[:port]`.
+    fn split_authority(authority: &str) -> Option<(&str, &str)> {
+        let authority = authority.split('/').next()?;
+        let authority = authority.rsplit('@').next()?;
+        let host = authority.split(':').next()?;
+        (!host.is_empty()).then_some((host, authority))
+    }
+}
+
+/// The `origin` remote of the checkout containing `dir`, if there is one and
+/// it looks like a hosted repository.
+pub fn origin(dir: &Path) -> Option<Remote> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["remote", "get-url", "origin"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Remote::parse(&String::from_utf8_lossy(&output.stdout))
+}
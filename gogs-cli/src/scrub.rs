@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use crate::api::{Comment, Issue, User};
+
+/// Replaces identities in exported issues: usernames become stable
+/// pseudonyms ("user-1", "user-2", ... in order of appearance), and emails,
+/// full names and attachments are removed. Mentions in text are rewritten to
+/// the same pseudonyms, so conversations still read coherently.
+#[derive(Debug, Default)]
+pub struct Scrubber {
+    pseudonyms: HashMap<String, String>,
+}
+
+impl Scrubber {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn issue(&mut self, issue: &mut Issue) {
+        self.user(&mut issue.user);
+        if let Some(assignee) = &mut issue.assignee {
+            self.user(assignee);
+        }
+        issue.body = issue.body.as_deref().map(|body| self.text(body));
+    }
+
+    pub fn comment(&mut self, comment: &mut Comment) {
+        self.user(&mut comment.user);
+        comment.body = self.text(&comment.body);
+    }
+
+    pub fn user(&mut self, user: &mut User) {
+        let pseudonym = self.pseudonym(&user.username);
+        // Ids can be looked up on the server, so they go too
+        user.id = pseudonym
+            .trim_start_matches("user-")
+            .parse()
+            .unwrap_or_default();
+        user.username = pseudonym;
+        user.full_name = None;
+        user.email = None;
+    }
+
+    /// Scrub free text: mentions, email addresses and attachment links.
+    pub fn text(&mut self, text: &str) -> String {
+        let text = strip_attachment_links(text);
+        let chars: Vec<char> = text.chars().collect();
+        let mut output = String::with_capacity(text.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            if chars[i] != '@' {
+                output.push(chars[i]);
+                i += 1;
+                continue;
+            }
+
+            // An '@' inside a word is an email address; at a word start, a mention
+            let local_start = output
+                .char_indices()
+                .rev()
+                .take_while(|(_, c)| is_email_char(*c))
+                .last()
+                .map(|(idx, _)| idx);
+            let domain_len = chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_ascii_alphanumeric() || **c == '.' || **c == '-')
+                .count();
+            let domain: String = chars[i + 1..i + 1 + domain_len].iter().collect();
+            let domain = domain.trim_end_matches('.');
+
+            if let Some(start) = local_start {
+                if domain.contains('.') {
+                    output.truncate(start);
+                    output.push_str("[email removed]");
+                    i += 1 + domain.chars().count();
+                    continue;
+                }
+            }
+
+            let name_len = chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+                .count();
+            let name: String = chars[i + 1..i + 1 + name_len].iter().collect();
+            let name = name.trim_end_matches('.');
+            if local_start.is_none() && !name.is_empty() {
+                output.push('@');
+                output.push_str(&self.pseudonym(name));
+                i += 1 + name.chars().count();
+            } else {
+                output.push('@');
+                i += 1;
+            }
+        }
+
+        output
+    }
+
+    fn pseudonym(&mut self, username: &str) -> String {
+        let next = self.pseudonyms.len() + 1;
+        self.pseudonyms
+            .entry(username.to_lowercase())
+            .or_insert_with(|| format!("user-{}", next))
+            .clone()
+    }
+}
+
+fn is_email_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '%' | '+' | '-')
+}
+
+fn is_attachment_url(url: &str) -> bool {
+    url.contains("/attachments/") || url.contains("/releases/download/")
+}
+
+/// Replace Markdown links and images pointing at uploads, and bare upload URLs.
+fn strip_attachment_links(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(mid) = rest.find("](") {
+        let url_end = rest[mid + 2..].find(')').map(|end| mid + 2 + end);
+        let start = rest[..mid].rfind('[');
+        let (Some(start), Some(url_end)) = (start, url_end) else {
+            output.push_str(&rest[..mid + 2]);
+            rest = &rest[mid + 2..];
+            continue;
+        };
+
+        if is_attachment_url(&rest[mid + 2..url_end]) {
+            let start = if rest[..start].ends_with('!') { start - 1 } else { start };
+            output.push_str(&rest[..start]);
+            output.push_str("[attachment removed]");
+        } else {
+            output.push_str(&rest[..=url_end]);
+        }
+        rest = &rest[url_end + 1..];
+    }
+    output.push_str(rest);
+
+    output
+        .split_inclusive(char::is_whitespace)
+        .map(|word| {
+            let trimmed = word.trim_end();
+            if (trimmed.starts_with("http://") || trimmed.starts_with("https://")) && is_attachment_url(trimmed) {
+                format!("[attachment removed]{}", &word[trimmed.len()..])
+            } else {
+                word.to_string()
+            }
+        })
+        .collect()
+}
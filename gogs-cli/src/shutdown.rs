@@ -0,0 +1,87 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tokio::sync::Notify;
+
+use crate::error::GogsError;
+
+/// Exit status of a command stopped by SIGINT or SIGTERM (128 + SIGINT, as
+/// shells report an interrupted command).
+pub const INTERRUPTED_EXIT: i32 = 130;
+
+/// Whether the running command stops gracefully rather than at the first signal.
+static GRACEFUL: AtomicBool = AtomicBool::new(false);
+
+/// Whether a signal has asked the command to stop.
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+fn notify() -> &'static Notify {
+    static NOTIFY: OnceLock<Notify> = OnceLock::new();
+    NOTIFY.get_or_init(Notify::new)
+}
+
+/// Handle SIGINT and SIGTERM for the rest of the process. Most commands exit
+/// at once, as without a handler. Commands that called `graceful` are asked
+/// to stop instead: they start no new work, let requests in flight finish and
+/// report what they did. A second signal exits at once either way.
+pub fn install() {
+    tokio::spawn(async {
+        loop {
+            if !wait_for_signal().await {
+                return;
+            }
+            if !GRACEFUL.load(Ordering::SeqCst) || REQUESTED.swap(true, Ordering::SeqCst) {
+                std::process::exit(INTERRUPTED_EXIT);
+            }
+            eprintln!("Interrupted: finishing requests in flight (interrupt again to stop at once)");
+            notify().notify_waiters();
+        }
+    });
+}
+
+/// Opt the running command into stopping gracefully; see `install`.
+pub fn graceful() {
+    GRACEFUL.store(true, Ordering::SeqCst);
+}
+
+/// Whether the command has been asked to stop.
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Wait until the command is asked to stop.
+pub async fn wait() {
+    let notified = notify().notified();
+    tokio::pin!(notified);
+    // Registered before checking, so a signal in between isn't missed
+    notified.as_mut().enable();
+    if requested() {
+        return;
+    }
+    notified.await;
+}
+
+/// The error a command returns after stopping early, once it has printed what
+/// it got done. `progress` says how far it got.
+pub fn interrupted(progress: impl Into<String>) -> anyhow::Error {
+    GogsError::Interrupted(progress.into()).into()
+}
+
+/// False if signals can't be listened for, so the caller stops trying.
+#[cfg(unix)]
+async fn wait_for_signal() -> bool {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let (Ok(mut interrupt), Ok(mut terminate)) = (signal(SignalKind::interrupt()), signal(SignalKind::terminate()))
+    else {
+        return false;
+    };
+    tokio::select! {
+        _ = interrupt.recv() => true,
+        _ = terminate.recv() => true,
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> bool {
+    tokio::signal::ctrl_c().await.is_ok()
+}
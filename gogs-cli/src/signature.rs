@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where a profile's signature goes on what it posts.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Position {
+    /// Before the text, on the same line: `[Builder] Done.`
+    #[default]
+    Prefix,
+    /// After the text, on a line of its own, so a body can start with a heading
+    Suffix,
+    /// Not at all
+    None,
+}
+
+impl Position {
+    pub fn is_default(&self) -> bool {
+        *self == Position::default()
+    }
+}
+
+/// Variables that change from one post to the next. When matching a posted
+/// signature against a profile's, they match any timestamp-like text.
+const TIME_VARIABLES: &[&str] = &["{timestamp}", "{date}"];
+
+/// This machine's host name, if it can be found.
+pub fn hostname() -> Option<String> {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+}
+
+/// `template` with the variables that stay the same between posts filled in:
+/// `{user}`, `{role}` and `{host}`. Unknown variables are left as written.
+pub fn pattern(template: &str, user: &str, role: &str) -> String {
+    let mut signature = template.replace("{user}", user).replace("{role}", role);
+    if signature.contains("{host}") {
+        let host = hostname().unwrap_or_else(|| "unknown-host".to_string());
+        signature = signature.replace("{host}", &host);
+    }
+    signature
+}
+
+/// `template` as posted at `now`: everything `pattern` fills in, plus
+/// `{date}` and `{timestamp}` (UTC, e.g. 2026-10-14 and 2026-10-14T09:30:00Z).
+pub fn expand(template: &str, user: &str, role: &str, now: DateTime<Utc>) -> String {
+    pattern(template, user, role)
+        .replace("{timestamp}", &now.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+}
+
+/// `text` with `signature` put at `position`. Empty text is just the signature.
+pub fn apply(signature: &str, position: Position, text: &str) -> String {
+    match position {
+        Position::None => text.to_string(),
+        _ if signature.is_empty() => text.to_string(),
+        _ if text.is_empty() => signature.to_string(),
+        Position::Prefix => format!("{} {}", signature, text),
+        Position::Suffix => format!("{}\n\n{}", text.trim_end(), signature),
+    }
+}
+
+/// `text` without the signature matching `pattern` that `apply` put at
+/// `position`, if it has one.
+pub fn remove<'a>(pattern: &str, position: Position, text: &'a str) -> Option<&'a str> {
+    match position {
+        Position::Prefix => strip(pattern, text).map(str::trim_start),
+        Position::Suffix => {
+            let text = text.trim_end();
+            let (body, last) = text.rsplit_once("\n\n").unwrap_or(("", text));
+            matches(pattern, last).then_some(body)
+        }
+        Position::None => None,
+    }
+}
+
+/// Whether `signature` is what `pattern` expands to at some time.
+pub fn matches(pattern: &str, signature: &str) -> bool {
+    match_len(&pieces(pattern), signature) == Some(signature.len())
+}
+
+/// `text` with a signature matching `pattern` taken off the front, if it
+/// starts with one.
+pub fn strip<'a>(pattern: &str, text: &'a str) -> Option<&'a str> {
+    match_len(&pieces(pattern), text).map(|len| &text[len..])
+}
+
+enum Piece<'a> {
+    Literal(&'a str),
+    Time,
+}
+
+/// `pattern` as literal text between time variables.
+fn pieces(pattern: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut rest = pattern;
+    while let Some((at, variable)) = TIME_VARIABLES
+        .iter()
+        .filter_map(|v| rest.find(v).map(|at| (at, v)))
+        .min()
+    {
+        if at > 0 {
+            pieces.push(Piece::Literal(&rest[..at]));
+        }
+        pieces.push(Piece::Time);
+        rest = &rest[at + variable.len()..];
+    }
+    if !rest.is_empty() {
+        pieces.push(Piece::Literal(rest));
+    }
+    pieces
+}
+
+/// How much of the start of `text` the pieces match, if they do. A time
+/// variable takes as many timestamp characters as still lets the rest match.
+fn match_len(pieces: &[Piece], text: &str) -> Option<usize> {
+    match pieces.split_first() {
+        None => Some(0),
+        Some((Piece::Literal(literal), rest)) => {
+            let after = text.strip_prefix(literal)?;
+            match_len(rest, after).map(|len| literal.len() + len)
+        }
+        Some((Piece::Time, rest)) => {
+            let run = text
+                .bytes()
+                .take_while(|b| b.is_ascii_digit() || b"-:+TZ".contains(b))
+                .count();
+            (1..=run)
+                .rev()
+                .find_map(|n| match_len(rest, &text[n..]).map(|len| n + len))
+        }
+    }
+}
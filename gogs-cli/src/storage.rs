@@ -0,0 +1,350 @@
+use rusqlite::{Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Which store local state (label cache, comment log, quarantine, journal) goes in.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// One SQLite database in the cache directory, shared by every invocation
+    #[default]
+    Sqlite,
+    /// A file per document under the cache directory
+    File,
+    /// Kept in the process only and gone when it exits, e.g. for tests and CI
+    Memory,
+}
+
+impl Backend {
+    pub fn is_default(&self) -> bool {
+        *self == Backend::default()
+    }
+}
+
+/// A stored document and when it was last written.
+pub struct Stored {
+    pub data: Vec<u8>,
+    pub modified: SystemTime,
+}
+
+/// Where gog keeps its local state. Keys are relative paths such as
+/// `labels/owner__repo.json` or `journal.jsonl`.
+pub trait Storage: Send + Sync {
+    /// The document at `key`, or None if there isn't one or it can't be read.
+    fn read(&self, key: &str) -> Option<Stored>;
+
+    /// Replace the document at `key`.
+    fn write(&self, key: &str, data: &[u8]) -> io::Result<()>;
+
+    /// Add `data` to the end of the document at `key`, creating it if needed.
+    fn append(&self, key: &str, data: &[u8]) -> io::Result<()>;
+
+    /// Forget the document at `key`; one that isn't there is not an error.
+    fn remove(&self, key: &str) -> io::Result<()>;
+
+    /// Where `key` lives, for messages.
+    fn describe(&self, key: &str) -> String;
+}
+
+/// Documents as files under a directory, the default.
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+
+    fn create_parent(path: &Path) -> io::Result<()> {
+        path.parent().map_or(Ok(()), fs::create_dir_all)
+    }
+}
+
+impl Storage for FileStorage {
+    fn read(&self, key: &str) -> Option<Stored> {
+        let path = self.path(key);
+        let modified = fs::metadata(&path).ok()?.modified().ok()?;
+        let data = fs::read(&path).ok()?;
+        Some(Stored { data, modified })
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        let path = self.path(key);
+        Self::create_parent(&path)?;
+        fs::write(path, data)
+    }
+
+    fn append(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        let path = self.path(key);
+        Self::create_parent(&path)?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(data))
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(key)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn describe(&self, key: &str) -> String {
+        self.path(key).display().to_string()
+    }
+}
+
+/// Name of the database the SQLite backend keeps in the cache directory.
+pub const DATABASE_FILE: &str = "state.db";
+
+/// Documents as rows of one SQLite database, the default. Concurrent gog
+/// processes (e.g. under `gog foreach`) wait for each other's writes rather
+/// than interleaving them. Documents the file backend left in the same
+/// directory are read once and moved into the database.
+pub struct SqliteStorage {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+    /// Where the file backend kept its documents
+    legacy: FileStorage,
+}
+
+impl SqliteStorage {
+    /// Open or create the database in `dir`.
+    pub fn open(dir: PathBuf) -> rusqlite::Result<Self> {
+        let path = dir.join(DATABASE_FILE);
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let connection = Connection::open(&path)?;
+        connection.busy_timeout(Duration::from_secs(5))?;
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS documents (
+                key TEXT PRIMARY KEY,
+                data BLOB NOT NULL,
+                modified INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        Ok(Self {
+            path,
+            connection: Mutex::new(connection),
+            legacy: FileStorage::new(dir),
+        })
+    }
+
+    fn select(connection: &Connection, key: &str) -> rusqlite::Result<Option<Stored>> {
+        connection
+            .query_row("SELECT data, modified FROM documents WHERE key = ?1", [key], |row| {
+                let millis: i64 = row.get(1)?;
+                Ok(Stored {
+                    data: row.get(0)?,
+                    modified: UNIX_EPOCH + Duration::from_millis(millis.max(0) as u64),
+                })
+            })
+            .optional()
+    }
+
+    /// Move the file backend's copy of `key`, if any, into the database.
+    fn import(&self, connection: &Connection, key: &str) -> rusqlite::Result<Option<Stored>> {
+        let Some(stored) = self.legacy.read(key) else {
+            return Ok(None);
+        };
+        connection.execute(
+            "INSERT OR IGNORE INTO documents (key, data, modified) VALUES (?1, ?2, ?3)",
+            rusqlite::params![key, stored.data, millis(stored.modified)],
+        )?;
+        let _ = self.legacy.remove(key);
+        Self::select(connection, key)
+    }
+}
+
+fn millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as i64
+}
+
+fn to_io(e: rusqlite::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+impl Storage for SqliteStorage {
+    fn read(&self, key: &str) -> Option<Stored> {
+        let connection = self.connection.lock().unwrap();
+        match Self::select(&connection, key).ok()? {
+            Some(stored) => Some(stored),
+            None => self.import(&connection, key).ok()?,
+        }
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "INSERT INTO documents (key, data, modified) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(key) DO UPDATE SET data = excluded.data, modified = excluded.modified",
+                rusqlite::params![key, data, millis(SystemTime::now())],
+            )
+            .map_err(to_io)?;
+        let _ = self.legacy.remove(key);
+        Ok(())
+    }
+
+    fn append(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        let connection = self.connection.lock().unwrap();
+        self.import(&connection, key).map_err(to_io)?;
+        connection
+            .execute(
+                "INSERT INTO documents (key, data, modified) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(key) DO UPDATE SET data = CAST(data || excluded.data AS BLOB), modified = excluded.modified",
+                rusqlite::params![key, data, millis(SystemTime::now())],
+            )
+            .map_err(to_io)?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute("DELETE FROM documents WHERE key = ?1", [key])
+            .map_err(to_io)?;
+        self.legacy.remove(key)
+    }
+
+    fn describe(&self, key: &str) -> String {
+        format!("{} in {}", key, self.path.display())
+    }
+}
+
+/// Documents in the process only.
+#[derive(Default)]
+pub struct MemoryStorage {
+    documents: Mutex<HashMap<String, Stored>>,
+}
+
+impl MemoryStorage {
+    /// The one store for the whole process, so every part of it sees the
+    /// same state, as it would with files.
+    pub fn shared() -> Arc<MemoryStorage> {
+        static SHARED: OnceLock<Arc<MemoryStorage>> = OnceLock::new();
+        SHARED.get_or_init(Arc::default).clone()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self, key: &str) -> Option<Stored> {
+        let documents = self.documents.lock().unwrap();
+        documents.get(key).map(|stored| Stored {
+            data: stored.data.clone(),
+            modified: stored.modified,
+        })
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        let stored = Stored {
+            data: data.to_vec(),
+            modified: SystemTime::now(),
+        };
+        self.documents.lock().unwrap().insert(key.to_string(), stored);
+        Ok(())
+    }
+
+    fn append(&self, key: &str, data: &[u8]) -> io::Result<()> {
+        let mut documents = self.documents.lock().unwrap();
+        let stored = documents.entry(key.to_string()).or_insert_with(|| Stored {
+            data: Vec::new(),
+            modified: SystemTime::now(),
+        });
+        stored.data.extend_from_slice(data);
+        stored.modified = SystemTime::now();
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        self.documents.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn describe(&self, key: &str) -> String {
+        format!("{} (in memory)", key)
+    }
+}
+
+/// The JSON document at `key`; a missing or damaged one reads as the default.
+pub fn load_json<T: DeserializeOwned + Default>(storage: &dyn Storage, key: &str) -> T {
+    storage
+        .read(key)
+        .and_then(|stored| serde_json::from_slice(&stored.data).ok())
+        .unwrap_or_default()
+}
+
+/// Replace the JSON document at `key`.
+pub fn save_json<T: Serialize>(storage: &dyn Storage, key: &str, value: &T) -> io::Result<()> {
+    storage.write(key, &serde_json::to_vec(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What every backend must do, checked through the trait alone.
+    fn round_trip(storage: &dyn Storage) {
+        assert!(storage.read("labels/a__b.json").is_none());
+
+        storage.write("labels/a__b.json", b"[1]").unwrap();
+        assert_eq!(storage.read("labels/a__b.json").unwrap().data, b"[1]");
+        storage.write("labels/a__b.json", b"[2]").unwrap();
+        assert_eq!(storage.read("labels/a__b.json").unwrap().data, b"[2]");
+
+        storage.append("journal.jsonl", b"one\n").unwrap();
+        storage.append("journal.jsonl", b"two\n").unwrap();
+        assert_eq!(storage.read("journal.jsonl").unwrap().data, b"one\ntwo\n");
+
+        storage.remove("labels/a__b.json").unwrap();
+        assert!(storage.read("labels/a__b.json").is_none());
+        storage.remove("labels/a__b.json").unwrap();
+
+        save_json(storage, "quarantine.json", &vec!["x".to_string()]).unwrap();
+        assert_eq!(load_json::<Vec<String>>(storage, "quarantine.json"), vec!["x"]);
+    }
+
+    #[test]
+    fn memory_round_trip() {
+        round_trip(&MemoryStorage::default());
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        round_trip(&FileStorage::new(dir.path().to_path_buf()));
+    }
+
+    #[test]
+    fn sqlite_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        round_trip(&SqliteStorage::open(dir.path().to_path_buf()).unwrap());
+    }
+
+    #[test]
+    fn sqlite_takes_over_file_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = FileStorage::new(dir.path().to_path_buf());
+        files.write("journal.jsonl", b"old\n").unwrap();
+
+        let db = SqliteStorage::open(dir.path().to_path_buf()).unwrap();
+        db.append("journal.jsonl", b"new\n").unwrap();
+        assert_eq!(db.read("journal.jsonl").unwrap().data, b"old\nnew\n");
+        assert!(files.read("journal.jsonl").is_none());
+    }
+}
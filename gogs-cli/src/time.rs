@@ -0,0 +1,142 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, Locale, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Default)]
+pub struct TimeDisplay {
+    /// Show absolute timestamps instead of "3h ago"
+    pub absolute: bool,
+    /// Locale for absolute timestamps (None falls back to ISO-style output)
+    pub locale: Option<Locale>,
+    /// Display timezone (None uses the system local timezone)
+    pub tz: Option<Tz>,
+}
+
+static DISPLAY: OnceLock<TimeDisplay> = OnceLock::new();
+
+/// Set how timestamps are rendered in human output. Only the first call takes effect.
+pub fn configure(display: TimeDisplay) {
+    let _ = DISPLAY.set(display);
+}
+
+fn display() -> &'static TimeDisplay {
+    DISPLAY.get_or_init(TimeDisplay::default)
+}
+
+/// Detect the time locale from the environment (LC_ALL, LC_TIME, LANG).
+pub fn locale_from_env() -> Option<Locale> {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| parse_locale(&value))
+}
+
+fn parse_locale(value: &str) -> Option<Locale> {
+    // Strip encoding and modifier: "de_DE.UTF-8@euro" -> "de_DE"
+    let name = value.split(['.', '@']).next()?;
+    if name == "C" || name == "POSIX" {
+        return None;
+    }
+    Locale::try_from(name).ok()
+}
+
+/// Parse an IANA timezone name such as "Europe/London".
+pub fn parse_tz(name: &str) -> Result<Tz> {
+    name.parse::<Tz>()
+        .map_err(|_| anyhow::anyhow!("Unknown timezone '{}'. Use an IANA name like 'Europe/London'", name))
+}
+
+/// Parse a date argument: RFC 3339, `YYYY-MM-DD` (midnight UTC), or a relative
+/// age such as `30m`, `12h`, `7d` or `2w` counted back from now.
+pub fn parse_date_arg(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc());
+    }
+
+    let invalid = || format!("invalid date '{}': expected YYYY-MM-DD, RFC 3339 or an age like 7d", value);
+    let (split, unit) = value.char_indices().last().ok_or_else(invalid)?;
+    let amount: i64 = value[..split].parse().map_err(|_| invalid())?;
+
+    let age = match unit {
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => return Err(format!("invalid age unit in '{}': use m, h, d or w", value)),
+    };
+
+    age.and_then(|age| Utc::now().checked_sub_signed(age))
+        .ok_or_else(|| format!("age '{}' is too far back", value))
+}
+
+/// Render an API timestamp for human output.
+pub fn format_timestamp(time: &DateTime<Utc>) -> String {
+    let display = display();
+
+    if !display.absolute {
+        return format_relative(time, Utc::now());
+    }
+
+    match display.tz {
+        Some(tz) => format_absolute(&time.with_timezone(&tz), display.locale),
+        None => format_absolute(&time.with_timezone(&Local), display.locale),
+    }
+}
+
+fn format_absolute<Z: TimeZone>(time: &DateTime<Z>, locale: Option<Locale>) -> String
+where
+    Z::Offset: Display,
+{
+    match locale {
+        Some(locale) => time.format_localized("%c", locale).to_string(),
+        None => time.format("%Y-%m-%d %H:%M %Z").to_string(),
+    }
+}
+
+fn format_relative(time: &DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - *time).num_seconds();
+    let future = seconds < 0;
+    let seconds = seconds.abs();
+
+    let amount = match seconds {
+        0..=59 => return "just now".to_string(),
+        60..=3_599 => format!("{}m", seconds / 60),
+        3_600..=86_399 => format!("{}h", seconds / 3_600),
+        86_400..=2_591_999 => format!("{}d", seconds / 86_400),
+        2_592_000..=31_535_999 => format!("{}mo", seconds / 2_592_000),
+        _ => format!("{}y", seconds / 31_536_000),
+    };
+
+    if future {
+        format!("in {}", amount)
+    } else {
+        format!("{} ago", amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ages_and_dates() {
+        let week = parse_date_arg("1w").unwrap();
+        let age = Utc::now() - week;
+        assert!(age >= Duration::days(7) && age < Duration::days(7) + Duration::minutes(1));
+        assert_eq!(parse_date_arg("2024-01-02").unwrap().to_rfc3339(), "2024-01-02T00:00:00+00:00");
+    }
+
+    #[test]
+    fn rejects_bad_input_without_panicking() {
+        for value in ["", "é", "5é", "d", "7y", "99999999999999d", "9223372036854775807w"] {
+            assert!(parse_date_arg(value).is_err(), "{} should be rejected", value);
+        }
+    }
+}
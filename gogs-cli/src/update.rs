@@ -0,0 +1,257 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::api::Release;
+
+/// The version of this binary.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Where `self-update` downloads from unless configured otherwise. Placeholders
+/// are filled in by `asset_url`.
+pub const DEFAULT_ASSET_URL: &str = "{server}/{repo}/releases/download/{tag}/{asset}";
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct UpdateConfig {
+    /// Repository on the configured server that publishes gog releases (owner/repo)
+    pub repo: Option<String>,
+
+    /// Download URL template with {server}, {repo}, {tag} and {asset} placeholders.
+    /// The checksum is fetched from the same URL with ".sha256" appended.
+    pub asset_url: Option<String>,
+
+    /// Channel used when `--channel` isn't given (default stable)
+    pub channel: Option<Channel>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    /// Full releases only
+    #[default]
+    Stable,
+    /// Pre-releases as well
+    Beta,
+}
+
+impl Channel {
+    pub fn name(self) -> &'static str {
+        match self {
+            Channel::Stable => "stable",
+            Channel::Beta => "beta",
+        }
+    }
+}
+
+/// The newest non-draft release on `channel`, by version number.
+pub fn pick_release(releases: &[Release], channel: Channel) -> Option<&Release> {
+    releases
+        .iter()
+        .filter(|r| !r.draft)
+        .filter(|r| channel == Channel::Beta || !r.prerelease)
+        .filter(|r| parse_version(&r.tag_name).is_some())
+        .max_by(|a, b| compare_versions(&a.tag_name, &b.tag_name))
+}
+
+/// Compare versions such as "v1.2.0" and "1.10.0-rc1". A pre-release sorts
+/// before the same version without a suffix, and pre-releases by their label,
+/// then by its number, so rc2 comes before rc10.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (parse_version(a), parse_version(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// How far along a version is; pre-releases come first.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Stage {
+    /// e.g. "rc" and 2 for "rc2", or "beta" and None for "beta"
+    Pre { label: String, number: Option<u64> },
+    Full,
+}
+
+/// Numeric components, then the stage.
+fn parse_version(tag: &str) -> Option<(Vec<u64>, Stage)> {
+    let tag = tag.trim().trim_start_matches('v');
+    let (numbers, suffix) = match tag.split_once('-') {
+        Some((numbers, suffix)) => (numbers, Some(suffix)),
+        None => (tag, None),
+    };
+
+    let mut parts: Vec<u64> = numbers
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    // So that 1.2 == 1.2.0
+    while parts.len() > 1 && parts.last() == Some(&0) {
+        parts.pop();
+    }
+    let stage = match suffix {
+        Some(suffix) => {
+            let label = suffix.trim_end_matches(|c: char| c.is_ascii_digit());
+            Stage::Pre {
+                number: suffix[label.len()..].parse().ok(),
+                label: label.trim_end_matches(['.', '-']).to_ascii_lowercase(),
+            }
+        }
+        None => Stage::Full,
+    };
+    Some((parts, stage))
+}
+
+/// Release asset name for the platform this binary was built for, e.g.
+/// "gog-linux-x86_64" or "gog-windows-x86_64.exe".
+pub fn asset_name() -> String {
+    format!(
+        "gog-{}-{}{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+pub fn asset_url(template: &str, server: &str, repo: &str, tag: &str, asset: &str) -> String {
+    template
+        .replace("{server}", server.trim_end_matches('/'))
+        .replace("{repo}", repo)
+        .replace("{tag}", tag)
+        .replace("{asset}", asset)
+}
+
+/// Find the expected digest in a checksum file: either a bare digest, or
+/// `sha256sum` output listing several files.
+pub fn parse_checksum(text: &str, asset: &str) -> Option<String> {
+    let lines: Vec<Vec<&str>> = text
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|fields| !fields.is_empty())
+        .collect();
+
+    let digest = match lines.as_slice() {
+        [only] if only.len() == 1 => only[0],
+        _ => lines.iter().find_map(|fields| match fields.as_slice() {
+            // sha256sum marks binary mode with a leading '*'
+            [digest, name] if name.trim_start_matches('*') == asset => Some(*digest),
+            [digest, _] if lines.len() == 1 => Some(*digest),
+            _ => None,
+        })?,
+    };
+
+    let digest = digest.to_ascii_lowercase();
+    (digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit())).then_some(digest)
+}
+
+/// Replace the running executable with `binary`. The new file is written next
+/// to the old one and renamed over it, so an interrupted update leaves the old
+/// binary in place.
+pub fn install(binary: &[u8]) -> Result<PathBuf> {
+    let current = std::env::current_exe()
+        .and_then(fs::canonicalize)
+        .context("Failed to locate the running gog binary")?;
+    let dir = current
+        .parent()
+        .context("The gog binary has no parent directory")?;
+    let staged = dir.join(format!(".gog-update-{}", std::process::id()));
+
+    write_executable(&staged, binary)
+        .and_then(|()| swap(&staged, &current))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&staged);
+        })
+        .with_context(|| format!("Failed to replace {}", current.display()))?;
+    Ok(current)
+}
+
+fn write_executable(path: &Path, data: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut file = fs::File::create(path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn swap(staged: &Path, current: &Path) -> Result<()> {
+    fs::rename(staged, current)?;
+    Ok(())
+}
+
+/// Windows won't replace a running executable, but will rename it.
+#[cfg(windows)]
+fn swap(staged: &Path, current: &Path) -> Result<()> {
+    let old = current.with_extension("old.exe");
+    let _ = fs::remove_file(&old);
+    fs::rename(current, &old)?;
+    if let Err(e) = fs::rename(staged, current) {
+        let _ = fs::rename(&old, current);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool) -> Release {
+        Release {
+            id: 0,
+            tag_name: tag.to_string(),
+            name: String::new(),
+            body: None,
+            draft: false,
+            prerelease,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn versions_order() {
+        let ascending = ["1.2.0-alpha", "1.2.0-beta2", "1.2.0-rc1", "v1.2.0-rc2", "1.2.0-rc10", "1.2", "1.10.0"];
+        for pair in ascending.windows(2) {
+            assert_eq!(compare_versions(pair[0], pair[1]), Ordering::Less, "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(compare_versions("v1.2.0", "1.2"), Ordering::Equal);
+    }
+
+    #[test]
+    fn picks_newest_release_on_channel() {
+        // Newest first, as the server lists them
+        let mut draft = release("v2.0.0", false);
+        draft.draft = true;
+        let releases = [
+            draft,
+            release("v1.3.0-rc2", true),
+            release("v1.3.0-rc1", true),
+            release("v1.2.0", false),
+            release("nightly", true),
+        ];
+
+        assert_eq!(pick_release(&releases, Channel::Stable).unwrap().tag_name, "v1.2.0");
+        assert_eq!(pick_release(&releases, Channel::Beta).unwrap().tag_name, "v1.3.0-rc2");
+        assert!(pick_release(&releases[..1], Channel::Beta).is_none());
+    }
+
+    #[test]
+    fn checksum_formats() {
+        let digest = "ab".repeat(32);
+        assert_eq!(parse_checksum(&format!("{}\n", digest.to_uppercase()), "gog"), Some(digest.clone()));
+        assert_eq!(parse_checksum(&format!("{}  gog\n", digest), "other"), Some(digest.clone()));
+
+        let listing = format!("{}  gog-linux\n{} *gog-macos\n", "cd".repeat(32), digest);
+        assert_eq!(parse_checksum(&listing, "gog-macos"), Some(digest));
+        assert_eq!(parse_checksum(&listing, "gog-windows.exe"), None);
+        assert_eq!(parse_checksum("not-a-digest", "gog"), None);
+    }
+}
@@ -0,0 +1,138 @@
+use serde::Serialize;
+
+use crate::api::GogsClient;
+use crate::config::Config;
+use crate::update::CURRENT_VERSION;
+
+/// How this binary was built.
+#[derive(Debug, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    pub target: &'static str,
+    pub profile: &'static str,
+    pub rustc: &'static str,
+    /// Cargo features enabled at build time
+    pub features: Vec<&'static str>,
+    pub tls: &'static str,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            git_sha: env!("GOG_GIT_SHA"),
+            target: env!("GOG_TARGET"),
+            profile: env!("GOG_PROFILE"),
+            rustc: env!("GOG_RUSTC_VERSION"),
+            features: env!("GOG_FEATURES").split(',').filter(|f| !f.is_empty()).collect(),
+            tls: tls_backend(),
+        }
+    }
+}
+
+/// reqwest's default TLS goes through the platform library.
+fn tls_backend() -> &'static str {
+    if cfg!(windows) {
+        "native-tls (Schannel)"
+    } else if cfg!(target_os = "macos") {
+        "native-tls (Security.framework)"
+    } else {
+        "native-tls (OpenSSL)"
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase", tag = "status", content = "reason")]
+pub enum Support {
+    Supported,
+    Unsupported,
+    /// Couldn't tell, and why
+    Unknown(String),
+}
+
+/// Whether the server backs one gog feature.
+#[derive(Debug, Serialize)]
+pub struct Feature {
+    pub name: &'static str,
+    pub endpoint: String,
+    #[serde(flatten)]
+    pub support: Support,
+}
+
+/// What the configured server is and which gog features it can serve.
+#[derive(Debug, Serialize)]
+pub struct ServerReport {
+    pub url: String,
+    pub version: Option<String>,
+    pub features: Vec<Feature>,
+}
+
+/// Probe the server's endpoints. Repository features are checked against the
+/// default repository, and skipped without one.
+pub async fn server_report(client: &GogsClient, config: &Config) -> ServerReport {
+    let version = client.get_server_version().await.ok().flatten();
+    let repo = config.get_repo(None).ok().map(|(owner, repo)| format!("{}/{}", owner, repo));
+    let release_repo = config
+        .update
+        .as_ref()
+        .and_then(|u| u.repo.clone())
+        .or_else(|| repo.clone());
+
+    let mut features = vec![probe(client, "authenticated user", "/user".to_string()).await];
+
+    match &repo {
+        Some(repo) => {
+            let endpoint = format!("/repos/{}/issues?limit=1", repo);
+            let (issues, counts) = match client.probe(&endpoint).await {
+                Ok(Some(resp)) => {
+                    let counts = if resp.headers().contains_key("X-Total-Count") {
+                        Support::Supported
+                    } else {
+                        Support::Unknown("no X-Total-Count header; counts page through issues".to_string())
+                    };
+                    (Support::Supported, counts)
+                }
+                Ok(None) => (Support::Unsupported, Support::Unsupported),
+                Err(e) => (Support::Unknown(format!("{:#}", e)), Support::Unknown(format!("{:#}", e))),
+            };
+            features.push(Feature { name: "issues", endpoint: endpoint.clone(), support: issues });
+            features.push(Feature { name: "fast issue counts", endpoint, support: counts });
+            features.push(probe(client, "labels", format!("/repos/{}/labels", repo)).await);
+            features.push(probe(client, "milestones", format!("/repos/{}/milestones", repo)).await);
+        }
+        None => {
+            for name in ["issues", "fast issue counts", "labels", "milestones"] {
+                features.push(Feature {
+                    name,
+                    endpoint: "/repos/{owner}/{repo}/...".to_string(),
+                    support: Support::Unknown("no default repository to check against".to_string()),
+                });
+            }
+        }
+    }
+
+    features.push(match release_repo {
+        Some(repo) => probe(client, "releases (self-update)", format!("/repos/{}/releases", repo)).await,
+        None => Feature {
+            name: "releases (self-update)",
+            endpoint: "/repos/{owner}/{repo}/releases".to_string(),
+            support: Support::Unknown("no repository to check against".to_string()),
+        },
+    });
+
+    ServerReport {
+        url: config.server.url.clone(),
+        version,
+        features,
+    }
+}
+
+async fn probe(client: &GogsClient, name: &'static str, endpoint: String) -> Feature {
+    let support = match client.probe(&endpoint).await {
+        Ok(Some(_)) => Support::Supported,
+        Ok(None) => Support::Unsupported,
+        Err(e) => Support::Unknown(format!("{:#}", e)),
+    };
+    Feature { name, endpoint, support }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::api::{Comment, Issue};
+use crate::signature;
+
+/// What `gog issue vote` posts after the signature.
+pub const VOTE: &str = "+1";
+
+/// Comment texts counted as a vote, besides `VOTE`.
+const VOTE_ALIASES: &[&str] = &["👍", ":+1:"];
+
+/// Days for an issue's recency bonus to halve unless configured otherwise.
+pub const DEFAULT_HALF_LIFE_DAYS: f64 = 14.0;
+
+/// An open issue with the numbers behind its place in `gog report top`.
+#[derive(Debug, Serialize)]
+pub struct RankedIssue {
+    pub repo: String,
+    #[serde(flatten)]
+    pub issue: Issue,
+    pub votes: usize,
+    /// 1.0 for an issue updated just now, halving every half-life
+    pub recency: f64,
+    /// votes + recency
+    pub score: f64,
+}
+
+/// Split a leading "[Signature]" off a comment body.
+fn split_signature(body: &str) -> (&str, &str) {
+    let body = body.trim_start();
+    if body.starts_with('[') {
+        if let Some(end) = body.find(']') {
+            return (&body[..=end], body[end + 1..].trim_start());
+        }
+    }
+    ("", body)
+}
+
+/// Whether `comment` is a vote: its first line after the signature is "+1"
+/// (or a thumbs-up), optionally followed by a reason.
+pub fn is_vote(comment: &Comment) -> bool {
+    let (_, text) = split_signature(&comment.body);
+    let first = text.lines().next().unwrap_or_default().trim();
+    first == VOTE || VOTE_ALIASES.contains(&first)
+}
+
+/// Who cast a vote. Several agents often share one account and tell
+/// themselves apart by signature, so each signature counts once per user.
+fn voter(comment: &Comment) -> (String, String) {
+    let (signature, _) = split_signature(&comment.body);
+    (comment.user.username.to_lowercase(), signature.to_string())
+}
+
+/// Votes on an issue, counting each voter once.
+pub fn count(comments: &[Comment]) -> usize {
+    comments
+        .iter()
+        .filter(|c| is_vote(c))
+        .map(voter)
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Whether `username` signing as `pattern` (see `Profile::signature_pattern`)
+/// has already voted.
+pub fn has_voted(comments: &[Comment], username: &str, pattern: &str) -> bool {
+    let pattern = split_signature(pattern).0;
+    comments
+        .iter()
+        .filter(|c| is_vote(c))
+        .map(voter)
+        .any(|(user, sig)| user == username.to_lowercase() && signature::matches(pattern, &sig))
+}
+
+/// Rank issues by votes plus a recency bonus between 0 and 1, so votes come
+/// first and, among equal votes, recently active issues.
+pub fn rank(
+    issues: Vec<(String, Issue, usize)>,
+    now: DateTime<Utc>,
+    half_life_days: f64,
+) -> Vec<RankedIssue> {
+    let mut ranked: Vec<RankedIssue> = issues
+        .into_iter()
+        .map(|(repo, issue, votes)| {
+            let age_days = (now - issue.updated_at).num_seconds().max(0) as f64 / 86_400.0;
+            let recency = 0.5f64.powf(age_days / half_life_days.max(f64::MIN_POSITIVE));
+            // Rounded so the JSON stays readable and ties stay ties
+            let recency = (recency * 1000.0).round() / 1000.0;
+            RankedIssue {
+                repo,
+                issue,
+                votes,
+                recency,
+                score: votes as f64 + recency,
+            }
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.repo.cmp(&b.repo))
+            .then_with(|| a.issue.number.cmp(&b.issue.number))
+    });
+    ranked
+}